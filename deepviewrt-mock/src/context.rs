@@ -0,0 +1,233 @@
+use crate::{
+    engine,
+    model::{register, LayerType, Model},
+    str_arg,
+    tensor::{into_ptr, Tensor},
+};
+use deepviewrt_sys::{NNContext, NNEngine, NNError, NNModel, NNTensor, NNTensorType};
+use std::{
+    ffi::{c_char, c_void},
+    ptr,
+    time::Instant,
+};
+
+/// A context holds the loaded model and one tensor per layer.
+struct Context {
+    engine: *mut NNEngine,
+    /// The loaded model and a copy of its memory, which `nn_context_model`
+    /// returns.
+    model: Option<(&'static Model, Vec<u8>)>,
+    tensors: Vec<*mut NNTensor>,
+    cache: *mut NNTensor,
+    mempool: *mut NNTensor,
+}
+
+impl Context {
+    fn unload(&mut self) {
+        for tensor in self.tensors.drain(..) {
+            drop(unsafe { Box::from_raw(tensor as *mut Tensor) });
+        }
+        self.model = None;
+    }
+
+    unsafe fn step(&mut self, index: usize) -> NNError {
+        let Some((model, _)) = self.model else {
+            return NNError::NN_ERROR_MODEL_MISSING;
+        };
+        let Some(layer) = model.layers.get(index) else {
+            return NNError::NN_ERROR_INVALID_LAYER;
+        };
+        let layer_type = layer.layer_type.name().to_str().unwrap_or_default();
+        if engine::is_kernel_missing(self.engine, layer_type) {
+            return NNError::NN_ERROR_KERNEL_MISSING;
+        }
+        let start = Instant::now();
+        let inputs: Vec<Vec<f32>> = layer
+            .inputs
+            .iter()
+            .map(|i| (*(self.tensors[*i] as *const Tensor)).values())
+            .collect();
+        let output = &mut *(self.tensors[index] as *mut Tensor);
+        if let Some(values) = layer.layer_type.evaluate(&inputs, output.shape()) {
+            output.set_values(&values);
+        }
+        output.time = start.elapsed().as_nanos() as i64;
+        NNError::NN_SUCCESS
+    }
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        self.unload();
+        for tensor in [self.cache, self.mempool] {
+            if !tensor.is_null() {
+                drop(unsafe { Box::from_raw(tensor as *mut Tensor) });
+            }
+        }
+    }
+}
+
+unsafe fn as_context<'a>(context: *mut NNContext) -> Option<&'a mut Context> {
+    (context as *mut Context).as_mut()
+}
+
+/// A `U8` tensor of `size` bytes, null when the size is zero.
+fn buffer(engine: *mut NNEngine, size: usize) -> *mut NNTensor {
+    if size == 0 {
+        return ptr::null_mut();
+    }
+    let mut tensor = Tensor::new(engine);
+    tensor.alloc(NNTensorType::U8, &[size as i32]);
+    into_ptr(Box::new(tensor))
+}
+
+#[no_mangle]
+pub extern "C" fn nn_context_sizeof() -> usize {
+    std::mem::size_of::<Context>()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_context_init(
+    engine: *mut NNEngine,
+    memory_size: usize,
+    memory: *mut c_void,
+    cache_size: usize,
+    cache: *mut c_void,
+) -> *mut NNContext {
+    if !memory.is_null() || !cache.is_null() {
+        return ptr::null_mut();
+    }
+    let context = Context {
+        engine,
+        model: None,
+        tensors: Vec::new(),
+        cache: buffer(engine, cache_size),
+        mempool: buffer(engine, memory_size),
+    };
+    Box::into_raw(Box::new(context)) as *mut NNContext
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_context_release(context: *mut NNContext) {
+    if !context.is_null() {
+        drop(Box::from_raw(context as *mut Context));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_context_cache(context: *mut NNContext) -> *mut NNTensor {
+    as_context(context).map_or(ptr::null_mut(), |c| c.cache)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_context_mempool(context: *mut NNContext) -> *mut NNTensor {
+    as_context(context).map_or(ptr::null_mut(), |c| c.mempool)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_context_engine(context: *mut NNContext) -> *mut NNEngine {
+    as_context(context).map_or(ptr::null_mut(), |c| c.engine)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_context_model(context: *mut NNContext) -> *const NNModel {
+    match as_context(context).and_then(|c| c.model.as_ref()) {
+        Some((_, data)) => data.as_ptr() as *const NNModel,
+        None => ptr::null(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_context_model_load(
+    context: *mut NNContext,
+    memory_size: usize,
+    memory: *const c_void,
+) -> NNError {
+    let Some(context) = as_context(context) else {
+        return NNError::NN_ERROR_INVALID_HANDLE;
+    };
+    if memory.is_null() {
+        return NNError::NN_ERROR_MODEL_INVALID;
+    }
+    let data = std::slice::from_raw_parts(memory as *const u8, memory_size);
+    let Ok(model) = register(data) else {
+        return NNError::NN_ERROR_MODEL_INVALID;
+    };
+    context.unload();
+    for layer in &model.layers {
+        let mut tensor = Tensor::new(context.engine);
+        let err = tensor.alloc(layer.datatype, &layer.shape);
+        if err != NNError::NN_SUCCESS {
+            context.unload();
+            return err;
+        }
+        tensor.scales = layer.scales.clone();
+        tensor.zeros = layer.zeros.clone();
+        tensor.axis = layer.axis;
+        if let (LayerType::Constant, Some(data)) = (layer.layer_type, layer.data()) {
+            tensor.set_values(data);
+        }
+        context.tensors.push(into_ptr(Box::new(tensor)));
+    }
+    context.model = Some((model, data.to_vec()));
+    NNError::NN_SUCCESS
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_context_model_unload(context: *mut NNContext) {
+    if let Some(context) = as_context(context) {
+        context.unload();
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_context_tensor(
+    context: *mut NNContext,
+    name: *const c_char,
+) -> *mut NNTensor {
+    let (Some(context), Some(name)) = (as_context(context), str_arg(name)) else {
+        return ptr::null_mut();
+    };
+    let Some((model, _)) = context.model else {
+        return ptr::null_mut();
+    };
+    match model.layers.iter().position(|l| l.name() == name) {
+        Some(index) => context.tensors[index],
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_context_tensor_index(
+    context: *mut NNContext,
+    index: usize,
+) -> *mut NNTensor {
+    as_context(context)
+        .and_then(|c| c.tensors.get(index).copied())
+        .unwrap_or(ptr::null_mut())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_context_run(context: *mut NNContext) -> NNError {
+    let Some(context) = as_context(context) else {
+        return NNError::NN_ERROR_INVALID_HANDLE;
+    };
+    if context.model.is_none() {
+        return NNError::NN_ERROR_MODEL_MISSING;
+    }
+    for index in 0..context.tensors.len() {
+        let err = context.step(index);
+        if err != NNError::NN_SUCCESS {
+            return err;
+        }
+    }
+    NNError::NN_SUCCESS
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_context_step(context: *mut NNContext, index: usize) -> NNError {
+    match as_context(context) {
+        Some(context) => context.step(index),
+        None => NNError::NN_ERROR_INVALID_HANDLE,
+    }
+}
@@ -0,0 +1,100 @@
+use crate::str_arg;
+use deepviewrt_sys::{NNEngine, NNError};
+use std::{
+    ffi::{c_char, c_void, CString},
+    path::Path,
+    ptr,
+};
+
+/// An engine whose plugin is any existing file, named after the file with
+/// the `deepview-rt-` prefix and extension removed.  The file lists the layer
+/// types the engine has no kernel for, one per line, running those layers
+/// fails with `NN_ERROR_KERNEL_MISSING`.
+struct Engine {
+    name: Option<CString>,
+    version: CString,
+    missing: Vec<String>,
+}
+
+unsafe fn as_engine<'a>(engine: *mut NNEngine) -> Option<&'a mut Engine> {
+    (engine as *mut Engine).as_mut()
+}
+
+/// Whether the loaded plugin of `engine` lacks a kernel for `layer_type`.
+pub(crate) unsafe fn is_kernel_missing(engine: *mut NNEngine, layer_type: &str) -> bool {
+    as_engine(engine).is_some_and(|e| e.name.is_some() && e.missing.iter().any(|m| m == layer_type))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_engine_init(memory: *mut c_void) -> *mut NNEngine {
+    if !memory.is_null() {
+        return ptr::null_mut();
+    }
+    let engine = Engine {
+        name: None,
+        version: crate::VERSION.to_owned(),
+        missing: Vec::new(),
+    };
+    Box::into_raw(Box::new(engine)) as *mut NNEngine
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_engine_release(engine: *mut NNEngine) {
+    if !engine.is_null() {
+        drop(Box::from_raw(engine as *mut Engine));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_engine_load(engine: *mut NNEngine, plugin: *const c_char) -> NNError {
+    let Some(engine) = as_engine(engine) else {
+        return NNError::NN_ERROR_INVALID_HANDLE;
+    };
+    let Some(path) = str_arg(plugin).map(Path::new).filter(|p| p.is_file()) else {
+        return NNError::NN_ERROR_INVALID_ENGINE;
+    };
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = stem
+        .trim_start_matches("lib")
+        .trim_start_matches("deepview-rt-");
+    engine.name = CString::new(name).ok();
+    engine.missing = std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+    NNError::NN_SUCCESS
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_engine_unload(engine: *mut NNEngine) {
+    if let Some(engine) = as_engine(engine) {
+        engine.name = None;
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_engine_name(engine: *mut NNEngine) -> *const c_char {
+    match as_engine(engine).and_then(|e| e.name.as_ref()) {
+        Some(name) => name.as_ptr(),
+        None => ptr::null(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_engine_version(engine: *mut NNEngine) -> *const c_char {
+    match as_engine(engine) {
+        Some(engine) if engine.name.is_some() => engine.version.as_ptr(),
+        _ => ptr::null(),
+    }
+}
+
+/// The engine itself once a plugin is loaded.
+#[no_mangle]
+pub unsafe extern "C" fn nn_engine_native_handle(engine: *mut NNEngine) -> *mut c_void {
+    match as_engine(engine) {
+        Some(inner) if inner.name.is_some() => engine as *mut c_void,
+        _ => ptr::null_mut(),
+    }
+}
@@ -0,0 +1,82 @@
+//! A Rust implementation of the DeepViewRT `nn_*` functions used by the
+//! `deepviewrt` crate, so it can be tested without the runtime library.
+//!
+//! Linking this crate provides the symbols otherwise resolved from
+//! `libdeepview-rt`.  Enable the `mock` feature of `deepviewrt`, or of
+//! `deepviewrt-sys` when depending on this crate directly, so the real
+//! library is not linked.  Tensors live on the heap,
+//! quantized tensors convert through their scales and zero-points, and
+//! models use the text format described in [`model`], which
+//! [`ModelBuilder`] writes.
+//!
+//! Objects are always allocated by the mock, `nn_*_init` functions given
+//! caller memory return `NULL`.
+
+// The functions below implement a C API, their safety requirements are those
+// documented in deepview_rt.h.
+#![allow(clippy::missing_safety_doc)]
+
+mod context;
+mod engine;
+pub mod model;
+mod tensor;
+
+pub use model::{Layer, ModelBuilder};
+
+use deepviewrt_sys::{NNError, NNOptions};
+use std::ffi::{c_char, CStr};
+
+/// Version reported by `nn_version`.
+pub const VERSION: &CStr = c"2.4.32-mock";
+
+/// Borrows a C string argument, `None` when null or not UTF-8.
+unsafe fn str_arg<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+    CStr::from_ptr(ptr).to_str().ok()
+}
+
+#[no_mangle]
+pub extern "C" fn nn_version() -> *const c_char {
+    VERSION.as_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_init(_options: *const NNOptions) -> NNError {
+    NNError::NN_SUCCESS
+}
+
+#[no_mangle]
+pub extern "C" fn nn_strerror(error: NNError) -> *const c_char {
+    let message = match error {
+        NNError::NN_SUCCESS => c"success",
+        NNError::NN_ERROR_INTERNAL => c"internal error",
+        NNError::NN_ERROR_INVALID_HANDLE => c"invalid handle",
+        NNError::NN_ERROR_OUT_OF_MEMORY => c"out of memory",
+        NNError::NN_ERROR_OUT_OF_RESOURCES => c"out of resources",
+        NNError::NN_ERROR_NOT_IMPLEMENTED => c"not implemented",
+        NNError::NN_ERROR_INVALID_PARAMETER => c"invalid parameter",
+        NNError::NN_ERROR_TYPE_MISMATCH => c"type mismatch",
+        NNError::NN_ERROR_SHAPE_MISMATCH => c"shape mismatch",
+        NNError::NN_ERROR_INVALID_SHAPE => c"invalid shape",
+        NNError::NN_ERROR_INVALID_ORDER => c"invalid order",
+        NNError::NN_ERROR_INVALID_AXIS => c"invalid axis",
+        NNError::NN_ERROR_MISSING_RESOURCE => c"missing resource",
+        NNError::NN_ERROR_INVALID_ENGINE => c"invalid engine",
+        NNError::NN_ERROR_TENSOR_NO_DATA => c"tensor has no data",
+        NNError::NN_ERROR_KERNEL_MISSING => c"kernel missing",
+        NNError::NN_ERROR_TENSOR_TYPE_UNSUPPORTED => c"tensor type unsupported",
+        NNError::NN_ERROR_TOO_MANY_INPUTS => c"too many inputs",
+        NNError::NN_ERROR_SYSTEM_ERROR => c"system error",
+        NNError::NN_ERROR_INVALID_LAYER => c"invalid layer",
+        NNError::NN_ERROR_MODEL_INVALID => c"model invalid",
+        NNError::NN_ERROR_MODEL_MISSING => c"model missing",
+        NNError::NN_ERROR_STRING_TOO_LARGE => c"string too large",
+        NNError::NN_ERROR_INVALID_QUANT => c"invalid quantization",
+        NNError::NN_ERROR_MODEL_GRAPH_FAILED => c"model graph failed",
+        NNError::NN_ERROR_GRAPH_VERIFY_FAILED => c"graph verify failed",
+        _ => return std::ptr::null(),
+    };
+    message.as_ptr()
+}
@@ -0,0 +1,1018 @@
+//! The mock model format.
+//!
+//! A model is UTF-8 text, one declaration per line, starting with the line
+//! `deepviewrt-mock 1 <size>` and ending with the line `end`.  The size is the
+//! length of the whole model in bytes as ten decimal digits.  Empty lines and
+//! lines starting with `#` are ignored.
+//!
+//! ```text
+//! deepviewrt-mock 1 0000000331
+//! name classifier
+//! uuid 6f1c2a9e-0000-4000-8000-000000000000
+//! serial 3
+//! label cat
+//! label dog
+//! layer input input f32 1,2
+//! layer weights constant f32 1,2 data=0.5,2
+//! layer scaled mul f32 1,2 inputs=input,weights
+//! layer output softmax f32 1,2 inputs=scaled
+//! output output
+//! resource notes text/plain 6869 greeting
+//! end
+//! ```
+//!
+//! A layer is `layer <name> <type> <datatype> <shape>` followed by optional
+//! `key=value` attributes:
+//!
+//! - `inputs=a,b` the layers consumed, by name.
+//! - `data=1,2,3` the values of a `constant` layer.
+//! - `scales=`, `zeros=` and `axis=` the quantization parameters.
+//! - `param.<key>=<type>:<values>` a layer parameter, where type is one of
+//!   `f32`, `i32`, `i16`, `i8`, `raw` or `str`.
+//!
+//! Layer types are `input`, `constant`, `add`, `sub` and `mul` (the second
+//! input repeats to the output volume), `relu`, `softmax` (over the last
+//! dimension) and `copy`, `quantize` and `dequantize` which convert to the
+//! layer datatype.  Outputs default to the layers no other layer consumes.
+//! Resources are `resource <name> <mime> <hex data> [meta]`.
+//!
+//! As with the runtime, an `NNModel` pointer is the model memory itself, its
+//! size is read from the header line.  Models are parsed once and kept for the
+//! life of the process.
+
+use crate::str_arg;
+use deepviewrt_sys::{NNModel, NNModelParameter, NNModelResource, NNTensorType};
+use std::{
+    ffi::{c_char, c_int, CStr, CString},
+    fmt::Write,
+    ptr,
+    sync::Mutex,
+};
+
+const HEADER: &str = "deepviewrt-mock 1";
+/// Digits of the model size following the header.
+const SIZE_DIGITS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LayerType {
+    Input,
+    Constant,
+    Add,
+    Sub,
+    Mul,
+    Relu,
+    Softmax,
+    Copy,
+    Quantize,
+    Dequantize,
+}
+
+const LAYER_TYPES: &[(LayerType, &CStr)] = &[
+    (LayerType::Input, c"input"),
+    (LayerType::Constant, c"constant"),
+    (LayerType::Add, c"add"),
+    (LayerType::Sub, c"sub"),
+    (LayerType::Mul, c"mul"),
+    (LayerType::Relu, c"relu"),
+    (LayerType::Softmax, c"softmax"),
+    (LayerType::Copy, c"copy"),
+    (LayerType::Quantize, c"quantize"),
+    (LayerType::Dequantize, c"dequantize"),
+];
+
+const DATATYPES: &[(NNTensorType, &CStr)] = &[
+    (NNTensorType::RAW, c"raw"),
+    (NNTensorType::STR, c"str"),
+    (NNTensorType::I8, c"i8"),
+    (NNTensorType::U8, c"u8"),
+    (NNTensorType::I16, c"i16"),
+    (NNTensorType::U16, c"u16"),
+    (NNTensorType::I32, c"i32"),
+    (NNTensorType::U32, c"u32"),
+    (NNTensorType::I64, c"i64"),
+    (NNTensorType::U64, c"u64"),
+    (NNTensorType::F16, c"f16"),
+    (NNTensorType::F32, c"f32"),
+    (NNTensorType::F64, c"f64"),
+];
+
+impl LayerType {
+    fn parse(name: &str) -> Option<Self> {
+        LAYER_TYPES
+            .iter()
+            .find(|(_, n)| n.to_bytes() == name.as_bytes())
+            .map(|(t, _)| *t)
+    }
+
+    fn id(self) -> usize {
+        LAYER_TYPES.iter().position(|(t, _)| *t == self).unwrap()
+    }
+
+    pub(crate) fn name(self) -> &'static CStr {
+        LAYER_TYPES[self.id()].1
+    }
+
+    fn input_count(self) -> usize {
+        match self {
+            LayerType::Input | LayerType::Constant => 0,
+            LayerType::Add | LayerType::Sub | LayerType::Mul => 2,
+            _ => 1,
+        }
+    }
+
+    /// Computes the output values from the input values, `None` for layers
+    /// which only hold a tensor.
+    pub(crate) fn evaluate(self, inputs: &[Vec<f32>], shape: &[i32]) -> Option<Vec<f32>> {
+        let volume: usize = shape.iter().map(|d| *d as usize).product();
+        let input = |n: usize, i: usize| {
+            let values: &[f32] = &inputs[n];
+            values.get(i % values.len().max(1)).copied().unwrap_or(0.0)
+        };
+        let binary =
+            |op: fn(f32, f32) -> f32| (0..volume).map(|i| op(input(0, i), input(1, i))).collect();
+        let values = match self {
+            LayerType::Input | LayerType::Constant => return None,
+            LayerType::Add => binary(|a, b| a + b),
+            LayerType::Sub => binary(|a, b| a - b),
+            LayerType::Mul => binary(|a, b| a * b),
+            LayerType::Relu => (0..volume).map(|i| input(0, i).max(0.0)).collect(),
+            LayerType::Softmax => {
+                let mut values: Vec<f32> = (0..volume).map(|i| input(0, i)).collect();
+                let last = shape.last().map_or(1, |d| (*d as usize).max(1));
+                for row in values.chunks_mut(last) {
+                    let max = row.iter().copied().fold(f32::MIN, f32::max);
+                    row.iter_mut().for_each(|v| *v = (*v - max).exp());
+                    let sum: f32 = row.iter().sum();
+                    row.iter_mut().for_each(|v| *v /= sum);
+                }
+                values
+            }
+            LayerType::Copy | LayerType::Quantize | LayerType::Dequantize => {
+                (0..volume).map(|i| input(0, i)).collect()
+            }
+        };
+        Some(values)
+    }
+}
+
+fn datatype(name: &str) -> Option<NNTensorType> {
+    DATATYPES
+        .iter()
+        .find(|(_, n)| n.to_bytes() == name.as_bytes())
+        .map(|(t, _)| *t)
+}
+
+fn datatype_name(tensor_type: NNTensorType) -> &'static CStr {
+    DATATYPES
+        .iter()
+        .find(|(t, _)| *t == tensor_type)
+        .map_or(c"", |(_, n)| n)
+}
+
+pub(crate) enum ParameterData {
+    F32(Vec<f32>),
+    I32(Vec<i32>),
+    I16(Vec<i16>),
+    I8(Vec<i8>),
+    Raw(Vec<u8>),
+    Str(Vec<CString>),
+}
+
+pub(crate) struct Parameter {
+    key: String,
+    shape: Vec<i32>,
+    data: ParameterData,
+}
+
+impl Parameter {
+    fn raw(&self) -> &[u8] {
+        fn bytes<T>(values: &[T]) -> &[u8] {
+            unsafe {
+                std::slice::from_raw_parts(
+                    values.as_ptr() as *const u8,
+                    std::mem::size_of_val(values),
+                )
+            }
+        }
+        match &self.data {
+            ParameterData::F32(v) => bytes(v),
+            ParameterData::I32(v) => bytes(v),
+            ParameterData::I16(v) => bytes(v),
+            ParameterData::I8(v) => bytes(v),
+            ParameterData::Raw(v) => v,
+            ParameterData::Str(_) => &[],
+        }
+    }
+}
+
+pub(crate) struct LayerDef {
+    name: CString,
+    pub(crate) layer_type: LayerType,
+    pub(crate) datatype: NNTensorType,
+    pub(crate) shape: Vec<i32>,
+    pub(crate) inputs: Vec<usize>,
+    pub(crate) scales: Vec<f32>,
+    pub(crate) zeros: Vec<i32>,
+    pub(crate) axis: i32,
+    parameters: Vec<Parameter>,
+}
+
+impl LayerDef {
+    pub(crate) fn name(&self) -> &str {
+        self.name.to_str().unwrap_or_default()
+    }
+
+    pub(crate) fn data(&self) -> Option<&[f32]> {
+        self.parameters
+            .iter()
+            .find_map(|p| match (&p.data, p.key.as_str()) {
+                (ParameterData::F32(values), "data") => Some(values.as_slice()),
+                _ => None,
+            })
+    }
+
+    fn size(&self) -> usize {
+        let volume: usize = self.shape.iter().map(|d| *d as usize).product();
+        volume * crate::tensor::element_size(self.datatype).unwrap_or(1)
+    }
+}
+
+struct Resource {
+    name: CString,
+    mime: CString,
+    meta: CString,
+    data: Vec<u8>,
+}
+
+pub(crate) struct Model {
+    name: CString,
+    uuid: CString,
+    serial: u32,
+    labels: Vec<CString>,
+    pub(crate) layers: Vec<LayerDef>,
+    inputs: Vec<u32>,
+    outputs: Vec<u32>,
+    resources: Vec<Resource>,
+}
+
+/// Errors returned by `nn_model_validate`, indexed by code minus one.
+const ERRORS: &[&CStr] = &[
+    c"not a mock model",
+    c"model is not UTF-8",
+    c"unknown declaration",
+    c"unknown layer type",
+    c"unknown datatype",
+    c"unknown layer",
+    c"invalid value",
+    c"wrong number of layer inputs",
+    c"missing end",
+    c"model size does not match the header",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ParseError {
+    NotMock = 1,
+    Utf8,
+    Declaration,
+    LayerType,
+    Datatype,
+    Layer,
+    Value,
+    InputCount,
+    End,
+    Size,
+}
+
+fn values<T: std::str::FromStr>(text: &str) -> Result<Vec<T>, ParseError> {
+    if text.is_empty() {
+        return Ok(Vec::new());
+    }
+    text.split(',')
+        .map(|v| v.trim().parse().map_err(|_| ParseError::Value))
+        .collect()
+}
+
+fn cstring(text: &str) -> Result<CString, ParseError> {
+    CString::new(text).map_err(|_| ParseError::Value)
+}
+
+fn hex(text: &str) -> Result<Vec<u8>, ParseError> {
+    if !text.len().is_multiple_of(2) {
+        return Err(ParseError::Value);
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).map_err(|_| ParseError::Value))
+        .collect()
+}
+
+fn parameter(key: &str, value: &str) -> Result<Parameter, ParseError> {
+    let (kind, text) = value.split_once(':').ok_or(ParseError::Value)?;
+    let data = match kind {
+        "f32" => ParameterData::F32(values(text)?),
+        "i32" => ParameterData::I32(values(text)?),
+        "i16" => ParameterData::I16(values(text)?),
+        "i8" => ParameterData::I8(values(text)?),
+        "raw" => ParameterData::Raw(hex(text)?),
+        "str" => ParameterData::Str(text.split(',').map(cstring).collect::<Result<_, _>>()?),
+        _ => return Err(ParseError::Datatype),
+    };
+    let len = match &data {
+        ParameterData::F32(v) => v.len(),
+        ParameterData::I32(v) => v.len(),
+        ParameterData::I16(v) => v.len(),
+        ParameterData::I8(v) => v.len(),
+        ParameterData::Raw(v) => v.len(),
+        ParameterData::Str(v) => v.len(),
+    };
+    Ok(Parameter {
+        key: key.to_string(),
+        shape: vec![len as i32],
+        data,
+    })
+}
+
+impl Model {
+    pub(crate) fn parse(data: &[u8]) -> Result<Model, ParseError> {
+        let text = std::str::from_utf8(data).map_err(|_| ParseError::Utf8)?;
+        let mut lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'));
+        let size = lines
+            .next()
+            .and_then(|l| l.strip_prefix(HEADER))
+            .and_then(|l| l.strip_prefix(' '))
+            .ok_or(ParseError::NotMock)?;
+        if size.len() != SIZE_DIGITS || size.parse::<usize>() != Ok(data.len()) {
+            return Err(ParseError::Size);
+        }
+
+        let mut model = Model {
+            name: CString::default(),
+            uuid: CString::default(),
+            serial: 0,
+            labels: Vec::new(),
+            layers: Vec::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            resources: Vec::new(),
+        };
+        // Layer inputs and outputs are resolved once every layer is known.
+        let mut layer_inputs = Vec::new();
+        let mut outputs = Vec::new();
+        let mut end = false;
+        for line in lines.by_ref() {
+            let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let rest = rest.trim();
+            match keyword {
+                "end" => {
+                    end = true;
+                    break;
+                }
+                "name" => model.name = cstring(rest)?,
+                "uuid" => model.uuid = cstring(rest)?,
+                "serial" => model.serial = rest.parse().map_err(|_| ParseError::Value)?,
+                "label" => model.labels.push(cstring(rest)?),
+                "output" => outputs.push(rest.to_string()),
+                "resource" => {
+                    let mut fields = rest.splitn(4, char::is_whitespace);
+                    let mut field = || fields.next().ok_or(ParseError::Value);
+                    let (name, mime, data) = (field()?, field()?, field()?);
+                    model.resources.push(Resource {
+                        name: cstring(name)?,
+                        mime: cstring(mime)?,
+                        data: hex(data)?,
+                        meta: cstring(fields.next().unwrap_or_default().trim())?,
+                    });
+                }
+                "layer" => {
+                    let mut fields = rest.split_whitespace();
+                    let mut field = || fields.next().ok_or(ParseError::Value);
+                    let name = cstring(field()?)?;
+                    let layer_type = LayerType::parse(field()?).ok_or(ParseError::LayerType)?;
+                    let datatype = datatype(field()?).ok_or(ParseError::Datatype)?;
+                    let shape = values(field()?)?;
+                    let mut layer = LayerDef {
+                        name,
+                        layer_type,
+                        datatype,
+                        shape,
+                        inputs: Vec::new(),
+                        scales: Vec::new(),
+                        zeros: Vec::new(),
+                        axis: -1,
+                        parameters: Vec::new(),
+                    };
+                    let mut inputs = Vec::new();
+                    for attribute in fields {
+                        let (key, value) = attribute.split_once('=').ok_or(ParseError::Value)?;
+                        match key {
+                            "inputs" => inputs = value.split(',').map(String::from).collect(),
+                            "data" => {
+                                let mut data = parameter("data", &format!("f32:{}", value))?;
+                                data.shape = layer.shape.clone();
+                                layer.parameters.push(data);
+                            }
+                            "scales" => layer.scales = values(value)?,
+                            "zeros" => layer.zeros = values(value)?,
+                            "axis" => layer.axis = value.parse().map_err(|_| ParseError::Value)?,
+                            _ => {
+                                let key = key.strip_prefix("param.").ok_or(ParseError::Value)?;
+                                layer.parameters.push(parameter(key, value)?);
+                            }
+                        }
+                    }
+                    if inputs.len() != layer_type.input_count() {
+                        return Err(ParseError::InputCount);
+                    }
+                    layer_inputs.push(inputs);
+                    model.layers.push(layer);
+                }
+                _ => return Err(ParseError::Declaration),
+            }
+        }
+        if !end {
+            return Err(ParseError::End);
+        }
+
+        let lookup = |model: &Model, name: &str| {
+            model
+                .layers
+                .iter()
+                .position(|l| l.name() == name)
+                .ok_or(ParseError::Layer)
+        };
+        for (index, inputs) in layer_inputs.iter().enumerate() {
+            let inputs = inputs
+                .iter()
+                .map(|name| lookup(&model, name))
+                .collect::<Result<Vec<_>, _>>()?;
+            model.layers[index].inputs = inputs;
+        }
+        model.inputs = (0..model.layers.len() as u32)
+            .filter(|i| model.layers[*i as usize].layer_type == LayerType::Input)
+            .collect();
+        model.outputs = if outputs.is_empty() {
+            (0..model.layers.len())
+                .filter(|i| !model.layers.iter().any(|l| l.inputs.contains(i)))
+                .map(|i| i as u32)
+                .collect()
+        } else {
+            outputs
+                .iter()
+                .map(|name| lookup(&model, name).map(|i| i as u32))
+                .collect::<Result<_, _>>()?
+        };
+        Ok(model)
+    }
+}
+
+/// A layer of a [`ModelBuilder`].
+#[derive(Debug, Clone)]
+pub struct Layer {
+    name: String,
+    layer_type: String,
+    datatype: String,
+    shape: Vec<i32>,
+    attributes: Vec<String>,
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+impl Layer {
+    /// A layer of `layer_type` producing a `datatype` tensor of `shape`, the
+    /// names are those of the model format.
+    pub fn new(name: &str, layer_type: &str, datatype: &str, shape: &[i32]) -> Self {
+        Layer {
+            name: name.to_string(),
+            layer_type: layer_type.to_string(),
+            datatype: datatype.to_string(),
+            shape: shape.to_vec(),
+            attributes: Vec::new(),
+        }
+    }
+
+    pub fn input(name: &str, datatype: &str, shape: &[i32]) -> Self {
+        Layer::new(name, "input", datatype, shape)
+    }
+
+    pub fn constant(name: &str, datatype: &str, shape: &[i32], data: &[f32]) -> Self {
+        Layer::new(name, "constant", datatype, shape).data(data)
+    }
+
+    pub fn inputs(mut self, names: &[&str]) -> Self {
+        self.attributes.push(format!("inputs={}", names.join(",")));
+        self
+    }
+
+    pub fn data(mut self, values: &[f32]) -> Self {
+        self.attributes.push(format!("data={}", join(values)));
+        self
+    }
+
+    pub fn quantization(mut self, scales: &[f32], zeros: &[i32], axis: i32) -> Self {
+        self.attributes.push(format!("scales={}", join(scales)));
+        self.attributes.push(format!("zeros={}", join(zeros)));
+        self.attributes.push(format!("axis={}", axis));
+        self
+    }
+
+    pub fn parameter_f32(mut self, key: &str, values: &[f32]) -> Self {
+        self.attributes
+            .push(format!("param.{}=f32:{}", key, join(values)));
+        self
+    }
+
+    pub fn parameter_i32(mut self, key: &str, values: &[i32]) -> Self {
+        self.attributes
+            .push(format!("param.{}=i32:{}", key, join(values)));
+        self
+    }
+
+    pub fn parameter_str(mut self, key: &str, values: &[&str]) -> Self {
+        self.attributes
+            .push(format!("param.{}=str:{}", key, values.join(",")));
+        self
+    }
+}
+
+/// Writes models in the mock format.
+#[derive(Debug, Clone, Default)]
+pub struct ModelBuilder {
+    name: String,
+    uuid: String,
+    serial: u32,
+    labels: Vec<String>,
+    layers: Vec<Layer>,
+    outputs: Vec<String>,
+    resources: Vec<String>,
+}
+
+impl ModelBuilder {
+    pub fn new(name: &str) -> Self {
+        ModelBuilder {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn uuid(mut self, uuid: &str) -> Self {
+        self.uuid = uuid.to_string();
+        self
+    }
+
+    pub fn serial(mut self, serial: u32) -> Self {
+        self.serial = serial;
+        self
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.labels.push(label.to_string());
+        self
+    }
+
+    pub fn layer(mut self, layer: Layer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    /// Marks the layer `name` as an output, by default the outputs are the
+    /// layers no other layer consumes.
+    pub fn output(mut self, name: &str) -> Self {
+        self.outputs.push(name.to_string());
+        self
+    }
+
+    pub fn resource(mut self, name: &str, mime: &str, data: &[u8], meta: &str) -> Self {
+        let mut line = format!("{} {} ", name, mime);
+        for byte in data {
+            let _ = write!(line, "{:02x}", byte);
+        }
+        line.push(' ');
+        line.push_str(meta);
+        self.resources.push(line);
+        self
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let mut text = format!("name {}\n", self.name);
+        if !self.uuid.is_empty() {
+            let _ = writeln!(text, "uuid {}", self.uuid);
+        }
+        if self.serial != 0 {
+            let _ = writeln!(text, "serial {}", self.serial);
+        }
+        for label in &self.labels {
+            let _ = writeln!(text, "label {}", label);
+        }
+        for layer in &self.layers {
+            let _ = write!(
+                text,
+                "layer {} {} {} {}",
+                layer.name,
+                layer.layer_type,
+                layer.datatype,
+                join(&layer.shape)
+            );
+            for attribute in &layer.attributes {
+                let _ = write!(text, " {}", attribute);
+            }
+            text.push('\n');
+        }
+        for output in &self.outputs {
+            let _ = writeln!(text, "output {}", output);
+        }
+        for resource in &self.resources {
+            let _ = writeln!(text, "resource {}", resource);
+        }
+        text.push_str("end\n");
+        let size = HEADER.len() + SIZE_DIGITS + 2 + text.len();
+        format!("{} {:0width$}\n{}", HEADER, size, text, width = SIZE_DIGITS).into_bytes()
+    }
+}
+
+/// Parsed models with the memory they were parsed from.
+static MODELS: Mutex<Vec<(Vec<u8>, &'static Model)>> = Mutex::new(Vec::new());
+
+/// Parses the model in `data` or returns the model already parsed from the
+/// same contents.
+pub(crate) fn register(data: &[u8]) -> Result<&'static Model, ParseError> {
+    let mut models = MODELS.lock().unwrap();
+    if let Some((_, model)) = models.iter().find(|(bytes, _)| bytes == data) {
+        return Ok(model);
+    }
+    let model: &'static Model = Box::leak(Box::new(Model::parse(data)?));
+    models.push((data.to_vec(), model));
+    Ok(model)
+}
+
+/// Size of the model at `data` as recorded in its header line.
+unsafe fn model_size(data: *const u8) -> Option<usize> {
+    let header = std::slice::from_raw_parts(data, HEADER.len() + SIZE_DIGITS + 2);
+    let size = header.strip_prefix(HEADER.as_bytes())?.strip_prefix(b" ")?;
+    let size = size.strip_suffix(b"\n")?;
+    std::str::from_utf8(size).ok()?.parse().ok()
+}
+
+unsafe fn as_model<'a>(model: *const NNModel) -> Option<&'a Model> {
+    if model.is_null() {
+        return None;
+    }
+    let size = model_size(model as *const u8)?;
+    register(std::slice::from_raw_parts(model as *const u8, size)).ok()
+}
+
+unsafe fn as_layer<'a>(model: *const NNModel, index: usize) -> Option<&'a LayerDef> {
+    as_model(model)?.layers.get(index)
+}
+
+unsafe fn set_len(len: *mut usize, value: usize) {
+    if !len.is_null() {
+        *len = value;
+    }
+}
+
+/// Pointer to the first element of `values`, null when empty.
+unsafe fn slice_ptr<T>(values: &[T], len: *mut usize) -> *const T {
+    set_len(len, values.len());
+    if values.is_empty() {
+        return ptr::null();
+    }
+    values.as_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_validate(memory: *const NNModel, size: usize) -> c_int {
+    if memory.is_null() {
+        return ParseError::NotMock as c_int;
+    }
+    let data = std::slice::from_raw_parts(memory as *const u8, size);
+    match Model::parse(data) {
+        Ok(_) => 0,
+        Err(e) => e as c_int,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn nn_model_validate_error(err: c_int) -> *const c_char {
+    match err {
+        0 => c"valid".as_ptr(),
+        err => ERRORS
+            .get(err as usize - 1)
+            .map_or(ptr::null(), |e| e.as_ptr()),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_name(model: *const NNModel) -> *const c_char {
+    as_model(model).map_or(ptr::null(), |m| m.name.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_uuid(model: *const NNModel) -> *const c_char {
+    as_model(model).map_or(ptr::null(), |m| m.uuid.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_serial(model: *const NNModel) -> u32 {
+    as_model(model).map_or(0, |m| m.serial)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_label_count(model: *const NNModel) -> c_int {
+    as_model(model).map_or(0, |m| m.labels.len() as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_label(model: *const NNModel, index: c_int) -> *const c_char {
+    as_model(model)
+        .and_then(|m| m.labels.get(usize::try_from(index).ok()?))
+        .map_or(ptr::null(), |l| l.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_inputs(
+    model: *const NNModel,
+    n_inputs: *mut usize,
+) -> *const u32 {
+    match as_model(model) {
+        Some(model) => slice_ptr(&model.inputs, n_inputs),
+        None => slice_ptr(&[], n_inputs),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_outputs(
+    model: *const NNModel,
+    n_outputs: *mut usize,
+) -> *const u32 {
+    match as_model(model) {
+        Some(model) => slice_ptr(&model.outputs, n_outputs),
+        None => slice_ptr(&[], n_outputs),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_layer_count(model: *const NNModel) -> usize {
+    as_model(model).map_or(0, |m| m.layers.len())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_layer_name(model: *const NNModel, index: usize) -> *const c_char {
+    as_layer(model, index).map_or(ptr::null(), |l| l.name.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_layer_lookup(
+    model: *const NNModel,
+    name: *const c_char,
+) -> c_int {
+    let (Some(model), Some(name)) = (as_model(model), str_arg(name)) else {
+        return -1;
+    };
+    model
+        .layers
+        .iter()
+        .position(|l| l.name() == name)
+        .map_or(-1, |i| i as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_layer_type(model: *const NNModel, index: usize) -> *const c_char {
+    as_layer(model, index).map_or(ptr::null(), |l| l.layer_type.name().as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_layer_type_id(model: *const NNModel, index: usize) -> i16 {
+    as_layer(model, index).map_or(-1, |l| l.layer_type.id() as i16)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_layer_datatype(
+    model: *const NNModel,
+    index: usize,
+) -> *const c_char {
+    as_layer(model, index).map_or(ptr::null(), |l| datatype_name(l.datatype).as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_layer_datatype_id(
+    model: *const NNModel,
+    index: usize,
+) -> NNTensorType {
+    as_layer(model, index).map_or(NNTensorType::RAW, |l| l.datatype)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_layer_shape(
+    model: *const NNModel,
+    index: usize,
+    n_dims: *mut usize,
+) -> *const i32 {
+    match as_layer(model, index) {
+        Some(layer) => slice_ptr(&layer.shape, n_dims),
+        None => slice_ptr(&[], n_dims),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_layer_scales(
+    model: *const NNModel,
+    index: usize,
+    n_scales: *mut usize,
+) -> *const f32 {
+    match as_layer(model, index) {
+        Some(layer) => slice_ptr(&layer.scales, n_scales),
+        None => slice_ptr(&[], n_scales),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_layer_zeros(
+    model: *const NNModel,
+    index: usize,
+    n_zeros: *mut usize,
+) -> *const i32 {
+    match as_layer(model, index) {
+        Some(layer) => slice_ptr(&layer.zeros, n_zeros),
+        None => slice_ptr(&[], n_zeros),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_layer_axis(model: *const NNModel, index: usize) -> c_int {
+    as_layer(model, index).map_or(-1, |l| l.axis)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_layer_parameter(
+    model: *const NNModel,
+    index: usize,
+    key: *const c_char,
+) -> *const NNModelParameter {
+    let (Some(layer), Some(key)) = (as_layer(model, index), str_arg(key)) else {
+        return ptr::null();
+    };
+    layer
+        .parameters
+        .iter()
+        .find(|p| p.key == key)
+        .map_or(ptr::null(), |p| {
+            p as *const Parameter as *const NNModelParameter
+        })
+}
+
+unsafe fn as_parameter<'a>(parameter: *const NNModelParameter) -> Option<&'a Parameter> {
+    (parameter as *const Parameter).as_ref()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_parameter_shape(
+    parameter: *const NNModelParameter,
+    n_dims: *mut usize,
+) -> *const i32 {
+    match as_parameter(parameter) {
+        Some(parameter) => slice_ptr(&parameter.shape, n_dims),
+        None => slice_ptr(&[], n_dims),
+    }
+}
+
+macro_rules! parameter_data {
+    ($name:ident, $variant:ident, $type:ty) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn $name(
+            parameter: *const NNModelParameter,
+            length: *mut usize,
+        ) -> *const $type {
+            match as_parameter(parameter).map(|p| &p.data) {
+                Some(ParameterData::$variant(values)) => slice_ptr(values, length),
+                _ => slice_ptr(&[], length),
+            }
+        }
+    };
+}
+
+parameter_data!(nn_model_parameter_data_f32, F32, f32);
+parameter_data!(nn_model_parameter_data_i32, I32, i32);
+parameter_data!(nn_model_parameter_data_i16, I16, i16);
+parameter_data!(nn_model_parameter_data_i8, I8, i8);
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_parameter_data_raw(
+    parameter: *const NNModelParameter,
+    length: *mut usize,
+) -> *const u8 {
+    match as_parameter(parameter) {
+        Some(parameter) => slice_ptr(parameter.raw(), length),
+        None => slice_ptr(&[], length),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_parameter_data_str(
+    parameter: *const NNModelParameter,
+    index: usize,
+) -> *const c_char {
+    match as_parameter(parameter).map(|p| &p.data) {
+        Some(ParameterData::Str(values)) => values.get(index).map_or(ptr::null(), |v| v.as_ptr()),
+        _ => ptr::null(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_parameter_data_str_len(
+    parameter: *const NNModelParameter,
+) -> usize {
+    match as_parameter(parameter).map(|p| &p.data) {
+        Some(ParameterData::Str(values)) => values.len(),
+        _ => 0,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_memory_size(model: *const NNModel) -> usize {
+    as_model(model).map_or(0, |m| m.layers.iter().map(LayerDef::size).sum())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_cache_minimum_size(_model: *const NNModel) -> usize {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_cache_optimum_size(model: *const NNModel) -> usize {
+    as_model(model).map_or(0, |m| {
+        m.layers.iter().map(LayerDef::size).max().unwrap_or(0)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_resource_count(model: *const NNModel) -> usize {
+    as_model(model).map_or(0, |m| m.resources.len())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_resource_at(
+    model: *const NNModel,
+    index: usize,
+) -> *const NNModelResource {
+    as_model(model)
+        .and_then(|m| m.resources.get(index))
+        .map_or(ptr::null(), |r| {
+            r as *const Resource as *const NNModelResource
+        })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_resource(
+    model: *const NNModel,
+    name: *const c_char,
+) -> *const NNModelResource {
+    let (Some(model), Some(name)) = (as_model(model), str_arg(name)) else {
+        return ptr::null();
+    };
+    model
+        .resources
+        .iter()
+        .find(|r| r.name.to_bytes() == name.as_bytes())
+        .map_or(ptr::null(), |r| {
+            r as *const Resource as *const NNModelResource
+        })
+}
+
+unsafe fn as_resource<'a>(resource: *const NNModelResource) -> Option<&'a Resource> {
+    (resource as *const Resource).as_ref()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_resource_name(resource: *const NNModelResource) -> *const c_char {
+    as_resource(resource).map_or(ptr::null(), |r| r.name.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_resource_mime(resource: *const NNModelResource) -> *const c_char {
+    as_resource(resource).map_or(ptr::null(), |r| r.mime.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_resource_meta(resource: *const NNModelResource) -> *const c_char {
+    as_resource(resource).map_or(ptr::null(), |r| r.meta.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_model_resource_data(
+    resource: *const NNModelResource,
+    data_size: *mut usize,
+) -> *const u8 {
+    match as_resource(resource) {
+        Some(resource) => slice_ptr(&resource.data, data_size),
+        None => slice_ptr(&[], data_size),
+    }
+}
@@ -0,0 +1,531 @@
+use deepviewrt_sys::{nn_aux_object_free, NNEngine, NNError, NNTensor, NNTensorType};
+use std::{
+    ffi::{c_char, c_int, c_void},
+    ptr,
+};
+
+/// Size in bytes of one element of `tensor_type`.
+pub(crate) fn element_size(tensor_type: NNTensorType) -> Option<usize> {
+    let size = match tensor_type {
+        NNTensorType::RAW | NNTensorType::STR | NNTensorType::I8 | NNTensorType::U8 => 1,
+        NNTensorType::I16 | NNTensorType::U16 | NNTensorType::F16 => 2,
+        NNTensorType::I32 | NNTensorType::U32 | NNTensorType::F32 => 4,
+        NNTensorType::I64 | NNTensorType::U64 | NNTensorType::F64 => 8,
+        _ => return None,
+    };
+    Some(size)
+}
+
+fn is_integer(tensor_type: NNTensorType) -> bool {
+    !matches!(
+        tensor_type,
+        NNTensorType::F16 | NNTensorType::F32 | NNTensorType::F64
+    )
+}
+
+/// Range of the values representable by an integer `tensor_type`.
+fn integer_range(tensor_type: NNTensorType) -> (f64, f64) {
+    match tensor_type {
+        NNTensorType::I8 => (i8::MIN as f64, i8::MAX as f64),
+        NNTensorType::I16 => (i16::MIN as f64, i16::MAX as f64),
+        NNTensorType::U16 => (0.0, u16::MAX as f64),
+        NNTensorType::I32 => (i32::MIN as f64, i32::MAX as f64),
+        NNTensorType::U32 => (0.0, u32::MAX as f64),
+        NNTensorType::I64 => (i64::MIN as f64, i64::MAX as f64),
+        NNTensorType::U64 => (0.0, u64::MAX as f64),
+        _ => (0.0, u8::MAX as f64),
+    }
+}
+
+fn f16_to_f32(half: u16) -> f32 {
+    let sign = ((half >> 15) as u32) << 31;
+    let exponent = ((half >> 10) & 0x1f) as u32;
+    let mantissa = (half & 0x3ff) as u32;
+    let bits = match exponent {
+        0 if mantissa == 0 => sign,
+        0 => {
+            // Subnormal, normalize the mantissa.
+            let shift = mantissa.leading_zeros() - 21;
+            sign | ((113 - shift) << 23) | (((mantissa << shift) & 0x3ff) << 13)
+        }
+        0x1f => sign | 0x7f80_0000 | (mantissa << 13),
+        _ => sign | ((exponent + 112) << 23) | (mantissa << 13),
+    };
+    f32::from_bits(bits)
+}
+
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32 - 127 + 15;
+    let mantissa = bits & 0x7f_ffff;
+    if value.is_nan() {
+        return sign | 0x7e00;
+    }
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if exponent <= 0 {
+        if exponent < -10 {
+            return sign;
+        }
+        let mantissa = (mantissa | 0x80_0000) >> (1 - exponent);
+        return sign | ((mantissa + 0x1000) >> 13) as u16;
+    }
+    let half = ((exponent as u32) << 10) | (mantissa >> 13);
+    // Round to nearest, a carry into the exponent is the correct result.
+    sign | (half + ((mantissa >> 12) & 1)) as u16
+}
+
+pub(crate) struct Tensor {
+    pub(crate) engine: *mut NNEngine,
+    pub(crate) tensor_type: NNTensorType,
+    dims: usize,
+    shape: [i32; 4],
+    strides: [i32; 4],
+    /// Element storage, `u64` keeps every element type aligned.
+    data: Vec<u64>,
+    size: usize,
+    pub(crate) scales: Vec<f32>,
+    pub(crate) zeros: Vec<i32>,
+    pub(crate) axis: i32,
+    maps: usize,
+    pub(crate) time: i64,
+    aux: Option<(*mut c_void, nn_aux_object_free)>,
+}
+
+impl Tensor {
+    pub(crate) fn new(engine: *mut NNEngine) -> Self {
+        Tensor {
+            engine,
+            tensor_type: NNTensorType::F32,
+            dims: 0,
+            shape: [0; 4],
+            strides: [0; 4],
+            data: Vec::new(),
+            size: 0,
+            scales: Vec::new(),
+            zeros: Vec::new(),
+            axis: -1,
+            maps: 0,
+            time: 0,
+            aux: None,
+        }
+    }
+
+    pub(crate) fn alloc(&mut self, tensor_type: NNTensorType, shape: &[i32]) -> NNError {
+        let Some(item_size) = element_size(tensor_type) else {
+            return NNError::NN_ERROR_TENSOR_TYPE_UNSUPPORTED;
+        };
+        if shape.len() > 4 || shape.iter().any(|d| *d < 0) {
+            return NNError::NN_ERROR_INVALID_SHAPE;
+        }
+        self.tensor_type = tensor_type;
+        self.dims = shape.len();
+        self.shape = [0; 4];
+        self.shape[..shape.len()].copy_from_slice(shape);
+        let mut stride = 1;
+        for dim in (0..self.dims).rev() {
+            self.strides[dim] = stride;
+            stride *= self.shape[dim];
+        }
+        self.size = self.volume() * item_size;
+        self.data = vec![0; self.size.div_ceil(8)];
+        NNError::NN_SUCCESS
+    }
+
+    pub(crate) fn shape(&self) -> &[i32] {
+        &self.shape[..self.dims]
+    }
+
+    pub(crate) fn volume(&self) -> usize {
+        if self.dims == 0 {
+            return 0;
+        }
+        self.shape().iter().map(|d| *d as usize).product()
+    }
+
+    pub(crate) fn bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.data.as_ptr() as *const u8, self.size) }
+    }
+
+    pub(crate) fn bytes_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.data.as_mut_ptr() as *mut u8, self.size) }
+    }
+
+    fn is_quantized(&self) -> bool {
+        is_integer(self.tensor_type) && !self.scales.is_empty()
+    }
+
+    /// Index of the quantization parameters applying to element `index`.
+    fn channel(&self, index: usize) -> usize {
+        if self.scales.len() <= 1 || self.axis < 0 || self.axis as usize >= self.dims {
+            return 0;
+        }
+        let axis = self.axis as usize;
+        let inner = self.strides[axis].max(1) as usize;
+        (index / inner) % self.shape[axis].max(1) as usize
+    }
+
+    fn scale_zero(&self, index: usize) -> (f64, f64) {
+        let channel = self.channel(index);
+        let scale = self.scales.get(channel).or(self.scales.first());
+        let zero = self.zeros.get(channel).or(self.zeros.first());
+        (
+            scale.copied().unwrap_or(1.0) as f64,
+            zero.copied().unwrap_or(0) as f64,
+        )
+    }
+
+    fn raw(&self, index: usize) -> f64 {
+        let size = element_size(self.tensor_type).unwrap_or(1);
+        let bytes = &self.bytes()[index * size..(index + 1) * size];
+        match self.tensor_type {
+            NNTensorType::I8 => bytes[0] as i8 as f64,
+            NNTensorType::I16 => i16::from_ne_bytes(bytes.try_into().unwrap()) as f64,
+            NNTensorType::U16 => u16::from_ne_bytes(bytes.try_into().unwrap()) as f64,
+            NNTensorType::I32 => i32::from_ne_bytes(bytes.try_into().unwrap()) as f64,
+            NNTensorType::U32 => u32::from_ne_bytes(bytes.try_into().unwrap()) as f64,
+            NNTensorType::I64 => i64::from_ne_bytes(bytes.try_into().unwrap()) as f64,
+            NNTensorType::U64 => u64::from_ne_bytes(bytes.try_into().unwrap()) as f64,
+            NNTensorType::F16 => f16_to_f32(u16::from_ne_bytes(bytes.try_into().unwrap())) as f64,
+            NNTensorType::F32 => f32::from_ne_bytes(bytes.try_into().unwrap()) as f64,
+            NNTensorType::F64 => f64::from_ne_bytes(bytes.try_into().unwrap()),
+            _ => bytes[0] as f64,
+        }
+    }
+
+    fn set_raw(&mut self, index: usize, value: f64) {
+        let tensor_type = self.tensor_type;
+        let size = element_size(tensor_type).unwrap_or(1);
+        let value = if is_integer(tensor_type) {
+            let (min, max) = integer_range(tensor_type);
+            value.round().clamp(min, max)
+        } else {
+            value
+        };
+        let bytes = &mut self.bytes_mut()[index * size..(index + 1) * size];
+        match tensor_type {
+            NNTensorType::I8 => bytes.copy_from_slice(&(value as i8).to_ne_bytes()),
+            NNTensorType::I16 => bytes.copy_from_slice(&(value as i16).to_ne_bytes()),
+            NNTensorType::U16 => bytes.copy_from_slice(&(value as u16).to_ne_bytes()),
+            NNTensorType::I32 => bytes.copy_from_slice(&(value as i32).to_ne_bytes()),
+            NNTensorType::U32 => bytes.copy_from_slice(&(value as u32).to_ne_bytes()),
+            NNTensorType::I64 => bytes.copy_from_slice(&(value as i64).to_ne_bytes()),
+            NNTensorType::U64 => bytes.copy_from_slice(&(value as u64).to_ne_bytes()),
+            NNTensorType::F16 => bytes.copy_from_slice(&f32_to_f16(value as f32).to_ne_bytes()),
+            NNTensorType::F32 => bytes.copy_from_slice(&(value as f32).to_ne_bytes()),
+            NNTensorType::F64 => bytes.copy_from_slice(&value.to_ne_bytes()),
+            _ => bytes[0] = value as u8,
+        }
+    }
+
+    /// The element `index` as a real value, dequantized when quantized.
+    pub(crate) fn get(&self, index: usize) -> f32 {
+        let raw = self.raw(index);
+        if !self.is_quantized() {
+            return raw as f32;
+        }
+        let (scale, zero) = self.scale_zero(index);
+        ((raw - zero) * scale) as f32
+    }
+
+    /// Stores the real `value` at `index`, quantized when quantized.
+    pub(crate) fn set(&mut self, index: usize, value: f32) {
+        let value = value as f64;
+        if !self.is_quantized() {
+            return self.set_raw(index, value);
+        }
+        let (scale, zero) = self.scale_zero(index);
+        self.set_raw(index, value / scale + zero)
+    }
+
+    pub(crate) fn values(&self) -> Vec<f32> {
+        (0..self.volume()).map(|i| self.get(i)).collect()
+    }
+
+    pub(crate) fn set_values(&mut self, values: &[f32]) {
+        for (index, value) in values.iter().enumerate().take(self.volume()) {
+            self.set(index, *value);
+        }
+    }
+
+    fn map(&mut self) -> *mut c_void {
+        if self.size == 0 {
+            return ptr::null_mut();
+        }
+        self.maps += 1;
+        self.data.as_mut_ptr() as *mut c_void
+    }
+
+    fn aux_object(&self) -> *mut c_void {
+        self.aux.map_or(ptr::null_mut(), |(object, _)| object)
+    }
+}
+
+impl Drop for Tensor {
+    fn drop(&mut self) {
+        // The free function receives the tensor and retrieves the object.
+        if let Some((_, Some(free))) = self.aux {
+            unsafe { free(self as *mut Tensor as *mut NNTensor) };
+        }
+    }
+}
+
+unsafe fn as_tensor<'a>(tensor: *const NNTensor) -> Option<&'a Tensor> {
+    (tensor as *const Tensor).as_ref()
+}
+
+unsafe fn as_tensor_mut<'a>(tensor: *mut NNTensor) -> Option<&'a mut Tensor> {
+    (tensor as *mut Tensor).as_mut()
+}
+
+pub(crate) fn into_ptr(tensor: Box<Tensor>) -> *mut NNTensor {
+    Box::into_raw(tensor) as *mut NNTensor
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_init(
+    memory: *mut c_void,
+    engine: *mut NNEngine,
+) -> *mut NNTensor {
+    if !memory.is_null() {
+        return ptr::null_mut();
+    }
+    into_ptr(Box::new(Tensor::new(engine)))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_release(tensor: *mut NNTensor) {
+    if !tensor.is_null() {
+        drop(Box::from_raw(tensor as *mut Tensor));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_alloc(
+    tensor: *mut NNTensor,
+    type_: NNTensorType,
+    n_dims: i32,
+    shape: *const i32,
+) -> NNError {
+    let Some(tensor) = as_tensor_mut(tensor) else {
+        return NNError::NN_ERROR_INVALID_HANDLE;
+    };
+    if !(0..=4).contains(&n_dims) || (n_dims > 0 && shape.is_null()) {
+        return NNError::NN_ERROR_INVALID_SHAPE;
+    }
+    let shape = if n_dims == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(shape, n_dims as usize)
+    };
+    tensor.alloc(type_, shape)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_engine(tensor: *mut NNTensor) -> *mut NNEngine {
+    as_tensor_mut(tensor).map_or(ptr::null_mut(), |t| t.engine)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_dims(tensor: *const NNTensor) -> i32 {
+    as_tensor(tensor).map_or(0, |t| t.dims as i32)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_shape(tensor: *const NNTensor) -> *const i32 {
+    as_tensor(tensor).map_or(ptr::null(), |t| t.shape.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_strides(tensor: *const NNTensor) -> *const i32 {
+    as_tensor(tensor).map_or(ptr::null(), |t| t.strides.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_volume(tensor: *const NNTensor) -> i32 {
+    as_tensor(tensor).map_or(0, |t| t.volume() as i32)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_size(tensor: *const NNTensor) -> i32 {
+    as_tensor(tensor).map_or(0, |t| t.size as i32)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_type(tensor: *const NNTensor) -> NNTensorType {
+    as_tensor(tensor).map_or(NNTensorType::RAW, |t| t.tensor_type)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_set_type(tensor: *mut NNTensor, type_: NNTensorType) -> NNError {
+    let Some(tensor) = as_tensor_mut(tensor) else {
+        return NNError::NN_ERROR_INVALID_HANDLE;
+    };
+    let shape = tensor.shape().to_vec();
+    tensor.alloc(type_, &shape)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_mapro(tensor: *mut NNTensor) -> *const c_void {
+    as_tensor_mut(tensor).map_or(ptr::null_mut(), |t| t.map())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_maprw(tensor: *mut NNTensor) -> *mut c_void {
+    as_tensor_mut(tensor).map_or(ptr::null_mut(), |t| t.map())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_mapwo(tensor: *mut NNTensor) -> *mut c_void {
+    as_tensor_mut(tensor).map_or(ptr::null_mut(), |t| t.map())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_unmap(tensor: *mut NNTensor) {
+    if let Some(tensor) = as_tensor_mut(tensor) {
+        tensor.maps = tensor.maps.saturating_sub(1);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_time(tensor: *mut NNTensor) -> i64 {
+    as_tensor_mut(tensor).map_or(0, |t| t.time)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_io_time(_tensor: *mut NNTensor) -> i64 {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_scales(
+    tensor: *const NNTensor,
+    n_scales: *mut usize,
+) -> *const f32 {
+    let Some(tensor) = as_tensor(tensor) else {
+        return ptr::null();
+    };
+    if !n_scales.is_null() {
+        *n_scales = tensor.scales.len();
+    }
+    if tensor.scales.is_empty() {
+        return ptr::null();
+    }
+    tensor.scales.as_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_zeros(
+    tensor: *const NNTensor,
+    n_zeros: *mut usize,
+) -> *const i32 {
+    let Some(tensor) = as_tensor(tensor) else {
+        return ptr::null();
+    };
+    if !n_zeros.is_null() {
+        *n_zeros = tensor.zeros.len();
+    }
+    if tensor.zeros.is_empty() {
+        return ptr::null();
+    }
+    tensor.zeros.as_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_axis(tensor: *const NNTensor) -> c_char {
+    as_tensor(tensor).map_or(-1, |t| t.axis as c_char)
+}
+
+/// The parameters are copied, `own` only tells the real runtime to free them.
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_set_scales(
+    tensor: *mut NNTensor,
+    n_scales: usize,
+    scales: *const f32,
+    _own: c_int,
+) {
+    if let Some(tensor) = as_tensor_mut(tensor) {
+        tensor.scales = if scales.is_null() {
+            Vec::new()
+        } else {
+            std::slice::from_raw_parts(scales, n_scales).to_vec()
+        };
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_set_zeros(
+    tensor: *mut NNTensor,
+    n_zeros: usize,
+    zeros: *const i32,
+    _own: c_int,
+) {
+    if let Some(tensor) = as_tensor_mut(tensor) {
+        tensor.zeros = if zeros.is_null() {
+            Vec::new()
+        } else {
+            std::slice::from_raw_parts(zeros, n_zeros).to_vec()
+        };
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_set_axis(tensor: *mut NNTensor, axis: i32) {
+    if let Some(tensor) = as_tensor_mut(tensor) {
+        tensor.axis = axis;
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_fill(tensor: *mut NNTensor, constant: f64) -> NNError {
+    let Some(tensor) = as_tensor_mut(tensor) else {
+        return NNError::NN_ERROR_INVALID_HANDLE;
+    };
+    if tensor.size == 0 {
+        return NNError::NN_ERROR_TENSOR_NO_DATA;
+    }
+    for index in 0..tensor.volume() {
+        tensor.set(index, constant as f32);
+    }
+    NNError::NN_SUCCESS
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_dequantize(
+    dest: *mut NNTensor,
+    source: *mut NNTensor,
+) -> NNError {
+    let (Some(dest), Some(source)) = (as_tensor_mut(dest), as_tensor(source)) else {
+        return NNError::NN_ERROR_INVALID_HANDLE;
+    };
+    if dest.tensor_type != NNTensorType::F32 {
+        return NNError::NN_ERROR_TENSOR_TYPE_UNSUPPORTED;
+    }
+    if dest.volume() != source.volume() {
+        return NNError::NN_ERROR_SHAPE_MISMATCH;
+    }
+    dest.set_values(&source.values());
+    NNError::NN_SUCCESS
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_set_aux_object(
+    tensor: *mut NNTensor,
+    aux_object: *mut c_void,
+    aux_object_free: nn_aux_object_free,
+) {
+    let Some(inner) = as_tensor_mut(tensor) else {
+        return;
+    };
+    if let Some((_, Some(free))) = inner.aux {
+        free(tensor);
+    }
+    inner.aux = Some((aux_object, aux_object_free));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nn_tensor_aux_object(tensor: *mut NNTensor) -> *mut c_void {
+    as_tensor_mut(tensor).map_or(ptr::null_mut(), |t| t.aux_object())
+}
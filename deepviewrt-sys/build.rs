@@ -1,3 +1,63 @@
+use std::{env, fs, path::PathBuf};
+
+#[cfg(feature = "bindgen")]
+#[path = "build/bindgen.rs"]
+mod bindgen;
+#[path = "build/dynamic.rs"]
+mod dynamic;
+#[path = "build/link.rs"]
+mod link;
+
+/// Pre-generated bindings by runtime version feature, newest first.
+#[cfg(not(feature = "bindgen"))]
+const BINDINGS: &[(&str, &str)] = &[("CARGO_FEATURE_V2_4", "src/bindings/v2_4.rs")];
+
+fn feature(name: &str) -> bool {
+    env::var_os(format!("CARGO_FEATURE_{}", name)).is_some()
+}
+
+#[cfg(not(feature = "bindgen"))]
+fn pregenerated() -> String {
+    let (_, path) = BINDINGS
+        .iter()
+        .find(|(feature, _)| env::var_os(feature).is_some())
+        .expect(
+            "no DeepViewRT bindings selected, enable a runtime version feature such as `v2_4` \
+             or the `bindgen` feature",
+        );
+    println!("cargo:rerun-if-changed={}", path);
+    fs::read_to_string(path).unwrap_or_else(|e| panic!("failed to read {}: {}", path, e))
+}
+
 fn main() {
-    println!("cargo:rustc-link-lib=deepview-rt");
+    for var in link::ENV_VARS {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+    let include = if feature("MOCK") {
+        // The symbols are provided by the deepviewrt-mock crate.
+        link::find_include_dir()
+    } else if feature("DYNAMIC") {
+        if feature("STATIC") {
+            println!("cargo:warning=the `static` feature has no effect with `dynamic`");
+        }
+        link::find_include_dir()
+    } else {
+        link::link()
+    };
+    if let Some(dir) = &include {
+        println!("cargo:include={}", dir.display());
+    }
+
+    #[cfg(feature = "bindgen")]
+    let bindings = bindgen::generate(include.as_deref());
+    #[cfg(not(feature = "bindgen"))]
+    let bindings = pregenerated();
+
+    let bindings = if feature("DYNAMIC") {
+        dynamic::bindings(&bindings)
+    } else {
+        bindings
+    };
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("ffi.rs");
+    fs::write(out, bindings).expect("failed to write ffi.rs");
 }
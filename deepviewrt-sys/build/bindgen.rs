@@ -0,0 +1,58 @@
+use crate::link;
+use bindgen::callbacks::{EnumVariantValue, ParseCallbacks};
+use std::path::{Path, PathBuf};
+
+/// Drops the enum name repeated in variant names, `NNTensorType_F32` becomes
+/// `NNTensorType::F32`.
+#[derive(Debug)]
+struct EnumVariants;
+
+impl ParseCallbacks for EnumVariants {
+    fn enum_variant_name(
+        &self,
+        enum_name: Option<&str>,
+        original_variant_name: &str,
+        _variant_value: EnumVariantValue,
+    ) -> Option<String> {
+        let enum_name = enum_name?.trim_start_matches("enum ");
+        original_variant_name
+            .strip_prefix(enum_name)
+            .and_then(|name| name.strip_prefix('_'))
+            .map(String::from)
+    }
+}
+
+/// Generates bindings from the installed header in `include`, or the header
+/// bundled with this crate when none was found.
+pub fn generate(include: Option<&Path>) -> String {
+    let header = match include {
+        Some(dir) => dir.join(link::HEADER),
+        None => {
+            println!(
+                "cargo:warning=no installed {} found, generating bindings from the bundled copy",
+                link::HEADER
+            );
+            PathBuf::from(link::HEADER)
+        }
+    };
+    println!("cargo:rerun-if-changed={}", header.display());
+
+    let mut builder = bindgen::Builder::default()
+        .header(header.to_string_lossy())
+        .allowlist_function("nn_.*")
+        .newtype_enum("NN.*")
+        .parse_callbacks(Box::new(EnumVariants));
+    if let Some(sysroot) = link::sysroot() {
+        builder = builder.clang_arg(format!("--sysroot={}", sysroot.display()));
+    }
+    builder
+        .generate()
+        .unwrap_or_else(|e| {
+            panic!(
+                "failed to generate bindings from {}: {}",
+                header.display(),
+                e
+            )
+        })
+        .to_string()
+}
@@ -0,0 +1,166 @@
+/// A function declared in an `extern "C"` block of the bindings.
+struct Function {
+    attrs: Vec<String>,
+    name: String,
+    params: Vec<(String, String)>,
+    ret: String,
+}
+
+impl Function {
+    /// The runtime version from the `@since` tag of the documentation, as
+    /// the `Option<[u32; 3]>` expression emitted in `DYNAMIC_SYMBOLS`.
+    fn since(&self) -> String {
+        let version = self.attrs.iter().find_map(|attr| {
+            let rest = &attr[attr.find("@since ")? + "@since ".len()..];
+            let end = rest
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(rest.len());
+            let mut parts = rest[..end]
+                .split('.')
+                .filter(|p| !p.is_empty())
+                .map(|p| p.parse::<u32>());
+            let major = parts.next()?.ok()?;
+            let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+            let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+            Some(format!("Some([{}, {}, {}])", major, minor, patch))
+        });
+        version.unwrap_or_else(|| String::from("None"))
+    }
+}
+
+/// Splits `s` on commas which are not nested in brackets.
+fn split_params(s: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    for c in s.chars() {
+        match c {
+            '(' | '<' | '[' => depth += 1,
+            ')' | '>' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_string());
+    }
+    parts
+}
+
+/// Parses a declaration such as `pub fn name(a: A, b: B) -> R;`, returns
+/// `None` for variadic functions which cannot be forwarded.
+fn parse_function(attrs: Vec<String>, decl: &str) -> Option<Function> {
+    let decl = decl.trim().strip_prefix("pub fn ")?.trim_end_matches(';');
+    let open = decl.find('(')?;
+    let name = decl[..open].trim().to_string();
+    let mut depth = 0;
+    let close = decl[open..].char_indices().find_map(|(i, c)| {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        (depth == 0).then_some(open + i)
+    })?;
+    let mut params = Vec::new();
+    for param in split_params(&decl[open + 1..close]) {
+        if param == "..." {
+            return None;
+        }
+        let (name, ty) = param.split_once(':')?;
+        params.push((name.trim().to_string(), ty.trim().to_string()));
+    }
+    let ret = decl[close + 1..].trim().to_string();
+    Some(Function {
+        attrs,
+        name,
+        params,
+        ret,
+    })
+}
+
+/// Rewrites the `extern "C"` blocks of bindgen output into wrappers calling
+/// symbols resolved by `crate::dynamic`, every other item is kept as is.
+pub fn bindings(bindings: &str) -> String {
+    let mut out = String::new();
+    let mut functions = Vec::new();
+    let mut lines = bindings.lines();
+    while let Some(line) = lines.next() {
+        if line.trim() != "extern \"C\" {" {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        }
+        let mut attrs = Vec::new();
+        let mut decl = String::new();
+        for line in lines.by_ref() {
+            let line = line.trim();
+            if line == "}" {
+                break;
+            } else if line.starts_with("#[") {
+                attrs.push(line.to_string());
+            } else {
+                decl.push_str(line);
+                if !line.ends_with('(') {
+                    decl.push(' ');
+                }
+                if line.ends_with(';') {
+                    functions.extend(parse_function(std::mem::take(&mut attrs), &decl));
+                    decl.clear();
+                }
+            }
+        }
+    }
+
+    out.push_str("\n/// Names of the functions resolved when the library is loaded, with the\n");
+    out.push_str("/// runtime version which introduced them.\n");
+    out.push_str("pub(crate) static DYNAMIC_SYMBOLS: &[(&str, Option<[u32; 3]>)] = &[\n");
+    for function in &functions {
+        out.push_str(&format!(
+            "    (\"{}\", {}),\n",
+            function.name,
+            function.since()
+        ));
+    }
+    out.push_str("];\n");
+
+    for (index, function) in functions.iter().enumerate() {
+        let params: Vec<String> = function
+            .params
+            .iter()
+            .map(|(name, ty)| format!("{}: {}", name, ty))
+            .collect();
+        let types: Vec<&str> = function.params.iter().map(|(_, ty)| ty.as_str()).collect();
+        let names: Vec<&str> = function.params.iter().map(|(n, _)| n.as_str()).collect();
+        let ret = if function.ret.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", function.ret)
+        };
+        out.push('\n');
+        for attr in &function.attrs {
+            out.push_str(attr);
+            out.push('\n');
+        }
+        out.push_str("#[allow(clippy::missing_safety_doc, clippy::too_many_arguments)]\n");
+        out.push_str(&format!(
+            "pub unsafe fn {}({}){} {{\n",
+            function.name,
+            params.join(", "),
+            ret
+        ));
+        out.push_str(&format!(
+            "    let f: unsafe extern \"C\" fn({}){} = ::std::mem::transmute(crate::dynamic::symbol({}));\n",
+            types.join(", "),
+            ret,
+            index
+        ));
+        out.push_str(&format!("    f({})\n}}\n", names.join(", ")));
+    }
+    out
+}
@@ -0,0 +1,192 @@
+use std::{env, path::PathBuf};
+
+/// Directory holding the DeepViewRT library, takes precedence over pkg-config.
+const LIB_DIR_VAR: &str = "DEEPVIEWRT_LIB_DIR";
+/// Directory holding the DeepViewRT headers.
+const INCLUDE_DIR_VAR: &str = "DEEPVIEWRT_INCLUDE_DIR";
+/// Root of the target filesystem searched when cross-compiling.
+const SYSROOT_VAR: &str = "DEEPVIEWRT_SYSROOT";
+
+pub const HEADER: &str = "deepview_rt.h";
+
+/// Environment variables which change the outcome of [`link`].
+pub const ENV_VARS: &[&str] = &[
+    LIB_DIR_VAR,
+    INCLUDE_DIR_VAR,
+    SYSROOT_VAR,
+    "PKG_CONFIG_SYSROOT_DIR",
+];
+
+fn var(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+fn cargo_var(name: &str) -> String {
+    env::var(name).unwrap_or_default()
+}
+
+struct Target {
+    triple: String,
+    os: String,
+    arch: String,
+    cross: bool,
+    statik: bool,
+}
+
+impl Target {
+    fn from_env() -> Self {
+        let triple = cargo_var("TARGET");
+        Target {
+            cross: triple != cargo_var("HOST"),
+            triple,
+            os: cargo_var("CARGO_CFG_TARGET_OS"),
+            arch: cargo_var("CARGO_CFG_TARGET_ARCH"),
+            statik: env::var_os("CARGO_FEATURE_STATIC").is_some(),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        if self.statik {
+            "static"
+        } else {
+            "dylib"
+        }
+    }
+
+    fn library_file(&self) -> &'static str {
+        match (self.os.as_str(), self.statik) {
+            ("windows", _) => "deepview-rt.lib",
+            (_, true) => "libdeepview-rt.a",
+            ("macos", false) => "libdeepview-rt.dylib",
+            (_, false) => "libdeepview-rt.so",
+        }
+    }
+
+    /// Debian multiarch directory name of the target.
+    fn multiarch(&self) -> Option<&'static str> {
+        match self.arch.as_str() {
+            "aarch64" => Some("aarch64-linux-gnu"),
+            "arm" if self.triple.ends_with("eabihf") => Some("arm-linux-gnueabihf"),
+            "arm" => Some("arm-linux-gnueabi"),
+            "x86_64" => Some("x86_64-linux-gnu"),
+            _ => None,
+        }
+    }
+
+    /// The sysroot to search, the host root unless cross-compiling.
+    fn sysroot(&self) -> Option<PathBuf> {
+        var(SYSROOT_VAR)
+            .or_else(|| var("PKG_CONFIG_SYSROOT_DIR"))
+            .or_else(|| (!self.cross).then(|| PathBuf::from("/")))
+    }
+
+    fn search_dirs(&self) -> Vec<PathBuf> {
+        let Some(sysroot) = self.sysroot() else {
+            return Vec::new();
+        };
+        let mut dirs = Vec::new();
+        for prefix in ["usr/local/lib", "usr/lib", "lib"] {
+            if let Some(multiarch) = self.multiarch() {
+                dirs.push(sysroot.join(prefix).join(multiarch));
+            }
+            dirs.push(sysroot.join(prefix));
+        }
+        dirs.push(sysroot.join("usr/lib64"));
+        dirs
+    }
+}
+
+/// `DEEPVIEWRT_INCLUDE_DIR`, otherwise `dir` when it holds the header.
+fn include_dir(dir: Option<PathBuf>) -> Option<PathBuf> {
+    var(INCLUDE_DIR_VAR).or_else(|| dir.filter(|d| d.join(HEADER).exists()))
+}
+
+/// The sysroot given for a cross-compilation.
+#[cfg(feature = "bindgen")]
+pub fn sysroot() -> Option<PathBuf> {
+    let target = Target::from_env();
+    target.sysroot().filter(|_| target.cross)
+}
+
+/// Finds the header directory without looking for the library.
+pub fn find_include_dir() -> Option<PathBuf> {
+    let sysroot = Target::from_env().sysroot();
+    include_dir(sysroot.and_then(|sysroot| {
+        ["usr/local/include", "usr/include"]
+            .iter()
+            .map(|dir| sysroot.join(dir))
+            .find(|dir| dir.join(HEADER).exists())
+    }))
+}
+
+fn emit_link(target: &Target, dir: &std::path::Path) {
+    println!("cargo:rustc-link-search=native={}", dir.display());
+    println!("cargo:rustc-link-lib={}=deepview-rt", target.kind());
+}
+
+/// Finds the DeepViewRT library and emits the link directives, in order from
+/// `DEEPVIEWRT_LIB_DIR`, pkg-config and the standard directories of the
+/// target sysroot.  Returns the header directory found along the library.
+pub fn link() -> Option<PathBuf> {
+    let target = Target::from_env();
+    let file = target.library_file();
+
+    if let Some(dir) = var(LIB_DIR_VAR) {
+        if !dir.join(file).exists() {
+            panic!(
+                "\n{}={} does not contain {}, point it at the directory holding the DeepViewRT \
+                 library for {}.\n",
+                LIB_DIR_VAR,
+                dir.display(),
+                file,
+                target.triple
+            );
+        }
+        emit_link(&target, &dir);
+        return include_dir(dir.parent().map(|p| p.join("include")));
+    }
+
+    let pkg_config = pkg_config::Config::new()
+        .statik(target.statik)
+        .probe("deepview-rt");
+    let pkg_config_error = match pkg_config {
+        Ok(library) => return include_dir(library.include_paths.first().cloned()),
+        Err(e) => e.to_string(),
+    };
+
+    let dirs = target.search_dirs();
+    if let Some(dir) = dirs.iter().find(|dir| dir.join(file).exists()) {
+        emit_link(&target, dir);
+        let include = dir
+            .ancestors()
+            .find_map(|p| include_dir(Some(p.join("include"))));
+        return include.or_else(|| include_dir(None));
+    }
+
+    let searched: Vec<String> = dirs.iter().map(|d| d.display().to_string()).collect();
+    let message = format!(
+        "could not find {} for {}.\n\
+         Install the DeepViewRT runtime, set {} to the directory holding {}, set {} to the \
+         target sysroot when cross-compiling, enable the `dynamic` feature to load the \
+         library at runtime, or the `mock` feature to build without it.\n\
+         searched: {}\n\
+         pkg-config: {}",
+        file,
+        target.triple,
+        LIB_DIR_VAR,
+        file,
+        SYSROOT_VAR,
+        if searched.is_empty() {
+            String::from("no sysroot")
+        } else {
+            searched.join(", ")
+        },
+        pkg_config_error
+            .lines()
+            .find(|l| !l.trim().is_empty())
+            .unwrap_or_default()
+    );
+    panic!("\n{}\n", message);
+}
@@ -357,98 +357,190 @@ fn bindgen_test_layout__IO_FILE() {
         )
     );
 }
-#[doc = " Successfull operation, no error."]
-pub const NNError_NN_SUCCESS: NNError = 0;
-#[doc = " Internal error without a specific error code, catch-all error."]
-pub const NNError_NN_ERROR_INTERNAL: NNError = 1;
-#[doc = " The provided handle is invalid.  This error is typically used by NNEngine\n when interfacing with another API such as OpenCL or OpenVX which require\n native handles for their internal API."]
-pub const NNError_NN_ERROR_INVALID_HANDLE: NNError = 2;
-#[doc = " Out of memory error, returned if a call to malloc returns NULL or similar\n error from an underlying engine plugin."]
-pub const NNError_NN_ERROR_OUT_OF_MEMORY: NNError = 3;
-#[doc = " Out of resources errors are similar to out of memory though sometimes\n treated separately by underlying engine plugins."]
-pub const NNError_NN_ERROR_OUT_OF_RESOURCES: NNError = 4;
-#[doc = " Signals an API has not been implemented.  Can be caught by the core\n DeepViewRT library when interfacing with engine plugins to gracefully\n fallback to the native implementation."]
-pub const NNError_NN_ERROR_NOT_IMPLEMENTED: NNError = 5;
-#[doc = " A required parameter was missing or NULL or simply invalid."]
-pub const NNError_NN_ERROR_INVALID_PARAMETER: NNError = 6;
-#[doc = " When attempting to run an operation where the input/output tensors are\n of different types and the operation does not support automatic type\n conversions."]
-pub const NNError_NN_ERROR_TYPE_MISMATCH: NNError = 7;
-#[doc = " When attempting to run an operation and the input/output tensors have\n invalid or unsupported shape combinations.  Some operations require the\n shapes to be the same while others, such as arithmetic broadcasting\n operations, will support various shape combinations but if the provided\n pairs are invalid then the shape mismatch is returned."]
-pub const NNError_NN_ERROR_SHAPE_MISMATCH: NNError = 8;
-#[doc = " The tensor's shape is invalid for the given operation.  It differs from\n the shape mismatch in that the shape is invalid on its own and not\n relative to another related tensor.  An example would be a shape with\n more than one -1 dimension."]
-pub const NNError_NN_ERROR_INVALID_SHAPE: NNError = 9;
-#[doc = " The requested ordering was invalid."]
-pub const NNError_NN_ERROR_INVALID_ORDER: NNError = 10;
-#[doc = " The requested axis for an operation was invalid or unsupported."]
-pub const NNError_NN_ERROR_INVALID_AXIS: NNError = 11;
-#[doc = " A required resource was missing or the reference invalid."]
-pub const NNError_NN_ERROR_MISSING_RESOURCE: NNError = 12;
-#[doc = " The requested engine is invalid."]
-pub const NNError_NN_ERROR_INVALID_ENGINE: NNError = 13;
-#[doc = " The tensor has no data or the data is not currently accessible.  An\n example of the latter would be attempting to call @ref nn_tensor_maprw\n while the tensor was already mapped read-only or write-only."]
-pub const NNError_NN_ERROR_TENSOR_NO_DATA: NNError = 14;
-#[doc = " The internal kernel or subroutine required to complete an operation using\n the engine plugin was missing.  An example would be OpenCL or OpenVX\n operation where the kernel implementation cannot be located."]
-pub const NNError_NN_ERROR_KERNEL_MISSING: NNError = 15;
-#[doc = " The operation does not support the tensor's type."]
-pub const NNError_NN_ERROR_TENSOR_TYPE_UNSUPPORTED: NNError = 16;
-#[doc = " For operations which can operate on an array of inputs, the provided list\n of inputs was too large."]
-pub const NNError_NN_ERROR_TOO_MANY_INPUTS: NNError = 17;
-#[doc = " A system error occured when interfacing with an operating system\n function.  On some systems errno might be updated with the underlying\n error code."]
-pub const NNError_NN_ERROR_SYSTEM_ERROR: NNError = 18;
-#[doc = " When working with a model a reference was made to a layer which did not\n exist."]
-pub const NNError_NN_ERROR_INVALID_LAYER: NNError = 19;
-#[doc = " The model is invalid or corrupted."]
-pub const NNError_NN_ERROR_MODEL_INVALID: NNError = 20;
-#[doc = " An operation referenced a model but the model was not provided."]
-pub const NNError_NN_ERROR_MODEL_MISSING: NNError = 21;
-#[doc = " The string was too large."]
-pub const NNError_NN_ERROR_STRING_TOO_LARGE: NNError = 22;
-#[doc = " The quantization parameters are invalid."]
-pub const NNError_NN_ERROR_INVALID_QUANT: NNError = 23;
-#[doc = " Failed to generate graph representation of model."]
-pub const NNError_NN_ERROR_MODEL_GRAPH_FAILED: NNError = 24;
-#[doc = " Failed to verify graph generateed from model."]
-pub const NNError_NN_ERROR_GRAPH_VERIFY_FAILED: NNError = 25;
+impl NNError {
+    #[doc = " Successfull operation, no error."]
+    pub const NN_SUCCESS: NNError = NNError(0);
+}
+impl NNError {
+    #[doc = " Internal error without a specific error code, catch-all error."]
+    pub const NN_ERROR_INTERNAL: NNError = NNError(1);
+}
+impl NNError {
+    #[doc = " The provided handle is invalid.  This error is typically used by NNEngine\n when interfacing with another API such as OpenCL or OpenVX which require\n native handles for their internal API."]
+    pub const NN_ERROR_INVALID_HANDLE: NNError = NNError(2);
+}
+impl NNError {
+    #[doc = " Out of memory error, returned if a call to malloc returns NULL or similar\n error from an underlying engine plugin."]
+    pub const NN_ERROR_OUT_OF_MEMORY: NNError = NNError(3);
+}
+impl NNError {
+    #[doc = " Out of resources errors are similar to out of memory though sometimes\n treated separately by underlying engine plugins."]
+    pub const NN_ERROR_OUT_OF_RESOURCES: NNError = NNError(4);
+}
+impl NNError {
+    #[doc = " Signals an API has not been implemented.  Can be caught by the core\n DeepViewRT library when interfacing with engine plugins to gracefully\n fallback to the native implementation."]
+    pub const NN_ERROR_NOT_IMPLEMENTED: NNError = NNError(5);
+}
+impl NNError {
+    #[doc = " A required parameter was missing or NULL or simply invalid."]
+    pub const NN_ERROR_INVALID_PARAMETER: NNError = NNError(6);
+}
+impl NNError {
+    #[doc = " When attempting to run an operation where the input/output tensors are\n of different types and the operation does not support automatic type\n conversions."]
+    pub const NN_ERROR_TYPE_MISMATCH: NNError = NNError(7);
+}
+impl NNError {
+    #[doc = " When attempting to run an operation and the input/output tensors have\n invalid or unsupported shape combinations.  Some operations require the\n shapes to be the same while others, such as arithmetic broadcasting\n operations, will support various shape combinations but if the provided\n pairs are invalid then the shape mismatch is returned."]
+    pub const NN_ERROR_SHAPE_MISMATCH: NNError = NNError(8);
+}
+impl NNError {
+    #[doc = " The tensor's shape is invalid for the given operation.  It differs from\n the shape mismatch in that the shape is invalid on its own and not\n relative to another related tensor.  An example would be a shape with\n more than one -1 dimension."]
+    pub const NN_ERROR_INVALID_SHAPE: NNError = NNError(9);
+}
+impl NNError {
+    #[doc = " The requested ordering was invalid."]
+    pub const NN_ERROR_INVALID_ORDER: NNError = NNError(10);
+}
+impl NNError {
+    #[doc = " The requested axis for an operation was invalid or unsupported."]
+    pub const NN_ERROR_INVALID_AXIS: NNError = NNError(11);
+}
+impl NNError {
+    #[doc = " A required resource was missing or the reference invalid."]
+    pub const NN_ERROR_MISSING_RESOURCE: NNError = NNError(12);
+}
+impl NNError {
+    #[doc = " The requested engine is invalid."]
+    pub const NN_ERROR_INVALID_ENGINE: NNError = NNError(13);
+}
+impl NNError {
+    #[doc = " The tensor has no data or the data is not currently accessible.  An\n example of the latter would be attempting to call @ref nn_tensor_maprw\n while the tensor was already mapped read-only or write-only."]
+    pub const NN_ERROR_TENSOR_NO_DATA: NNError = NNError(14);
+}
+impl NNError {
+    #[doc = " The internal kernel or subroutine required to complete an operation using\n the engine plugin was missing.  An example would be OpenCL or OpenVX\n operation where the kernel implementation cannot be located."]
+    pub const NN_ERROR_KERNEL_MISSING: NNError = NNError(15);
+}
+impl NNError {
+    #[doc = " The operation does not support the tensor's type."]
+    pub const NN_ERROR_TENSOR_TYPE_UNSUPPORTED: NNError = NNError(16);
+}
+impl NNError {
+    #[doc = " For operations which can operate on an array of inputs, the provided list\n of inputs was too large."]
+    pub const NN_ERROR_TOO_MANY_INPUTS: NNError = NNError(17);
+}
+impl NNError {
+    #[doc = " A system error occured when interfacing with an operating system\n function.  On some systems errno might be updated with the underlying\n error code."]
+    pub const NN_ERROR_SYSTEM_ERROR: NNError = NNError(18);
+}
+impl NNError {
+    #[doc = " When working with a model a reference was made to a layer which did not\n exist."]
+    pub const NN_ERROR_INVALID_LAYER: NNError = NNError(19);
+}
+impl NNError {
+    #[doc = " The model is invalid or corrupted."]
+    pub const NN_ERROR_MODEL_INVALID: NNError = NNError(20);
+}
+impl NNError {
+    #[doc = " An operation referenced a model but the model was not provided."]
+    pub const NN_ERROR_MODEL_MISSING: NNError = NNError(21);
+}
+impl NNError {
+    #[doc = " The string was too large."]
+    pub const NN_ERROR_STRING_TOO_LARGE: NNError = NNError(22);
+}
+impl NNError {
+    #[doc = " The quantization parameters are invalid."]
+    pub const NN_ERROR_INVALID_QUANT: NNError = NNError(23);
+}
+impl NNError {
+    #[doc = " Failed to generate graph representation of model."]
+    pub const NN_ERROR_MODEL_GRAPH_FAILED: NNError = NNError(24);
+}
+impl NNError {
+    #[doc = " Failed to verify graph generateed from model."]
+    pub const NN_ERROR_GRAPH_VERIFY_FAILED: NNError = NNError(25);
+}
+#[repr(transparent)]
 #[doc = " Enumeration of all errors provided by DeepViewRT.  Most functions will\n return an NNError with NN_SUCCESS being zero. A common usage pattern for\n client code is to check for err using `if (err) ...` as any error condition\n will return non-zero."]
-pub type NNError = ::std::os::raw::c_uint;
-#[doc = " Raw byte-stream tensor, useful for encoded tensors such as PNG images.\n The size of this tensor would be in bytes."]
-pub const NNTensorType_NNTensorType_RAW: NNTensorType = 0;
-#[doc = " String tensor data, a single dimension would hold one null-terminated\n string of variable length.  A standard C char* array."]
-pub const NNTensorType_NNTensorType_STR: NNTensorType = 1;
-#[doc = " Signed 8-bit integer tensor data internally @ref int8_t"]
-pub const NNTensorType_NNTensorType_I8: NNTensorType = 2;
-#[doc = " Unsigned 8-bit integer tensor data internally @ref uint8_t"]
-pub const NNTensorType_NNTensorType_U8: NNTensorType = 3;
-#[doc = " Signed 16-bit integer tensor data internally @ref int16_t"]
-pub const NNTensorType_NNTensorType_I16: NNTensorType = 4;
-#[doc = " Unsigned 16-bit integer tensor data internally @ref uint16_t"]
-pub const NNTensorType_NNTensorType_U16: NNTensorType = 5;
-#[doc = " Signed 16-bit integer tensor data internally @ref int32_t"]
-pub const NNTensorType_NNTensorType_I32: NNTensorType = 6;
-#[doc = " Unsigned 16-bit integer tensor data internally @ref uint32_t"]
-pub const NNTensorType_NNTensorType_U32: NNTensorType = 7;
-#[doc = " Signed 16-bit integer tensor data internally @ref int64_t"]
-pub const NNTensorType_NNTensorType_I64: NNTensorType = 8;
-#[doc = " Unsigned 16-bit integer tensor data internally @ref uint64_t"]
-pub const NNTensorType_NNTensorType_U64: NNTensorType = 9;
-#[doc = " Half precision (16-bit) floating point tensor data."]
-pub const NNTensorType_NNTensorType_F16: NNTensorType = 10;
-#[doc = " Single precision (32-bit) floating point tensor data."]
-pub const NNTensorType_NNTensorType_F32: NNTensorType = 11;
-#[doc = " Double precision (64-bit) floating point tensor data."]
-pub const NNTensorType_NNTensorType_F64: NNTensorType = 12;
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct NNError(pub ::std::os::raw::c_uint);
+impl NNTensorType {
+    #[doc = " Raw byte-stream tensor, useful for encoded tensors such as PNG images.\n The size of this tensor would be in bytes."]
+    pub const RAW: NNTensorType = NNTensorType(0);
+}
+impl NNTensorType {
+    #[doc = " String tensor data, a single dimension would hold one null-terminated\n string of variable length.  A standard C char* array."]
+    pub const STR: NNTensorType = NNTensorType(1);
+}
+impl NNTensorType {
+    #[doc = " Signed 8-bit integer tensor data internally @ref int8_t"]
+    pub const I8: NNTensorType = NNTensorType(2);
+}
+impl NNTensorType {
+    #[doc = " Unsigned 8-bit integer tensor data internally @ref uint8_t"]
+    pub const U8: NNTensorType = NNTensorType(3);
+}
+impl NNTensorType {
+    #[doc = " Signed 16-bit integer tensor data internally @ref int16_t"]
+    pub const I16: NNTensorType = NNTensorType(4);
+}
+impl NNTensorType {
+    #[doc = " Unsigned 16-bit integer tensor data internally @ref uint16_t"]
+    pub const U16: NNTensorType = NNTensorType(5);
+}
+impl NNTensorType {
+    #[doc = " Signed 16-bit integer tensor data internally @ref int32_t"]
+    pub const I32: NNTensorType = NNTensorType(6);
+}
+impl NNTensorType {
+    #[doc = " Unsigned 16-bit integer tensor data internally @ref uint32_t"]
+    pub const U32: NNTensorType = NNTensorType(7);
+}
+impl NNTensorType {
+    #[doc = " Signed 16-bit integer tensor data internally @ref int64_t"]
+    pub const I64: NNTensorType = NNTensorType(8);
+}
+impl NNTensorType {
+    #[doc = " Unsigned 16-bit integer tensor data internally @ref uint64_t"]
+    pub const U64: NNTensorType = NNTensorType(9);
+}
+impl NNTensorType {
+    #[doc = " Half precision (16-bit) floating point tensor data."]
+    pub const F16: NNTensorType = NNTensorType(10);
+}
+impl NNTensorType {
+    #[doc = " Single precision (32-bit) floating point tensor data."]
+    pub const F32: NNTensorType = NNTensorType(11);
+}
+impl NNTensorType {
+    #[doc = " Double precision (64-bit) floating point tensor data."]
+    pub const F64: NNTensorType = NNTensorType(12);
+}
+#[repr(transparent)]
 #[doc = " @enum NNTensorType\n Enumeration of the data types supported by NNTensors in DeepViewRT."]
-pub type NNTensorType = ::std::os::raw::c_uint;
-#[doc = " No quantization for tensor."]
-pub const NNQuantizationType_NNQuantizationType_None: NNQuantizationType = 0;
-#[doc = " Affine quantization with parameters applied globally across the tensor.\n\n The scale term is queried from @ref nn_tensor_scales() while the zero\n term is queried from @ref nn_tensor_zeros().\n\n Quantization: \\f$ f(x) = \\frac{x}{scale} + zero \\f$\n\n Dequantization: \\f$ f(x) = (x - zero) * scale \\f$"]
-pub const NNQuantizationType_NNQuantizationType_Affine_PerTensor: NNQuantizationType = 1;
-#[doc = " Affine quantization with separate parameters applied to each channel.\n Also known as per-axis where the axis is always the channel \"C\" axis in\n a NCHW, NHWC, and so-on shaped tensor.\n\n Same equation as @ref NNQuantization_Affine_PerTensor but applied\n per-channel.  The scale and zero_point are vectors of channel length."]
-pub const NNQuantizationType_NNQuantizationType_Affine_PerChannel: NNQuantizationType = 2;
-#[doc = " Quantized using Dynamic Fixed Point."]
-pub const NNQuantizationType_NNQuantizationType_DFP: NNQuantizationType = 3;
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct NNTensorType(pub ::std::os::raw::c_uint);
+impl NNQuantizationType {
+    #[doc = " No quantization for tensor."]
+    pub const None: NNQuantizationType = NNQuantizationType(0);
+}
+impl NNQuantizationType {
+    #[doc = " Affine quantization with parameters applied globally across the tensor.\n\n The scale term is queried from @ref nn_tensor_scales() while the zero\n term is queried from @ref nn_tensor_zeros().\n\n Quantization: \\f$ f(x) = \\frac{x}{scale} + zero \\f$\n\n Dequantization: \\f$ f(x) = (x - zero) * scale \\f$"]
+    pub const Affine_PerTensor: NNQuantizationType = NNQuantizationType(1);
+}
+impl NNQuantizationType {
+    #[doc = " Affine quantization with separate parameters applied to each channel.\n Also known as per-axis where the axis is always the channel \"C\" axis in\n a NCHW, NHWC, and so-on shaped tensor.\n\n Same equation as @ref NNQuantization_Affine_PerTensor but applied\n per-channel.  The scale and zero_point are vectors of channel length."]
+    pub const Affine_PerChannel: NNQuantizationType = NNQuantizationType(2);
+}
+impl NNQuantizationType {
+    #[doc = " Quantized using Dynamic Fixed Point."]
+    pub const DFP: NNQuantizationType = NNQuantizationType(3);
+}
+#[repr(transparent)]
 #[doc = " Enumeration of all quantization type provided by DeepViewRT."]
-pub type NNQuantizationType = ::std::os::raw::c_uint;
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct NNQuantizationType(pub ::std::os::raw::c_uint);
 #[doc = " DeepViewRT library initialization options."]
 pub type NNOptions = isize;
 #[repr(C)]
@@ -0,0 +1,198 @@
+//! Runtime loading of the DeepViewRT library, enabled by the `dynamic`
+//! feature.
+//!
+//! Every `nn_*` function keeps its signature but is resolved from the library
+//! loaded by [`load`], or on first use from the path in `DEEPVIEWRT_LIBRARY`
+//! or the platform default `libdeepview-rt.so`.  Loading fails unless the
+//! library exports every function introduced by the version `nn_version`
+//! reports, so only functions newer than the runtime can be missing.  Use
+//! [`require`] or [`is_available`] before calling those.  Calling a function
+//! whose symbol is missing, or any function when the library failed to load,
+//! panics with the [`LoadError`]; the safe `deepviewrt` API checks both
+//! before its calls.
+//!
+//! `nn_tensor_offsetv` is variadic and cannot be forwarded, it is not
+//! available with this feature.
+
+use crate::DYNAMIC_SYMBOLS;
+use std::{
+    ffi::{CStr, OsStr, OsString},
+    fmt,
+    os::raw::c_char,
+    sync::OnceLock,
+};
+
+/// Environment variable holding the path of the library loaded on first use.
+pub const LIBRARY_VAR: &str = "DEEPVIEWRT_LIBRARY";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    /// The library could not be opened.
+    Library { path: OsString, message: String },
+    /// [`load`] was called after a library was already loaded.
+    AlreadyLoaded,
+    /// The loaded library does not export the symbol.
+    MissingSymbol(&'static str),
+    /// The symbol is not part of these bindings.
+    UnknownSymbol(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Library { path, message } => write!(
+                f,
+                "failed to load DeepViewRT library {}: {}",
+                path.to_string_lossy(),
+                message
+            ),
+            LoadError::AlreadyLoaded => write!(f, "DeepViewRT library is already loaded"),
+            LoadError::MissingSymbol(name) => {
+                write!(
+                    f,
+                    "{} is not provided by the loaded DeepViewRT library",
+                    name
+                )
+            }
+            LoadError::UnknownSymbol(name) => write!(f, "{} is not a DeepViewRT function", name),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+type Symbol = unsafe extern "C" fn();
+
+struct Library {
+    path: OsString,
+    symbols: Vec<Option<Symbol>>,
+    _library: libloading::Library,
+}
+
+static LIBRARY: OnceLock<Result<Library, LoadError>> = OnceLock::new();
+
+fn open(path: OsString) -> Result<Library, LoadError> {
+    let library = unsafe { libloading::Library::new(&path) }.map_err(|e| LoadError::Library {
+        path: path.clone(),
+        message: e.to_string(),
+    })?;
+    let symbols: Vec<Option<Symbol>> = DYNAMIC_SYMBOLS
+        .iter()
+        .map(|(name, _)| {
+            unsafe { library.get::<Symbol>(name.as_bytes()) }
+                .ok()
+                .map(|s| *s)
+        })
+        .collect();
+    let version = runtime_version(&symbols)?;
+    for ((name, since), symbol) in DYNAMIC_SYMBOLS.iter().zip(&symbols) {
+        let expected = match (since, version) {
+            (Some(since), Some(version)) => *since <= version,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if expected && symbol.is_none() {
+            return Err(LoadError::MissingSymbol(name));
+        }
+    }
+    Ok(Library {
+        path,
+        symbols,
+        _library: library,
+    })
+}
+
+/// The version reported by `nn_version` as `[major, minor, patch]`, `None`
+/// when it cannot be parsed.
+fn runtime_version(symbols: &[Option<Symbol>]) -> Result<Option<[u32; 3]>, LoadError> {
+    let index = index("nn_version")?;
+    let symbol = symbols[index].ok_or(LoadError::MissingSymbol(DYNAMIC_SYMBOLS[index].0))?;
+    let nn_version: unsafe extern "C" fn() -> *const c_char =
+        unsafe { std::mem::transmute(symbol) };
+    let version = unsafe { nn_version() };
+    if version.is_null() {
+        return Ok(None);
+    }
+    let version = unsafe { CStr::from_ptr(version) }.to_string_lossy();
+    let core = version.trim().split(['-', '+', ' ']).next().unwrap_or("");
+    let mut parts = core.split('.').map(|p| p.parse::<u32>());
+    let (Some(Ok(major)), Some(Ok(minor))) = (parts.next(), parts.next()) else {
+        return Ok(None);
+    };
+    let Ok(patch) = parts.next().unwrap_or(Ok(0)) else {
+        return Ok(None);
+    };
+    Ok(Some([major, minor, patch]))
+}
+
+fn index(name: &str) -> Result<usize, LoadError> {
+    DYNAMIC_SYMBOLS
+        .iter()
+        .position(|(s, _)| *s == name)
+        .ok_or_else(|| LoadError::UnknownSymbol(name.to_string()))
+}
+
+/// The library loaded on first use when [`load`] was not called.
+pub fn default_library() -> OsString {
+    std::env::var_os(LIBRARY_VAR).unwrap_or_else(|| libloading::library_filename("deepview-rt"))
+}
+
+/// Loads the library at `path`.  Must be called before any `nn_*` function,
+/// a library can only be loaded once per process.
+pub fn load<P: AsRef<OsStr>>(path: P) -> Result<(), LoadError> {
+    let mut opened = false;
+    let library = LIBRARY.get_or_init(|| {
+        opened = true;
+        open(path.as_ref().to_os_string())
+    });
+    match library {
+        Ok(_) if !opened => Err(LoadError::AlreadyLoaded),
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.clone()),
+    }
+}
+
+fn library() -> Result<&'static Library, LoadError> {
+    LIBRARY
+        .get_or_init(|| open(default_library()))
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+/// Returns the error of a library which failed to load, loading the default
+/// library if needed.
+pub fn ensure_loaded() -> Result<(), LoadError> {
+    library().map(|_| ())
+}
+
+/// Path of the loaded library, loading the default library if needed.
+pub fn library_path() -> Result<OsString, LoadError> {
+    Ok(library()?.path.clone())
+}
+
+/// Returns an error unless the loaded library exports the function `name`,
+/// loading the default library if needed.
+pub fn require(name: &str) -> Result<(), LoadError> {
+    let library = library()?;
+    let index = index(name)?;
+    match library.symbols[index] {
+        Some(_) => Ok(()),
+        None => Err(LoadError::MissingSymbol(DYNAMIC_SYMBOLS[index].0)),
+    }
+}
+
+/// Whether the function `name` can be called.
+pub fn is_available(name: &str) -> bool {
+    require(name).is_ok()
+}
+
+/// The address of the function `DYNAMIC_SYMBOLS[index]`.
+pub(crate) fn symbol(index: usize) -> Symbol {
+    let symbol = library().and_then(|library| {
+        library.symbols[index].ok_or(LoadError::MissingSymbol(DYNAMIC_SYMBOLS[index].0))
+    });
+    match symbol {
+        Ok(symbol) => symbol,
+        Err(e) => panic!("{}", e),
+    }
+}
@@ -2,4 +2,10 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
-include!("ffi.rs");
+#[cfg(all(feature = "mock", feature = "dynamic"))]
+compile_error!("the `mock` and `dynamic` features are mutually exclusive");
+
+include!(concat!(env!("OUT_DIR"), "/ffi.rs"));
+
+#[cfg(feature = "dynamic")]
+pub mod dynamic;
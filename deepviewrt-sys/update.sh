@@ -1,3 +1,8 @@
 #!/bin/sh
-
-bindgen --allowlist-function 'nn_.*' deepview-rt.h > src/ffi.rs
+# Regenerates the pre-generated bindings src/bindings/v$1.rs, for example
+# `./update.sh 2_4`, from the header found by the build script.
+set -e
+version=${1:?usage: update.sh MAJOR_MINOR}
+out_dir=$(cargo build --features bindgen --message-format=json |
+    grep -o '"out_dir":"[^"]*deepviewrt-sys-[^"]*"' | tail -n 1 | cut -d '"' -f 4)
+cp "$out_dir/ffi.rs" "src/bindings/v$version.rs"
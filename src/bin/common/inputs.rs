@@ -0,0 +1,157 @@
+//! Reading model inputs from files, shared by the command-line tools.
+
+// Each tool uses part of this module.
+#![allow(dead_code)]
+
+use clap::ValueEnum;
+use deepviewrt::{
+    context::Context,
+    error::Error,
+    npy::Array,
+    tensor::{Tensor, TensorType},
+    transform::{ResizeMode, Transform},
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Resize {
+    Stretch,
+    CenterCrop,
+    Letterbox,
+}
+
+impl From<Resize> for ResizeMode {
+    fn from(resize: Resize) -> Self {
+        match resize {
+            Resize::Stretch => ResizeMode::Stretch,
+            Resize::CenterCrop => ResizeMode::CenterCrop,
+            Resize::Letterbox => ResizeMode::Letterbox,
+        }
+    }
+}
+
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "bmp", "gif", "jpeg", "jpg", "png", "pnm", "tif", "tiff", "webp",
+];
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+#[cfg(feature = "image")]
+fn write_image(tensor: &mut Tensor, path: &Path, resize: Resize) -> Result<Transform, Error> {
+    let image = ::image::open(path).map_err(|e| Error::WrapperError(e.to_string()))?;
+    tensor.write_image(&image, resize.into())
+}
+
+#[cfg(not(feature = "image"))]
+fn write_image(_tensor: &mut Tensor, path: &Path, _resize: Resize) -> Result<Transform, Error> {
+    Err(Error::WrapperError(format!(
+        "{} is an image, rebuild with the `image` feature to read images",
+        path.display()
+    )))
+}
+
+fn write_raw(tensor: &mut Tensor, path: &Path) -> Result<(), Error> {
+    let data = fs::read(path)?;
+    let mut map = tensor.mapwo()?;
+    let bytes = map.as_bytes_mut();
+    if bytes.len() != data.len() {
+        return Err(Error::WrapperError(format!(
+            "{} holds {} bytes but the tensor holds {} bytes",
+            path.display(),
+            data.len(),
+            bytes.len()
+        )));
+    }
+    bytes.copy_from_slice(&data);
+    Ok(())
+}
+
+/// Writes the file at `path` into `tensor`, returning the image transform
+/// when the file is an image.  `.npy` files are copied, images are resized
+/// into the tensor and any other file is copied as raw bytes.
+pub fn write_file(
+    tensor: &mut Tensor,
+    path: &Path,
+    resize: Resize,
+) -> Result<Option<Transform>, Error> {
+    if path.extension().is_some_and(|e| e == "npy") {
+        let mut reader = fs::File::open(path)?;
+        Array::read(&mut reader)?.copy_into(tensor)?;
+        Ok(None)
+    } else if is_image(path) {
+        write_image(tensor, path, resize).map(Some)
+    } else {
+        write_raw(tensor, path).map(|_| None)
+    }
+}
+
+/// Fills a tensor with values in [0, 1) from a fixed seed linear congruential
+/// generator so repeated runs see the same input.
+pub fn fill_noise(tensor: &mut Tensor, seed: u32) -> Result<(), Error> {
+    let mut state = seed.wrapping_mul(2_654_435_761).wrapping_add(1);
+    let volume = tensor.volume().max(0) as usize;
+    let data: Vec<u8> = (0..volume)
+        .flat_map(|_| {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            ((state >> 8) as f32 / (1 << 24) as f32).to_ne_bytes()
+        })
+        .collect();
+    Array {
+        dtype: TensorType::F32,
+        shape: vec![volume],
+        data,
+    }
+    .copy_into(tensor)
+}
+
+/// Pairs each model input with a file from `args`, given as `LAYER=PATH` or
+/// as `PATH` for the next input without a file.  Inputs left without a file
+/// are paired with `None`.
+pub fn assign(context: &Context, args: &[String]) -> Result<Vec<(usize, Option<PathBuf>)>, Error> {
+    let model = context
+        .model()
+        .ok_or_else(|| Error::WrapperError(String::from("context has no model loaded")))?;
+    let mut inputs: Vec<(usize, Option<PathBuf>)> = model
+        .inputs()?
+        .iter()
+        .map(|i| (*i as usize, None))
+        .collect();
+    let mut positional = Vec::new();
+    for arg in args {
+        let named = arg
+            .split_once('=')
+            .and_then(|(name, path)| Some((model.layer_lookup(name).ok()? as usize, path)));
+        match named {
+            Some((index, path)) => match inputs.iter_mut().find(|(i, _)| *i == index) {
+                Some((_, file)) => *file = Some(PathBuf::from(path)),
+                None => {
+                    return Err(Error::WrapperError(format!(
+                        "layer {} is not a model input",
+                        model.layer_name(index).unwrap_or("?")
+                    )))
+                }
+            },
+            None => positional.push(PathBuf::from(arg)),
+        }
+    }
+    let mut free = inputs.iter_mut().filter(|(_, file)| file.is_none());
+    for path in positional {
+        match free.next() {
+            Some((_, file)) => *file = Some(path),
+            None => {
+                return Err(Error::WrapperError(format!(
+                    "{} given but every model input already has a file",
+                    path.display()
+                )))
+            }
+        }
+    }
+    Ok(inputs)
+}
@@ -0,0 +1,191 @@
+use clap::Parser;
+use deepviewrt::{
+    context::Context,
+    engine::Engine,
+    error::Error,
+    profile::{Benchmark, BenchmarkReport, Latency},
+};
+use inputs::Resize;
+use serde_json::{json, Value};
+use std::{fs, path::PathBuf, process::ExitCode};
+
+#[path = "common/inputs.rs"]
+mod inputs;
+
+/// Measures the latency and throughput of a model.
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// Model file (.rtm)
+    model: PathBuf,
+
+    /// Engine plugin, the CPU when omitted
+    #[arg(short, long)]
+    engine: Option<String>,
+
+    /// Input as LAYER=PATH, or PATH for the next model input in order.
+    /// Inputs without a file are filled with deterministic noise
+    #[arg(short, long)]
+    input: Vec<String>,
+
+    /// Iterations run before measuring
+    #[arg(short, long, default_value_t = Benchmark::default().warmup)]
+    warmup: usize,
+
+    /// Measured iterations
+    #[arg(short = 'n', long, default_value_t = Benchmark::default().iterations)]
+    iterations: usize,
+
+    /// Report the time of every layer
+    #[arg(short, long)]
+    profile: bool,
+
+    /// Print JSON instead of text
+    #[arg(long)]
+    json: bool,
+}
+
+fn latency_json(latency: &Latency) -> Value {
+    json!({
+        "min": latency.min,
+        "mean": latency.mean,
+        "p50": latency.p50,
+        "p90": latency.p90,
+        "p99": latency.p99,
+        "max": latency.max,
+    })
+}
+
+fn to_json(model: &str, engine: &str, report: &BenchmarkReport) -> Value {
+    let layers: Vec<Value> = report
+        .layers
+        .iter()
+        .map(|l| json!({"index": l.index, "name": l.name, "type": l.layer_type, "time": l.time}))
+        .collect();
+    json!({
+        "model": model,
+        "engine": engine,
+        "warmup": report.warmup,
+        "iterations": report.iterations,
+        "input_map": latency_json(&report.input_map),
+        "run": latency_json(&report.run),
+        "output_map": latency_json(&report.output_map),
+        "total": latency_json(&report.total),
+        "throughput": report.throughput,
+        "layers": layers,
+    })
+}
+
+fn print_text(model: &str, engine: &str, report: &BenchmarkReport) {
+    println!("model:      {}", model);
+    println!("engine:     {}", engine);
+    println!(
+        "iterations: {} ({} warmup)",
+        report.iterations, report.warmup
+    );
+    println!(
+        "{:<12}{:>10}{:>10}{:>10}{:>10}{:>10}{:>10}",
+        "ms", "min", "mean", "p50", "p90", "p99", "max"
+    );
+    for (name, latency) in [
+        ("input map", &report.input_map),
+        ("run", &report.run),
+        ("output map", &report.output_map),
+        ("total", &report.total),
+    ] {
+        println!(
+            "{:<12}{:>10.3}{:>10.3}{:>10.3}{:>10.3}{:>10.3}{:>10.3}",
+            name, latency.min, latency.mean, latency.p50, latency.p90, latency.p99, latency.max
+        );
+    }
+    println!("throughput: {:.1} inferences/s", report.throughput);
+
+    if report.layers.is_empty() {
+        return;
+    }
+    let total: f64 = report.layers.iter().map(|l| l.time).sum();
+    let name_width = report
+        .layers
+        .iter()
+        .map(|l| l.name.len())
+        .max()
+        .unwrap_or(0);
+    let type_width = report
+        .layers
+        .iter()
+        .map(|l| l.layer_type.len())
+        .max()
+        .unwrap_or(0);
+    println!("layers:");
+    for layer in &report.layers {
+        println!(
+            "  {:>5}  {:<name_width$}  {:<type_width$}  {:>10.3} ms  {:>5.1}%",
+            layer.index,
+            layer.name,
+            layer.layer_type,
+            layer.time,
+            if total > 0.0 {
+                layer.time / total * 100.0
+            } else {
+                0.0
+            },
+            name_width = name_width,
+            type_width = type_width
+        );
+    }
+}
+
+fn run(args: Args) -> Result<(), Error> {
+    let data = fs::read(&args.model)?;
+    let engine = args.engine.as_ref().map(Engine::new).transpose()?;
+    let mut context = Context::with_model(engine, data)?;
+
+    for (n, (index, path)) in inputs::assign(&context, &args.input)?
+        .into_iter()
+        .enumerate()
+    {
+        let tensor = context.tensor_index_mut(index)?;
+        match path {
+            Some(path) => {
+                inputs::write_file(tensor, &path, Resize::Stretch)?;
+            }
+            None => inputs::fill_noise(tensor, n as u32)?,
+        }
+    }
+
+    let benchmark = Benchmark {
+        warmup: args.warmup,
+        iterations: args.iterations,
+        profile: args.profile,
+    };
+    let report = benchmark.run(&context)?;
+
+    let model = context
+        .model()
+        .and_then(|m| m.name().ok())
+        .unwrap_or_default()
+        .to_string();
+    let engine = context
+        .engine()
+        .and_then(|e| e.name())
+        .unwrap_or("cpu")
+        .to_string();
+    if args.json {
+        let json = serde_json::to_string_pretty(&to_json(&model, &engine, &report))
+            .map_err(|e| Error::WrapperError(e.to_string()))?;
+        println!("{}", json);
+    } else {
+        print_text(&model, &engine, &report);
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
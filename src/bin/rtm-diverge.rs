@@ -0,0 +1,86 @@
+use clap::Parser;
+use deepviewrt::{
+    context::Context, divergence::find_divergence, engine::Engine, error::Error, npy::Array,
+    tensor::Tolerance,
+};
+use std::{fs, path::PathBuf, process::ExitCode};
+
+#[path = "common/inputs.rs"]
+mod inputs;
+
+/// Finds the first layer of a model whose output differs between two engines.
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// Model file (.rtm)
+    model: PathBuf,
+
+    /// Engine plugin under test
+    #[arg(short, long)]
+    engine: String,
+
+    /// Engine plugin of the reference, the CPU when omitted
+    #[arg(short, long)]
+    reference_engine: Option<String>,
+
+    /// Input .npy files in model input order, inputs without a file are
+    /// filled with deterministic noise
+    #[arg(short, long)]
+    input: Vec<PathBuf>,
+
+    /// Absolute tolerance
+    #[arg(long, default_value_t = Tolerance::default().abs)]
+    abs: f32,
+
+    /// Relative tolerance
+    #[arg(long, default_value_t = Tolerance::default().rel)]
+    rel: f32,
+}
+
+fn run(args: Args) -> Result<bool, Error> {
+    let data = fs::read(&args.model)?;
+    let reference_engine = args.reference_engine.map(Engine::new).transpose()?;
+    let mut reference = Context::with_model(reference_engine, data.clone())?;
+    let mut candidate = Context::with_model(Some(Engine::new(args.engine)?), data)?;
+
+    let inputs: Vec<usize> = match reference.model() {
+        Some(model) => model.inputs()?.iter().map(|i| *i as usize).collect(),
+        None => Vec::new(),
+    };
+    if args.input.len() > inputs.len() {
+        return Err(Error::WrapperError(format!(
+            "{} input files given but the model has {} inputs",
+            args.input.len(),
+            inputs.len()
+        )));
+    }
+    for (n, index) in inputs.iter().enumerate() {
+        let tensor = reference.tensor_index_mut(*index)?;
+        match args.input.get(n) {
+            Some(path) => {
+                let mut reader = fs::File::open(path)?;
+                Array::read(&mut reader)?.copy_into(tensor)?;
+            }
+            None => inputs::fill_noise(tensor, n as u32)?,
+        }
+    }
+
+    let tolerance = Tolerance {
+        abs: args.abs,
+        rel: args.rel,
+    };
+    let report = find_divergence(&mut reference, &mut candidate, tolerance)?;
+    println!("{}", report);
+    Ok(report.first_divergence().is_none())
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
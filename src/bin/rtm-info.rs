@@ -0,0 +1,165 @@
+use clap::Parser;
+use deepviewrt::{
+    error::Error,
+    model::{LayerInfo, ModelInfo},
+};
+use serde_json::{json, Value};
+use std::{fs, path::PathBuf, process::ExitCode};
+
+/// Prints the inputs, outputs, layers and resources of a model.
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// Model file (.rtm)
+    model: PathBuf,
+
+    /// Print JSON instead of text
+    #[arg(long)]
+    json: bool,
+}
+
+fn layer_json(layer: &LayerInfo) -> Value {
+    json!({
+        "index": layer.index,
+        "name": layer.name,
+        "type": layer.layer_type,
+        "datatype": layer.datatype,
+        "shape": layer.shape,
+        "scales": layer.quant.scales,
+        "zeros": layer.quant.zeros,
+        "axis": layer.quant.axis,
+    })
+}
+
+fn to_json(info: &ModelInfo) -> Value {
+    let resources: Vec<Value> = info
+        .resources
+        .iter()
+        .map(|r| json!({"name": r.name, "mime": r.mime, "meta": r.meta, "size": r.size}))
+        .collect();
+    json!({
+        "name": info.name,
+        "uuid": info.uuid,
+        "serial": info.serial,
+        "labels": info.labels,
+        "inputs": info.inputs,
+        "outputs": info.outputs,
+        "layers": info.layers.iter().map(layer_json).collect::<Vec<_>>(),
+        "memory_size": info.memory_size,
+        "cache_minimum_size": info.cache_minimum_size,
+        "cache_optimum_size": info.cache_optimum_size,
+        "resources": resources,
+    })
+}
+
+/// Formats a list of values, eliding the middle of long per-channel lists.
+fn list<T: ToString>(values: &[T]) -> String {
+    if values.is_empty() {
+        return String::from("-");
+    }
+    let items: Vec<String> = if values.len() > 4 {
+        let mut items: Vec<String> = values[..3].iter().map(T::to_string).collect();
+        items.push(format!("... ({})", values.len()));
+        items
+    } else {
+        values.iter().map(T::to_string).collect()
+    };
+    items.join(",")
+}
+
+fn print_layers(header: &str, info: &ModelInfo, indices: &[usize]) {
+    println!("{}:", header);
+    for layer in indices.iter().filter_map(|i| info.layers.get(*i)) {
+        println!(
+            "  {}: {} {} {:?}",
+            layer.index, layer.name, layer.datatype, layer.shape
+        );
+    }
+}
+
+fn print_text(info: &ModelInfo) {
+    println!("name:      {}", info.name);
+    println!("uuid:      {}", info.uuid);
+    println!("serial:    {}", info.serial);
+    println!("labels:    {}", info.labels.len());
+    for (index, label) in info.labels.iter().enumerate() {
+        println!("  {}: {}", index, label);
+    }
+    println!("memory:    {} bytes", info.memory_size);
+    println!(
+        "cache:     {} bytes minimum, {} bytes optimum",
+        info.cache_minimum_size, info.cache_optimum_size
+    );
+    print_layers("inputs", info, &info.inputs);
+    print_layers("outputs", info, &info.outputs);
+
+    let header = [
+        "index", "name", "type", "datatype", "shape", "scales", "zeros", "axis",
+    ];
+    let rows: Vec<[String; 8]> = info
+        .layers
+        .iter()
+        .map(|layer| {
+            [
+                layer.index.to_string(),
+                layer.name.clone(),
+                layer.layer_type.clone(),
+                layer.datatype.clone(),
+                list(&layer.shape),
+                list(&layer.quant.scales),
+                list(&layer.quant.zeros),
+                layer.quant.axis.to_string(),
+            ]
+        })
+        .collect();
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    println!("layers:    {}", rows.len());
+    let print_row = |cells: Vec<&str>| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        println!("  {}", line.join("  ").trim_end());
+    };
+    print_row(header.to_vec());
+    for row in &rows {
+        print_row(row.iter().map(String::as_str).collect());
+    }
+
+    println!("resources: {}", info.resources.len());
+    for resource in &info.resources {
+        println!(
+            "  {} {} {} bytes {}",
+            resource.name, resource.mime, resource.size, resource.meta
+        );
+    }
+}
+
+fn run(args: Args) -> Result<(), Error> {
+    let data = fs::read(&args.model)?;
+    let info = ModelInfo::from_bytes(&data)?;
+    if args.json {
+        let json = serde_json::to_string_pretty(&to_json(&info))
+            .map_err(|e| Error::WrapperError(e.to_string()))?;
+        println!("{}", json);
+    } else {
+        print_text(&info);
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
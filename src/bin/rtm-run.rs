@@ -0,0 +1,212 @@
+use clap::{Parser, ValueEnum};
+use deepviewrt::{
+    context::Context,
+    engine::Engine,
+    error::Error,
+    postprocess::{
+        classification,
+        detection::{BoxFormat, Decoder, Detector, YoloVersion},
+    },
+    transform::Transform,
+};
+use inputs::Resize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+#[path = "common/inputs.rs"]
+mod inputs;
+
+/// Runs a model on inputs read from files and prints or saves its outputs.
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// Model file (.rtm)
+    model: PathBuf,
+
+    /// Engine plugin, the CPU when omitted
+    #[arg(short, long)]
+    engine: Option<String>,
+
+    /// Input as LAYER=PATH, or PATH for the next model input in order.  .npy
+    /// files are copied into the tensor, images are resized into it and any
+    /// other file is copied as raw bytes
+    #[arg(short, long)]
+    input: Vec<String>,
+
+    /// How images are fitted to the input tensor
+    #[arg(long, value_enum, default_value_t = Resize::Stretch)]
+    resize: Resize,
+
+    /// Print the K highest scoring classes of the first output
+    #[arg(short = 'k', long)]
+    top_k: Option<usize>,
+
+    /// Decode the outputs as detections.  `decoded` reads boxes, scores and
+    /// optionally classes and count from the outputs in that order
+    #[arg(short, long, value_enum)]
+    detect: Option<Layout>,
+
+    /// Minimum score of a detection
+    #[arg(long, default_value_t = 0.25)]
+    score_threshold: f32,
+
+    /// Overlap above which detections are suppressed
+    #[arg(long, default_value_t = 0.45)]
+    iou_threshold: f32,
+
+    /// Write each output to DIR/<layer>.npy
+    #[arg(short, long)]
+    output_dir: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Layout {
+    Yolov5,
+    Yolov8,
+    Decoded,
+}
+
+fn print_detections(
+    args: &Args,
+    layout: Layout,
+    context: &Context,
+    transform: Option<&Transform>,
+) -> Result<(), Error> {
+    let outputs: Vec<usize> = match context.model() {
+        Some(model) => model.outputs()?.iter().map(|i| *i as usize).collect(),
+        None => Vec::new(),
+    };
+    let output = |n: usize| {
+        outputs
+            .get(n)
+            .copied()
+            .ok_or_else(|| Error::WrapperError(format!("model has fewer than {} outputs", n + 1)))
+    };
+    let decoder = match layout {
+        Layout::Yolov5 | Layout::Yolov8 => Decoder::Yolo {
+            output: output(0)?,
+            version: match layout {
+                Layout::Yolov5 => YoloVersion::V5,
+                _ => YoloVersion::V8,
+            },
+            classes: None,
+            normalized: false,
+        },
+        Layout::Decoded => Decoder::Decoded {
+            boxes: output(0)?,
+            scores: output(1)?,
+            classes: outputs.get(2).copied(),
+            count: outputs.get(3).copied(),
+            format: BoxFormat::Yxyx,
+            normalized: true,
+        },
+    };
+    let mut detector = Detector::new(decoder);
+    detector.score_threshold = args.score_threshold;
+    detector.iou_threshold = args.iou_threshold;
+    for detection in detector.detect(context)? {
+        let detection = match transform {
+            Some(transform) => detection.to_source(transform),
+            None => detection,
+        };
+        let [x0, y0, x1, y1] = detection.bbox;
+        println!(
+            "{:.4} {} {} [{:.1}, {:.1}, {:.1}, {:.1}]",
+            detection.score,
+            detection.class,
+            detection.label.as_deref().unwrap_or("-"),
+            x0,
+            y0,
+            x1,
+            y1
+        );
+    }
+    Ok(())
+}
+
+fn save_outputs(context: &Context, dir: &Path) -> Result<(), Error> {
+    let model = context
+        .model()
+        .ok_or_else(|| Error::WrapperError(String::from("context has no model loaded")))?;
+    fs::create_dir_all(dir)?;
+    for index in model.outputs()? {
+        let index = *index as usize;
+        // Layer names may hold path separators.
+        let name = model.layer_name(index)?.replace(['/', '\\'], "_");
+        let path = dir.join(format!("{}.npy", name));
+        context.tensor_index(index)?.save_npy(&path)?;
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+fn run(args: Args) -> Result<(), Error> {
+    let data = fs::read(&args.model)?;
+    let engine = args.engine.as_ref().map(Engine::new).transpose()?;
+    let mut context = Context::with_model(engine, data)?;
+
+    let mut transform = None;
+    for (index, path) in inputs::assign(&context, &args.input)? {
+        let Some(path) = path else {
+            let model = context.model();
+            return Err(Error::WrapperError(format!(
+                "no file given for input {}",
+                model.and_then(|m| m.layer_name(index).ok()).unwrap_or("?")
+            )));
+        };
+        let tensor = context.tensor_index_mut(index)?;
+        if let Some(t) = inputs::write_file(tensor, &path, args.resize)? {
+            transform.get_or_insert(t);
+        }
+    }
+    context.run_model()?;
+
+    if let Some(k) = args.top_k {
+        let output = match context.model() {
+            Some(model) => model.outputs()?.first().copied(),
+            None => None,
+        }
+        .ok_or_else(|| Error::WrapperError(String::from("model has no outputs")))?;
+        for class in classification::top_k(&context, output as usize, k)? {
+            println!(
+                "{:.4} {} {}",
+                class.score,
+                class.index,
+                class.label.as_deref().unwrap_or("-")
+            );
+        }
+    }
+    if let Some(layout) = args.detect {
+        print_detections(&args, layout, &context, transform.as_ref())?;
+    }
+    if let Some(dir) = &args.output_dir {
+        save_outputs(&context, dir)?;
+    }
+    if args.top_k.is_none() && args.detect.is_none() && args.output_dir.is_none() {
+        let model = context
+            .model()
+            .ok_or_else(|| Error::WrapperError(String::from("context has no model loaded")))?;
+        for index in model.outputs()? {
+            let index = *index as usize;
+            println!(
+                "{}: {}",
+                model.layer_name(index)?,
+                context.tensor_index(index)?
+            );
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
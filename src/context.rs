@@ -1,23 +1,52 @@
-use crate::{engine::Engine, error::Error, model::Model, tensor::Tensor};
+use crate::{
+    engine::{Engine, EngineAttempt, EnginePolicy, EngineSelection},
+    error::Error,
+    model::Model,
+    runtime::{self, Capabilities},
+    tensor::Tensor,
+};
 use deepviewrt_sys as ffi;
 use std::{
-    cell::{Cell, RefCell},
+    cell::{Cell, OnceCell, RefCell},
     ffi::CString,
-    ptr,
+    fmt, ptr,
 };
 
+/// A model loaded for inference together with the memory its layers run in.
+///
+/// Contexts are neither `Send` nor `Sync`: a context holds a clone of its
+/// [`Engine`] and caches its model and tensor handles in cells.  Threads each
+/// create and run their own context, several contexts may load the same
+/// model.
+///
+/// The model view is created once per loaded model and never replaced while
+/// borrowed.  Tensor views are boxed so the cache behind [`Context::tensor`]
+/// and [`Context::tensor_index`] may grow without moving them.  References
+/// returned by either stay valid until the model is unloaded, which takes
+/// `&mut self`.
+///
+/// ```compile_fail
+/// fn send<T: Send>() {}
+/// send::<deepviewrt::context::Context>();
+/// ```
+///
+/// ```compile_fail
+/// fn sync<T: Sync>() {}
+/// sync::<deepviewrt::context::Context>();
+/// ```
 pub struct Context {
     owned: bool,
     ptr: *mut ffi::NNContext,
     engine: Cell<Option<Engine>>,
     model_data: Option<Vec<u8>>,
-    model: Cell<Option<Model>>,
-    tensors: RefCell<Vec<(i32, Tensor)>>,
+    model: OnceCell<Model>,
+    tensors: RefCell<Vec<(i32, Box<Tensor>)>>,
 }
 
 impl Context {
-    pub fn sizeof() -> usize {
-        return unsafe { ffi::nn_context_sizeof() };
+    pub fn sizeof() -> Result<usize, Error> {
+        runtime::ensure_loaded()?;
+        Ok(unsafe { ffi::nn_context_sizeof() })
     }
 
     pub fn new(
@@ -25,6 +54,7 @@ impl Context {
         memory_size: usize,
         cache_size: usize,
     ) -> Result<Context, Error> {
+        runtime::ensure_init()?;
         let engine_ptr = if let Some(engine_) = &engine {
             unsafe { engine_.to_ptr_mut() }
         } else {
@@ -44,27 +74,114 @@ impl Context {
                 "nn_context_init returned null",
             )));
         }
-        let tensors_ref: Vec<(i32, Tensor)> = Vec::new();
+        let tensors_ref: Vec<(i32, Box<Tensor>)> = Vec::new();
         let tensors = RefCell::new(tensors_ref);
         Ok(Context {
             owned: true,
             ptr: ret,
             engine: Cell::new(engine),
             model_data: None,
-            model: Cell::new(None),
+            model: OnceCell::new(),
             tensors,
         })
     }
 
-    /*
-    pub fn cache(&self) -> Option<Tensor> {
+    /// Creates a context sized for the model in `data` and loads the model.
+    pub fn with_model(engine: Option<Engine>, data: Vec<u8>) -> Result<Context, Error> {
+        Model::validate(&data)?;
+        let model = unsafe { Model::try_from_ptr(data.as_ptr() as *const ffi::NNModel)? };
+        let memory_size = model.memory_size();
+        let cache_size = model.cache_optimum_size();
+        let mut context = Context::new(engine, memory_size, cache_size)?;
+        context.load_model(data)?;
+        Ok(context)
+    }
 
+    /// Creates a context for the model in `data` on the first engine of
+    /// `policy` which loads the model and completes a warm-up run, the inputs
+    /// are zeroed for the warm-up run.  The selection reports the chosen
+    /// engine and why each engine before it was rejected.
+    pub fn with_policy(
+        policy: &EnginePolicy,
+        data: Vec<u8>,
+    ) -> Result<(Context, EngineSelection), Error> {
+        let mut rejected = Vec::new();
+        for choice in policy.choices() {
+            let engine = match Engine::from_choice(&choice) {
+                Ok(engine) => engine,
+                Err(error) => {
+                    rejected.push(EngineAttempt { choice, error });
+                    continue;
+                }
+            };
+            let name = engine.as_ref().and_then(|e| e.name()).map(String::from);
+            let version = engine.as_ref().and_then(|e| e.version()).map(String::from);
+            let context = Context::with_model(engine, data.clone()).and_then(|context| {
+                context.zero_inputs()?;
+                context.run_model()?;
+                Ok(context)
+            });
+            match context {
+                Ok(context) => {
+                    let selection = EngineSelection {
+                        choice,
+                        name,
+                        version,
+                        rejected,
+                    };
+                    return Ok((context, selection));
+                }
+                Err(error) if EnginePolicy::is_fallback_error(&error) => {
+                    rejected.push(EngineAttempt { choice, error });
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        let reasons: Vec<String> = rejected
+            .iter()
+            .map(|a| format!("{}: {}", a.choice, a.error))
+            .collect();
+        Err(Error::WrapperError(format!(
+            "no engine could run the model ({})",
+            reasons.join(", ")
+        )))
     }
 
-    pub fn mempool(&self) -> Option<Tensor> {
+    fn zero_inputs(&self) -> Result<(), Error> {
+        let inputs: Vec<usize> = match self.model() {
+            Some(model) => model.inputs()?.iter().map(|i| *i as usize).collect(),
+            None => return Ok(()),
+        };
+        for index in inputs {
+            self.tensor_index(index)?.fill(0.0)?;
+        }
+        Ok(())
+    }
 
+    /// The cache tensor used by accelerated engines, not owned by the caller.
+    pub fn cache(&self) -> Option<Tensor> {
+        if !Capabilities::get().context_memory {
+            return None;
+        }
+        let ret = unsafe { ffi::nn_context_cache(self.ptr) };
+        if ret.is_null() {
+            return None;
+        }
+        unsafe { Tensor::from_ptr(ret, false).ok() }
+    }
+
+    /// The memory pool holding the intermediate tensors, not owned by the
+    /// caller.
+    pub fn mempool(&self) -> Option<Tensor> {
+        if !Capabilities::get().context_memory {
+            return None;
+        }
+        let ret = unsafe { ffi::nn_context_mempool(self.ptr) };
+        if ret.is_null() {
+            return None;
+        }
+        unsafe { Tensor::from_ptr(ret, false).ok() }
     }
-    */
 
     pub fn engine(&self) -> Option<&Engine> {
         let engine_ptr = self.engine.as_ptr();
@@ -84,25 +201,23 @@ impl Context {
         return unsafe { (&*self.engine.as_ptr()).as_ref() };
     }
 
+    /// The loaded model.  The view is created on the first call and kept
+    /// until the model is unloaded, so earlier references stay valid.
     pub fn model(&self) -> Option<&Model> {
+        if let Some(model) = self.model.get() {
+            return Some(model);
+        }
         let ret = unsafe { ffi::nn_context_model(self.ptr) };
         if ret.is_null() {
             return None;
         }
-        let model = match unsafe { Model::try_from_ptr(ret) } {
-            Ok(model) => Some(model),
+        match unsafe { Model::try_from_ptr(ret) } {
+            Ok(model) => Some(self.model.get_or_init(|| model)),
             Err(e) => {
                 eprintln!("{}", e);
                 None
             }
-        };
-        self.model.set(model);
-
-        let model_ref = unsafe { &*self.model.as_ptr() };
-        if model_ref.is_some() {
-            return model_ref.as_ref();
         }
-        None
     }
 
     pub fn load_model(&mut self, data: Vec<u8>) -> Result<(), Error> {
@@ -116,7 +231,7 @@ impl Context {
                 model_data_ref.as_ptr() as *const std::ffi::c_void,
             )
         };
-        if ret != ffi::NNError_NN_SUCCESS {
+        if ret != ffi::NNError::NN_SUCCESS {
             return Err(Error::from(ret));
         }
         return Ok(());
@@ -124,13 +239,29 @@ impl Context {
 
     pub fn unload_model(&mut self) {
         unsafe { ffi::nn_context_model_unload(self.ptr) };
-        let tensors_ref: Vec<(i32, Tensor)> = Vec::new();
+        let tensors_ref: Vec<(i32, Box<Tensor>)> = Vec::new();
         self.tensors = RefCell::new(tensors_ref);
         self.model_data = None;
-        self.model.set(None);
+        self.model.take();
+    }
+
+    pub fn run_model(&self) -> Result<(), Error> {
+        let ret = unsafe { ffi::nn_context_run(self.ptr) };
+        if ret != ffi::NNError::NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+        Ok(())
     }
 
-    pub fn run_model(&self) {}
+    /// Runs the single layer `index` of the model.
+    pub fn step(&self, index: usize) -> Result<(), Error> {
+        runtime::require(runtime::CONTEXT_STEP, "nn_context_step")?;
+        let ret = unsafe { ffi::nn_context_step(self.ptr, index) };
+        if ret != ffi::NNError::NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+        Ok(())
+    }
 
     pub fn tensor(&self, name: &str) -> Result<&Tensor, Error> {
         let cname = match CString::new(name) {
@@ -151,7 +282,7 @@ impl Context {
 
         let tensor = unsafe { Tensor::from_ptr(ret, false).unwrap() };
         match self.tensors.try_borrow_mut() {
-            Ok(mut borrowed) => borrowed.push((index, tensor)),
+            Ok(mut borrowed) => borrowed.push((index, Box::new(tensor))),
             Err(e) => {
                 return Err(Error::WrapperError(e.to_string()));
             }
@@ -176,7 +307,7 @@ impl Context {
 
         match self.tensors.try_borrow_mut() {
             Ok(mut borrowed) => {
-                borrowed.push((index as i32, tensor));
+                borrowed.push((index as i32, Box::new(tensor)));
             }
             Err(e) => {
                 return Err(Error::WrapperError(e.to_string()));
@@ -202,7 +333,7 @@ impl Context {
 
         match self.tensors.try_borrow_mut() {
             Ok(mut borrowed) => {
-                borrowed.push((index as i32, tensor));
+                borrowed.push((index as i32, Box::new(tensor)));
             }
             Err(e) => {
                 return Err(Error::WrapperError(e.to_string()));
@@ -224,20 +355,56 @@ impl Context {
             return Err(Error::WrapperError(String::from("ptr is null")));
         }
 
-        let tensors_ref: Vec<(i32, Tensor)> = Vec::new();
+        let tensors_ref: Vec<(i32, Box<Tensor>)> = Vec::new();
         let tensors = RefCell::new(tensors_ref);
         return Ok(Self {
             owned: false,
             ptr,
             engine: Cell::new(None),
             model_data: None,
-            model: Cell::new(None),
+            model: OnceCell::new(),
             tensors,
             //tensors_ref
         });
     }
 }
 
+impl fmt::Debug for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let engine = self
+            .engine()
+            .map(|e| (e.name().unwrap_or("?"), e.version().unwrap_or("?")));
+        f.debug_struct("Context")
+            .field("owned", &self.owned)
+            .field("engine", &engine)
+            .field("model", &self.model().and_then(|m| m.name().ok()))
+            .field("mempool", &self.mempool().map(|t| t.size()))
+            .field("cache", &self.cache().map(|t| t.size()))
+            .finish()
+    }
+}
+
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.engine() {
+            Some(engine) => write!(
+                f,
+                "engine: {} {}",
+                engine.name().unwrap_or("?"),
+                engine.version().unwrap_or("?")
+            )?,
+            None => write!(f, "engine: cpu")?,
+        }
+        match self.model() {
+            Some(model) => write!(f, "\nmodel: {}", model.name().unwrap_or("<unnamed>"))?,
+            None => write!(f, "\nmodel: none")?,
+        }
+        let mempool = self.mempool().map_or(0, |t| t.size());
+        let cache = self.cache().map_or(0, |t| t.size());
+        write!(f, "\nmemory: {} bytes, cache: {} bytes", mempool, cache)
+    }
+}
+
 impl Drop for Context {
     fn drop(&mut self) {
         if self.owned {
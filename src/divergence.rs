@@ -0,0 +1,130 @@
+use crate::{
+    context::Context,
+    error::Error,
+    npy::Array,
+    tensor::{ComparisonReport, TensorType, Tolerance},
+};
+use std::fmt;
+
+/// Comparison of one layer output between the reference and candidate
+/// contexts.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayerError {
+    pub layer: usize,
+    pub name: String,
+    pub layer_type: String,
+    pub report: ComparisonReport,
+}
+
+/// Per-layer errors of a candidate context against a reference context.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DivergenceReport {
+    pub tolerance: Tolerance,
+    pub layers: Vec<LayerError>,
+}
+
+impl DivergenceReport {
+    /// The first layer, in execution order, whose output exceeds the
+    /// tolerance.
+    pub fn first_divergence(&self) -> Option<&LayerError> {
+        self.layers.iter().find(|l| !l.report.is_match())
+    }
+}
+
+impl fmt::Display for DivergenceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>5}  {:<32} {:<16} {:>12} {:>12} {:>10} {:>8}",
+            "layer", "name", "type", "max abs", "mean abs", "mismatch", "cosine"
+        )?;
+        for layer in &self.layers {
+            let report = &layer.report;
+            writeln!(
+                f,
+                "{:>5}  {:<32} {:<16} {:>12.6e} {:>12.6e} {:>10} {:>8.5}{}",
+                layer.layer,
+                layer.name,
+                layer.layer_type,
+                report.max_abs_error,
+                report.mean_abs_error,
+                report.mismatches,
+                report.cosine_similarity,
+                if report.is_match() { "" } else { "  *" }
+            )?;
+        }
+        match self.first_divergence() {
+            Some(layer) => write!(
+                f,
+                "first divergence at layer {} {} ({})",
+                layer.layer, layer.name, layer.layer_type
+            ),
+            None => write!(f, "no layer exceeds the tolerance"),
+        }
+    }
+}
+
+/// Steps the same model through a `reference` and a `candidate` context, for
+/// example the CPU and an accelerator engine, and compares the output of every
+/// layer.
+///
+/// The inputs of `reference` must be filled by the caller, they are copied
+/// into `candidate` so both run on identical data.  Both tensors are
+/// dequantized before comparison so engines may use different tensor types.
+pub fn find_divergence(
+    reference: &mut Context,
+    candidate: &mut Context,
+    tolerance: Tolerance,
+) -> Result<DivergenceReport, Error> {
+    let no_model = || Error::WrapperError(String::from("no model loaded"));
+    let model = reference.model().ok_or_else(no_model)?;
+    let layer_count = model.layer_count();
+    let inputs: Vec<usize> = model.inputs()?.iter().map(|i| *i as usize).collect();
+    let layers: Vec<(String, String)> = (0..layer_count)
+        .map(|index| {
+            (
+                model.layer_name(index).unwrap_or_default().to_string(),
+                model.layer_type(index).unwrap_or_default().to_string(),
+            )
+        })
+        .collect();
+    if candidate.model().ok_or_else(no_model)?.layer_count() != layer_count {
+        return Err(Error::WrapperError(String::from(
+            "reference and candidate contexts hold different models",
+        )));
+    }
+
+    for index in inputs {
+        let array = Array::from_tensor(reference.tensor_index(index)?)?;
+        array.copy_into(candidate.tensor_index_mut(index)?)?;
+    }
+
+    let mut report = DivergenceReport {
+        tolerance,
+        layers: Vec::with_capacity(layer_count),
+    };
+    for (index, (name, layer_type)) in layers.into_iter().enumerate() {
+        reference.step(index)?;
+        candidate.step(index)?;
+        let expected = reference.tensor_index(index)?;
+        let actual = candidate.tensor_index(index)?;
+        // Layers without numeric outputs, such as string constants, are
+        // skipped.
+        if [expected.tensor_type(), actual.tensor_type()]
+            .iter()
+            .any(|t| matches!(t, TensorType::STR | TensorType::RAW))
+        {
+            continue;
+        }
+        let layer_report = actual.compare(expected, tolerance)?;
+        report.layers.push(LayerError {
+            layer: index,
+            name,
+            layer_type,
+            report: layer_report,
+        });
+    }
+    Ok(report)
+}
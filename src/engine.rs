@@ -1,48 +1,377 @@
-use crate::error::Error;
+use crate::{error::Error, runtime};
 use deepviewrt_sys as ffi;
 use std::{
+    cell::{Cell, RefCell},
+    env,
     ffi::{CStr, CString},
-    path::Path,
+    fmt, fs,
+    path::{Path, PathBuf},
+    ptr::NonNull,
+    rc::Rc,
 };
 
-pub struct Engine {
+/// Environment variable listing additional directories to search for engine
+/// plugins, separated as `PATH` is.
+pub const ENGINE_PATH_VAR: &str = "DEEPVIEWRT_ENGINE_PATH";
+
+/// Directories searched for engine plugins after those of
+/// [`ENGINE_PATH_VAR`].
+const ENGINE_DIRS: &[&str] = &[
+    "/usr/local/lib",
+    "/usr/lib",
+    "/usr/lib64",
+    "/usr/lib/aarch64-linux-gnu",
+    "/usr/lib/arm-linux-gnueabihf",
+    "/usr/lib/x86_64-linux-gnu",
+];
+
+/// An engine plugin found by [`Engine::discover`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineInfo {
+    pub path: PathBuf,
+    pub name: String,
+    pub version: String,
+}
+
+/// Whether the file name looks like a DeepViewRT engine plugin,
+/// `deepview-rt-<engine>` followed by a shared library extension.
+fn is_plugin(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    let Some(rest) = name.strip_prefix("deepview-rt-") else {
+        return false;
+    };
+    rest.ends_with(".so")
+        || rest.contains(".so.")
+        || rest.ends_with(".dll")
+        || rest.ends_with(".dylib")
+}
+
+/// The engine object shared by every clone of an [`Engine`].
+struct Handle {
     owned: bool,
     ptr: *mut ffi::NNEngine,
+    loaded: Cell<bool>,
+    plugin: RefCell<Option<PathBuf>>,
+}
+
+/// An engine to try when creating a context with an [`EnginePolicy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineChoice {
+    /// A plugin found by [`Engine::by_name`].
+    Name(String),
+    /// A plugin at the given path.
+    Path(PathBuf),
+    /// The built-in CPU implementation.
+    Cpu,
+}
+
+impl fmt::Display for EngineChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineChoice::Name(name) => write!(f, "{}", name),
+            EngineChoice::Path(path) => write!(f, "{}", path.display()),
+            EngineChoice::Cpu => write!(f, "cpu"),
+        }
+    }
+}
+
+/// Ordered engine preferences, by default followed by the CPU.
+///
+/// A context created with a policy falls through to the next engine when the
+/// plugin cannot be loaded, or when loading the model or its first run fails
+/// with `NN_ERROR_KERNEL_MISSING` or `NN_ERROR_INVALID_ENGINE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnginePolicy {
+    pub engines: Vec<EngineChoice>,
+    pub cpu_fallback: bool,
+}
+
+impl Default for EnginePolicy {
+    fn default() -> Self {
+        EnginePolicy {
+            engines: Vec::new(),
+            cpu_fallback: true,
+        }
+    }
+}
+
+impl EnginePolicy {
+    pub fn new() -> Self {
+        EnginePolicy::default()
+    }
+
+    /// Appends an engine to try.
+    pub fn prefer(mut self, engine: EngineChoice) -> Self {
+        self.engines.push(engine);
+        self
+    }
+
+    /// Whether to try the CPU after every preferred engine failed.
+    pub fn cpu_fallback(mut self, cpu_fallback: bool) -> Self {
+        self.cpu_fallback = cpu_fallback;
+        self
+    }
+
+    /// The engines to try in order.
+    pub fn choices(&self) -> Vec<EngineChoice> {
+        let mut choices = self.engines.clone();
+        if self.cpu_fallback && !choices.contains(&EngineChoice::Cpu) {
+            choices.push(EngineChoice::Cpu);
+        }
+        choices
+    }
+
+    /// Whether an error loading or running a model should fall through to the
+    /// next engine.
+    pub fn is_fallback_error(error: &Error) -> bool {
+        error.is_nn_error(ffi::NNError::NN_ERROR_KERNEL_MISSING)
+            || error.is_nn_error(ffi::NNError::NN_ERROR_INVALID_ENGINE)
+    }
+}
+
+/// An engine which was tried and rejected.
+#[derive(Debug, Clone)]
+pub struct EngineAttempt {
+    pub choice: EngineChoice,
+    pub error: Error,
+}
+
+/// The engine chosen by an [`EnginePolicy`] and the engines rejected before
+/// it.
+#[derive(Debug, Clone)]
+pub struct EngineSelection {
+    pub choice: EngineChoice,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub rejected: Vec<EngineAttempt>,
+}
+
+impl fmt::Display for EngineSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.name, &self.version) {
+            (Some(name), Some(version)) => write!(f, "using {} {}", name, version)?,
+            (Some(name), None) => write!(f, "using {}", name)?,
+            _ => write!(f, "using {}", self.choice)?,
+        }
+        for attempt in &self.rejected {
+            write!(f, ", {} rejected: {}", attempt.choice, attempt.error)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        if self.owned {
+            unsafe { ffi::nn_engine_release(self.ptr) };
+        }
+    }
+}
+
+/// An engine plugin, such as an accelerator backend, used by contexts and
+/// tensors in place of the CPU.
+///
+/// Cloning an `Engine` shares the same engine object rather than loading the
+/// plugin again, so one engine can back several contexts.  A context keeps
+/// its clone for its whole lifetime and the engine is released when the last
+/// clone is dropped.  Loading, unloading and reloading the plugin affects
+/// every clone, and is refused on engines borrowed through [`Engine::wrap`].
+///
+/// Engines are neither `Send` nor `Sync`: clones share the engine object
+/// through an `Rc` and plugins are not required to be thread-safe.  Create
+/// the engine on the thread which runs its contexts.
+///
+/// ```compile_fail
+/// fn send<T: Send>() {}
+/// send::<deepviewrt::engine::Engine>();
+/// ```
+///
+/// ```compile_fail
+/// fn sync<T: Sync>() {}
+/// sync::<deepviewrt::engine::Engine>();
+/// ```
+#[derive(Clone)]
+pub struct Engine {
+    handle: Rc<Handle>,
 }
 
 impl Engine {
-    pub fn new<P: AsRef<Path> + Into<Vec<u8>>>(path: P) -> Result<Self, Error> {
-        let init_ret = unsafe { ffi::nn_engine_init(std::ptr::null_mut()) };
-        if init_ret.is_null() {
+    /// Creates an engine and loads the plugin at `path`.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let engine = Engine::init()?;
+        engine.load(path.as_ref())?;
+        Ok(engine)
+    }
+
+    /// Creates an engine without a plugin, see [`Engine::load`].
+    pub fn init() -> Result<Self, Error> {
+        runtime::ensure_init()?;
+        let ptr = unsafe { ffi::nn_engine_init(std::ptr::null_mut()) };
+        if ptr.is_null() {
             return Err(Error::WrapperError(
                 "nn_engine_init memory allocated failed".to_string(),
             ));
         }
-        let engine = Self {
-            owned: true,
-            ptr: init_ret,
-        };
+        Ok(Engine {
+            handle: Rc::new(Handle {
+                owned: true,
+                ptr,
+                loaded: Cell::new(false),
+                plugin: RefCell::new(None),
+            }),
+        })
+    }
+
+    fn require_owned(&self) -> Result<(), Error> {
+        if !self.handle.owned {
+            return Err(Error::WrapperError(String::from(
+                "cannot load or unload a plugin of a borrowed engine",
+            )));
+        }
+        Ok(())
+    }
 
-        let engine_cstring = CString::new(path);
-        if let Err(e) = engine_cstring {
-            return Err(Error::WrapperError(e.to_string()));
+    /// Loads the plugin at `path`, unloading the current plugin first.  The
+    /// path may also be a library name found in the standard search path.
+    pub fn load(&self, path: &Path) -> Result<(), Error> {
+        self.require_owned()?;
+        let plugin = path.to_str().ok_or_else(|| {
+            Error::WrapperError(format!("engine path {} is not UTF-8", path.display()))
+        })?;
+        let plugin = CString::new(plugin).map_err(|e| Error::WrapperError(e.to_string()))?;
+        self.unload()?;
+        let ret = unsafe { ffi::nn_engine_load(self.handle.ptr, plugin.as_ptr()) };
+        if ret != ffi::NNError::NN_SUCCESS {
+            return Err(Error::from(ret));
         }
-        let load_ret = unsafe { ffi::nn_engine_load(init_ret, engine_cstring.unwrap().into_raw()) };
-        if load_ret != ffi::NNError_NN_SUCCESS {
-            return Err(Error::from(load_ret));
+        self.handle.loaded.set(true);
+        *self.handle.plugin.borrow_mut() = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Unloads the plugin, the engine keeps its plugin path for
+    /// [`Engine::reload`].
+    pub fn unload(&self) -> Result<(), Error> {
+        self.require_owned()?;
+        if self.handle.loaded.replace(false) {
+            unsafe { ffi::nn_engine_unload(self.handle.ptr) };
         }
-        return Ok(engine);
+        Ok(())
     }
 
+    /// Unloads then loads the plugin again from the path it was loaded from.
+    pub fn reload(&self) -> Result<(), Error> {
+        let path = self.plugin().ok_or_else(|| {
+            Error::WrapperError(String::from("engine was never loaded from a plugin"))
+        })?;
+        self.load(&path)
+    }
+
+    /// Path of the plugin last loaded into this engine.
+    pub fn plugin(&self) -> Option<PathBuf> {
+        self.handle.plugin.borrow().clone()
+    }
+
+    /// The plugin's native handle, for example the OpenVX context, as a
+    /// pointer to `T`.  The pointee type depends on the plugin and is not
+    /// checked, dereferencing it is up to the caller.
+    pub fn native_handle<T>(&self) -> Result<Option<NonNull<T>>, Error> {
+        runtime::require(runtime::ENGINE_HANDLE, "nn_engine_native_handle")?;
+        let ret = unsafe { ffi::nn_engine_native_handle(self.handle.ptr) };
+        Ok(NonNull::new(ret as *mut T))
+    }
+
+    /// Scans the directories of `DEEPVIEWRT_ENGINE_PATH` followed by the
+    /// standard library directories for engine plugins.  Each plugin is
+    /// loaded to query its name and version, plugins which fail to load are
+    /// skipped.  A plugin found in several directories is reported once, from
+    /// the first directory.
+    pub fn discover() -> Vec<EngineInfo> {
+        let mut dirs: Vec<PathBuf> = env::var_os(ENGINE_PATH_VAR)
+            .map(|paths| env::split_paths(&paths).collect())
+            .unwrap_or_default();
+        dirs.extend(ENGINE_DIRS.iter().map(PathBuf::from));
+
+        let mut found: Vec<EngineInfo> = Vec::new();
+        let mut seen = Vec::new();
+        for dir in dirs {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| is_plugin(path))
+                .collect();
+            paths.sort();
+            for path in paths {
+                let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+                let file_name = path.file_name().map(|n| n.to_owned());
+                if seen.contains(&canonical)
+                    || found
+                        .iter()
+                        .any(|info| info.path.file_name() == file_name.as_deref())
+                {
+                    continue;
+                }
+                seen.push(canonical);
+                let Ok(engine) = Engine::new(&path) else {
+                    continue;
+                };
+                found.push(EngineInfo {
+                    name: engine.name().unwrap_or_default().to_string(),
+                    version: engine.version().unwrap_or_default().to_string(),
+                    path,
+                });
+            }
+        }
+        found
+    }
+
+    /// Loads the first discovered engine plugin whose name matches `name`,
+    /// ignoring case.
+    pub fn by_name(name: &str) -> Result<Self, Error> {
+        match Engine::discover()
+            .into_iter()
+            .find(|info| info.name.eq_ignore_ascii_case(name))
+        {
+            Some(info) => Engine::new(&info.path),
+            None => Err(Error::WrapperError(format!(
+                "no engine plugin named {} found, set {} to its directory",
+                name, ENGINE_PATH_VAR
+            ))),
+        }
+    }
+
+    /// Creates the engine for `choice`, `None` for the CPU.
+    pub fn from_choice(choice: &EngineChoice) -> Result<Option<Self>, Error> {
+        match choice {
+            EngineChoice::Name(name) => Engine::by_name(name).map(Some),
+            EngineChoice::Path(path) => Engine::new(path).map(Some),
+            EngineChoice::Cpu => Ok(None),
+        }
+    }
+
+    /// Borrows an engine owned elsewhere, such as the engine of a context.
+    /// The engine is not released on drop and its plugin cannot be loaded or
+    /// unloaded.
     pub fn wrap(ptr: *mut ffi::NNEngine) -> Result<Self, Error> {
         if ptr.is_null() {
             return Err(Error::Null());
         }
-        return Ok(Engine { owned: false, ptr });
+        return Ok(Engine {
+            handle: Rc::new(Handle {
+                owned: false,
+                ptr,
+                loaded: Cell::new(true),
+                plugin: RefCell::new(None),
+            }),
+        });
     }
 
     pub fn name(&self) -> Option<&str> {
-        let ret = unsafe { ffi::nn_engine_name(self.ptr) };
+        let ret = unsafe { ffi::nn_engine_name(self.handle.ptr) };
         if ret.is_null() {
             return None;
         }
@@ -51,7 +380,7 @@ impl Engine {
     }
 
     pub fn version(&self) -> Option<&str> {
-        let ret = unsafe { ffi::nn_engine_version(self.ptr) };
+        let ret = unsafe { ffi::nn_engine_version(self.handle.ptr) };
         if ret.is_null() {
             return None;
         }
@@ -60,18 +389,10 @@ impl Engine {
     }
 
     pub unsafe fn to_ptr(&self) -> *const ffi::NNEngine {
-        self.ptr
+        self.handle.ptr
     }
 
     pub unsafe fn to_ptr_mut(&self) -> *mut ffi::NNEngine {
-        self.ptr as *mut ffi::NNEngine
-    }
-}
-
-impl Drop for Engine {
-    fn drop(&mut self) {
-        if self.owned {
-            unsafe { ffi::nn_engine_release(self.ptr) };
-        }
+        self.handle.ptr
     }
 }
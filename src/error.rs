@@ -3,24 +3,37 @@ use std::{ffi::CStr, fmt, io};
 
 #[derive(Debug, Clone)]
 pub enum Error {
-    NNError(&'static str),
+    NNError(ffi::NNError, &'static str),
     WrapperError(String),
     Null(),
     IoError(io::ErrorKind),
     Utf8Error(std::str::Utf8Error),
+    #[cfg(feature = "dynamic")]
+    LoadError(ffi::dynamic::LoadError),
 }
 
 impl From<ffi::NNError> for Error {
     fn from(value: ffi::NNError) -> Self {
         let ret = unsafe { ffi::nn_strerror(value) };
-        if ret.is_null() {
-            return Error::Null();
-        }
-        let desc = unsafe { CStr::from_ptr(ret) };
-        match desc.to_str() {
-            Ok(estr) => return Error::NNError(estr),
-            Err(_) => return Error::Null(),
-        }
+        let message = if ret.is_null() {
+            None
+        } else {
+            unsafe { CStr::from_ptr(ret) }.to_str().ok()
+        };
+        Error::NNError(value, message.unwrap_or("unknown error"))
+    }
+}
+
+impl Error {
+    /// Whether this error is the runtime error `code`.
+    pub fn is_nn_error(&self, code: ffi::NNError) -> bool {
+        matches!(self, Error::NNError(error, _) if *error == code)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Error::IoError(value.kind())
     }
 }
 
@@ -30,10 +43,17 @@ impl From<std::str::Utf8Error> for Error {
     }
 }
 
+#[cfg(feature = "dynamic")]
+impl From<ffi::dynamic::LoadError> for Error {
+    fn from(value: ffi::dynamic::LoadError) -> Self {
+        Error::LoadError(value)
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Error::NNError(e) => return write!(f, "{}", e),
+            Error::NNError(_, e) => return write!(f, "{}", e),
             Error::WrapperError(e) => return write!(f, "{}", e),
             Error::Null() => return write!(f, "null/unknown error message unavailable"),
             Error::IoError(kind) => {
@@ -43,6 +63,10 @@ impl fmt::Display for Error {
             Error::Utf8Error(e) => {
                 return write!(f, "{}", e);
             }
+            #[cfg(feature = "dynamic")]
+            Error::LoadError(e) => {
+                return write!(f, "{}", e);
+            }
         }
     }
 }
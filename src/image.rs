@@ -0,0 +1,149 @@
+use crate::{
+    error::Error,
+    preprocess::PixelEncoder,
+    tensor::{f32_to_f16, Element, MappedDataMut, Tensor},
+    transform::{ResizeMode, Transform},
+};
+use ::image::{imageops::FilterType, DynamicImage};
+
+impl Tensor {
+    /// Resizes `image` to the height and width of this NHWC (or HWC) tensor
+    /// and writes it into the tensor, converting to the tensor's channel count
+    /// (1 for grayscale, 3 for RGB or 4 for RGBA).
+    ///
+    /// Pixels are written as by a default
+    /// [`Preprocessor`](crate::preprocess::Preprocessor): float tensors
+    /// receive the pixel normalized to `[0, 1]`, quantized tensors receive it
+    /// quantized through the tensor's scales and zero-points and unquantized
+    /// integer tensors receive the raw pixel value, shifted by -128 for `I8`
+    /// tensors without quantization parameters.
+    ///
+    /// The returned transform maps tensor coordinates back into the source
+    /// image, for example to place detections on the original frame.
+    pub fn write_image(
+        &mut self,
+        image: &DynamicImage,
+        mode: ResizeMode,
+    ) -> Result<Transform, Error> {
+        let (height, width, channels) = image_shape(self)?;
+        let (pixels, transform) = fit_image(image, mode, width, height, channels)?;
+        let tensor_type = self.tensor_type();
+        let encoder = PixelEncoder::new(self.quant_params(), tensor_type, self.dims() - 1);
+
+        let mut map = self.mapwo()?;
+        match &mut *map {
+            MappedDataMut::I8(dst) => write_pixels(dst, &pixels, channels, &encoder),
+            MappedDataMut::U8(dst) => write_pixels(dst, &pixels, channels, &encoder),
+            MappedDataMut::I16(dst) => write_pixels(dst, &pixels, channels, &encoder),
+            MappedDataMut::U16(dst) => write_pixels(dst, &pixels, channels, &encoder),
+            MappedDataMut::I32(dst) => write_pixels(dst, &pixels, channels, &encoder),
+            MappedDataMut::U32(dst) => write_pixels(dst, &pixels, channels, &encoder),
+            MappedDataMut::I64(dst) => write_pixels(dst, &pixels, channels, &encoder),
+            MappedDataMut::U64(dst) => write_pixels(dst, &pixels, channels, &encoder),
+            MappedDataMut::F32(dst) => write_normalized(dst, &pixels),
+            MappedDataMut::F64(dst) => write_normalized(dst, &pixels),
+            MappedDataMut::F16(dst) => {
+                for (out, pixel) in dst.chunks_exact_mut(2).zip(&pixels) {
+                    out.copy_from_slice(&f32_to_f16(*pixel as f32 / 255.0).to_ne_bytes());
+                }
+            }
+            MappedDataMut::RAW(_) => {
+                return Err(Error::WrapperError(format!(
+                    "write_image: unsupported tensor type {:?}",
+                    tensor_type
+                )));
+            }
+        }
+        Ok(transform)
+    }
+}
+
+/// Returns the height, width and channels of an NHWC or HWC image tensor.
+fn image_shape(tensor: &Tensor) -> Result<(u32, u32, usize), Error> {
+    let shape = tensor.shape();
+    let (height, width, channels) = match tensor.dims() {
+        3 => (shape[0], shape[1], shape[2]),
+        4 if shape[0] == 1 => (shape[1], shape[2], shape[3]),
+        _ => {
+            return Err(Error::WrapperError(format!(
+                "expected an NHWC image tensor with a batch of 1 but got shape {:?}",
+                &shape[..tensor.dims().clamp(0, 4) as usize]
+            )));
+        }
+    };
+    if !matches!(channels, 1 | 3 | 4) || height <= 0 || width <= 0 {
+        return Err(Error::WrapperError(format!(
+            "unsupported image tensor shape {}x{}x{}",
+            height, width, channels
+        )));
+    }
+    Ok((height as u32, width as u32, channels as usize))
+}
+
+/// Fits `image` into a `width` x `height` canvas with `channels` interleaved
+/// channels, returning the canvas and the transform which produced it.
+pub(crate) fn fit_image(
+    image: &DynamicImage,
+    mode: ResizeMode,
+    width: u32,
+    height: u32,
+    channels: usize,
+) -> Result<(Vec<u8>, Transform), Error> {
+    if image.width() == 0 || image.height() == 0 {
+        return Err(Error::WrapperError(String::from("image is empty")));
+    }
+    let transform = Transform::new(mode, image.width(), image.height(), width, height);
+    let (resized_width, resized_height) = match mode {
+        ResizeMode::Stretch => (width, height),
+        _ => transform.resized_size(),
+    };
+    let resized = if (resized_width, resized_height) == (image.width(), image.height()) {
+        image.clone()
+    } else {
+        image.resize_exact(resized_width, resized_height, FilterType::Triangle)
+    };
+    let src = match channels {
+        1 => resized.to_luma8().into_raw(),
+        3 => resized.to_rgb8().into_raw(),
+        4 => resized.to_rgba8().into_raw(),
+        _ => {
+            return Err(Error::WrapperError(format!(
+                "unsupported channel count {}",
+                channels
+            )));
+        }
+    };
+
+    let mut canvas = vec![0u8; width as usize * height as usize * channels];
+    let offset_x = transform.offset_x as i64;
+    let offset_y = transform.offset_y as i64;
+    for y in 0..height as i64 {
+        let sy = y - offset_y;
+        if sy < 0 || sy >= resized_height as i64 {
+            continue;
+        }
+        let x0 = offset_x.max(0);
+        let x1 = (offset_x + resized_width as i64).min(width as i64);
+        if x0 >= x1 {
+            continue;
+        }
+        let src_start =
+            (sy as usize * resized_width as usize + (x0 - offset_x) as usize) * channels;
+        let dst_start = (y as usize * width as usize + x0 as usize) * channels;
+        let len = (x1 - x0) as usize * channels;
+        canvas[dst_start..dst_start + len].copy_from_slice(&src[src_start..src_start + len]);
+    }
+    Ok((canvas, transform))
+}
+
+fn write_pixels<T: Element>(dst: &mut [T], pixels: &[u8], channels: usize, encoder: &PixelEncoder) {
+    for (i, (out, pixel)) in dst.iter_mut().zip(pixels).enumerate() {
+        *out = encoder.encode(*pixel as f32 / 255.0, i % channels);
+    }
+}
+
+fn write_normalized<T: Element>(dst: &mut [T], pixels: &[u8]) {
+    for (out, pixel) in dst.iter_mut().zip(pixels) {
+        *out = T::from_f32(*pixel as f32 / 255.0);
+    }
+}
@@ -1,21 +1,50 @@
 use deepviewrt_sys as ffi;
 pub mod context;
+pub mod divergence;
 pub mod engine;
 pub mod error;
+#[cfg(feature = "image")]
+pub mod image;
 pub mod model;
+pub mod npy;
+pub mod postprocess;
+pub mod preprocess;
+pub mod profile;
+#[cfg(feature = "record")]
+pub mod record;
+pub mod runtime;
+#[cfg(feature = "safetensors")]
+pub mod safetensors;
 pub mod tensor;
+pub mod transform;
+use error::Error;
 use std::ffi::CStr;
 
+// Links the mock implementation of the nn_* functions.
+#[cfg(feature = "mock")]
+use deepviewrt_mock as _;
+
 pub enum QuantizationType {
     TypeNone = 0,
     TypeAffinePerTensor = 1,
     TypeAffinePerChannel = 2,
 }
 
-pub fn version() -> &'static str {
+/// Version string of the runtime library, an error when a dynamically
+/// loaded runtime failed to load.
+pub fn version() -> Result<&'static str, Error> {
+    runtime::ensure_loaded()?;
     let version = unsafe { ffi::nn_version() };
+    if version.is_null() {
+        return Err(Error::Null());
+    }
     let ret_cstr = unsafe { CStr::from_ptr(version) };
-    return ret_cstr.to_str().unwrap();
+    Ok(ret_cstr.to_str()?)
 }
 
-pub fn init() {}
+/// Initializes the runtime with default options, see
+/// [`runtime::init_with`].  Constructors initialize the runtime themselves so
+/// calling this is only needed to check for initialization errors early.
+pub fn init() -> Result<(), Error> {
+    runtime::ensure_init()
+}
@@ -1,13 +1,39 @@
-use crate::{error::Error, tensor::TensorType};
+use crate::{
+    error::Error,
+    runtime::{self, Capabilities},
+    tensor::{QuantParams, TensorType},
+};
 use deepviewrt_sys as ffi;
-use std::ffi::{CStr, CString};
+use std::{
+    ffi::{CStr, CString},
+    fmt,
+    marker::PhantomData,
+};
 
+/// A view of model memory, such as the model loaded in a context.
+///
+/// The runtime only reads model memory, so a `Model` is `Send` and `Sync` and
+/// may be shared between threads for as long as the memory outlives it.  The
+/// model of a context is created once by [`crate::context::Context::model`]
+/// and not written again while it is borrowed.
 pub struct Model {
     ptr: *const ffi::NNModel,
 }
 
+// SAFETY: every nn_model_* function only reads the model memory, which
+// try_from_ptr requires to stay valid and unmodified while the Model exists.
+// Context::model fills its cell once and only clears it through &mut self,
+// so a shared &Model is never written to.
+unsafe impl Send for Model {}
+unsafe impl Sync for Model {}
+
 impl Model {
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid model which is neither freed nor modified
+    /// while the `Model` exists.
     pub unsafe fn try_from_ptr(ptr: *const ffi::NNModel) -> Result<Self, Error> {
+        runtime::ensure_loaded()?;
         if ptr.is_null() {
             return Err(Error::WrapperError(String::from(
                 "try_from_ptr: pointer is null",
@@ -17,6 +43,22 @@ impl Model {
         return Ok(Self { ptr });
     }
 
+    /// Checks that `data` holds a valid model.
+    pub fn validate(data: &[u8]) -> Result<(), Error> {
+        runtime::ensure_loaded()?;
+        let ret =
+            unsafe { ffi::nn_model_validate(data.as_ptr() as *const ffi::NNModel, data.len()) };
+        if ret != 0 {
+            let msg = unsafe { ffi::nn_model_validate_error(ret) };
+            if msg.is_null() {
+                return Err(Error::WrapperError(String::from("invalid model")));
+            }
+            let msg = unsafe { CStr::from_ptr(msg) }.to_string_lossy();
+            return Err(Error::WrapperError(format!("invalid model: {}", msg)));
+        }
+        Ok(())
+    }
+
     pub fn name(&self) -> Result<&str, Error> {
         let ret = unsafe { ffi::nn_model_name(self.ptr) };
         if ret.is_null() {
@@ -29,11 +71,21 @@ impl Model {
         }
     }
 
-    /*
-    pub fn serial(&self) -> Result<&str, Error> {
+    pub fn uuid(&self) -> Result<&str, Error> {
+        let ret = unsafe { ffi::nn_model_uuid(self.ptr) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from("nn_model_uuid is null")));
+        }
+        let cstr = unsafe { CStr::from_ptr(ret) };
+        match cstr.to_str() {
+            Ok(s) => Ok(s),
+            Err(e) => Err(Error::WrapperError(e.to_string())),
+        }
+    }
 
+    pub fn serial(&self) -> u32 {
+        unsafe { ffi::nn_model_serial(self.ptr) }
     }
-    */
 
     pub fn label_count(&self) -> Result<i32, Error> {
         let ret = unsafe { ffi::nn_model_label_count(self.ptr) };
@@ -58,6 +110,7 @@ impl Model {
     }
 
     pub fn inputs(&self) -> Result<&[u32], Error> {
+        runtime::require(runtime::MODEL_IO, "nn_model_inputs")?;
         let mut len: usize = 0;
 
         let ret = unsafe { ffi::nn_model_inputs(self.ptr, &mut len as *mut usize) };
@@ -71,6 +124,7 @@ impl Model {
     }
 
     pub fn outputs(&self) -> Result<&[u32], Error> {
+        runtime::require(runtime::MODEL_IO, "nn_model_outputs")?;
         let mut n_outputs: usize = 0;
         let ret = unsafe { ffi::nn_model_outputs(self.ptr, &mut n_outputs as *mut usize) };
         if ret.is_null() {
@@ -129,6 +183,7 @@ impl Model {
     }
 
     pub fn layer_type_id(&self, index: usize) -> Result<i16, Error> {
+        runtime::require(runtime::MODEL_IO, "nn_model_layer_type_id")?;
         let ret = unsafe { ffi::nn_model_layer_type_id(self.ptr, index) };
         if ret == 0 {
             return Err(Error::WrapperError(String::from("index out of range")));
@@ -152,12 +207,12 @@ impl Model {
 
     pub fn layer_datatype_id(&self, index: usize) -> Result<TensorType, Error> {
         let ret = unsafe { ffi::nn_model_layer_datatype_id(self.ptr, index) };
-        if ret == 0 {
+        if ret == ffi::NNTensorType::RAW {
             return Err(Error::WrapperError(String::from(
                 "Model is invalid or Index is out of range",
             )));
         }
-        match TensorType::try_from(ret as u32) {
+        match TensorType::try_from(ret) {
             Ok(tensor_type) => {
                 return Ok(tensor_type);
             }
@@ -168,6 +223,7 @@ impl Model {
     }
 
     pub fn layer_zeros(&self, index: usize) -> Result<&[i32], Error> {
+        runtime::require(runtime::QUANTIZATION, "nn_model_layer_zeros")?;
         let mut n_zeros: isize = -1;
         let ret = unsafe {
             ffi::nn_model_layer_zeros(self.ptr, index, &mut n_zeros as *mut isize as *mut usize)
@@ -179,6 +235,7 @@ impl Model {
     }
 
     pub fn layer_scales(&self, index: usize) -> Result<&[f32], Error> {
+        runtime::require(runtime::QUANTIZATION, "nn_model_layer_scales")?;
         let mut n_scales: isize = -1;
         let ret = unsafe {
             ffi::nn_model_layer_scales(self.ptr, index, &mut n_scales as *mut isize as *mut usize)
@@ -189,18 +246,36 @@ impl Model {
         return unsafe { Ok(std::slice::from_raw_parts(ret, n_scales as usize)) };
     }
 
+    /// Channel axis of the layer quantization, -1 when it is not set.
     pub fn layer_axis(&self, index: usize) -> Result<i32, Error> {
-        let ret = unsafe { ffi::nn_model_layer_axis(self.ptr, index) };
-        if ret == 0 {
-            //TODO 0 indicates faliure, but docs say -1 (should be clarified
+        runtime::require(runtime::QUANTIZATION, "nn_model_layer_axis")?;
+        if index >= self.layer_count() {
             return Err(Error::WrapperError(String::from("Index out of range")));
         }
-        return Ok(ret);
+        return Ok(unsafe { ffi::nn_model_layer_axis(self.ptr, index) });
+    }
+
+    /// Quantization parameters of the layer, empty when the layer is not
+    /// quantized.
+    pub fn layer_quant_params(&self, index: usize) -> QuantParams {
+        QuantParams {
+            scales: self
+                .layer_scales(index)
+                .map(|s| s.to_vec())
+                .unwrap_or_default(),
+            zeros: self
+                .layer_zeros(index)
+                .map(|z| z.to_vec())
+                .unwrap_or_default(),
+            axis: self.layer_axis(index).unwrap_or(-1),
+        }
     }
 
     pub fn layer_shape(&self, index: usize) -> Result<&[i32], Error> {
-        let n_dims: isize = -1;
-        let ret = unsafe { ffi::nn_model_layer_shape(self.ptr, index, &mut (n_dims as usize)) };
+        let mut n_dims: isize = -1;
+        let ret = unsafe {
+            ffi::nn_model_layer_shape(self.ptr, index, &mut n_dims as *mut isize as *mut usize)
+        };
         if ret.is_null() || n_dims == -1 {
             return Err(Error::WrapperError(String::from("Index out of range")));
         }
@@ -209,9 +284,355 @@ impl Model {
 
     /*
     pub fn layer_inputs(&self, index: usize) {}
+    */
+
+    pub fn layer_parameter(&self, index: usize, key: &str) -> Result<Parameter<'_>, Error> {
+        runtime::require(runtime::LAYER_PARAMETERS, "nn_model_layer_parameter")?;
+        let key = match CString::new(key) {
+            Ok(s) => s,
+            Err(e) => return Err(Error::WrapperError(e.to_string())),
+        };
+        let ret = unsafe { ffi::nn_model_layer_parameter(self.ptr, index, key.as_ptr()) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from(
+                "layer does not contain this parameter",
+            )));
+        }
+        Ok(Parameter {
+            ptr: ret,
+            _model: PhantomData,
+        })
+    }
 
-    pub fn layer_parameter() {}
+    pub fn layer_parameter_shape(&self, index: usize, key: &str) -> Result<&[i32], Error> {
+        self.layer_parameter(index, key)?.shape()
+    }
 
-    pub fn layer_parameter_shape() {}
-    */
+    /// Size of the memory pool required by the model.
+    pub fn memory_size(&self) -> usize {
+        unsafe { ffi::nn_model_memory_size(self.ptr) }
+    }
+
+    pub fn cache_minimum_size(&self) -> usize {
+        unsafe { ffi::nn_model_cache_minimum_size(self.ptr) }
+    }
+
+    pub fn cache_optimum_size(&self) -> usize {
+        unsafe { ffi::nn_model_cache_optimum_size(self.ptr) }
+    }
+
+    pub fn resource_count(&self) -> usize {
+        if !Capabilities::get().resources {
+            return 0;
+        }
+        unsafe { ffi::nn_model_resource_count(self.ptr) }
+    }
+
+    pub fn resource_at(&self, index: usize) -> Result<Resource<'_>, Error> {
+        runtime::require(runtime::RESOURCES, "nn_model_resource_at")?;
+        let ret = unsafe { ffi::nn_model_resource_at(self.ptr, index) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from("Index out of range")));
+        }
+        Ok(Resource {
+            ptr: ret,
+            _model: PhantomData,
+        })
+    }
+
+    pub fn resource(&self, name: &str) -> Result<Resource<'_>, Error> {
+        runtime::require(runtime::RESOURCES, "nn_model_resource")?;
+        let name = match CString::new(name) {
+            Ok(s) => s,
+            Err(e) => return Err(Error::WrapperError(e.to_string())),
+        };
+        let ret = unsafe { ffi::nn_model_resource(self.ptr, name.as_ptr()) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from("resource not found")));
+        }
+        Ok(Resource {
+            ptr: ret,
+            _model: PhantomData,
+        })
+    }
+}
+
+impl Model {
+    /// Collects the description of the model and all of its layers.
+    pub fn info(&self) -> ModelInfo {
+        let labels = (0..self.label_count().unwrap_or(0))
+            .map(|i| self.label(i).unwrap_or_default().to_string())
+            .collect();
+        let indices = |layers: Result<&[u32], Error>| {
+            layers.unwrap_or(&[]).iter().map(|i| *i as usize).collect()
+        };
+        let layers = (0..self.layer_count())
+            .map(|index| LayerInfo {
+                index,
+                name: self.layer_name(index).unwrap_or_default().to_string(),
+                layer_type: self.layer_type(index).unwrap_or_default().to_string(),
+                datatype: self.layer_datatype(index).unwrap_or_default().to_string(),
+                shape: self
+                    .layer_shape(index)
+                    .map(|s| s.to_vec())
+                    .unwrap_or_default(),
+                quant: self.layer_quant_params(index),
+            })
+            .collect();
+        let resources = (0..self.resource_count())
+            .filter_map(|index| self.resource_at(index).ok())
+            .map(|resource| ResourceInfo {
+                name: resource.name().unwrap_or_default().to_string(),
+                mime: resource.mime().unwrap_or_default().to_string(),
+                meta: resource.meta().unwrap_or_default().to_string(),
+                size: resource.data().map_or(0, |d| d.len()),
+            })
+            .collect();
+        ModelInfo {
+            name: self.name().unwrap_or_default().to_string(),
+            uuid: self.uuid().unwrap_or_default().to_string(),
+            serial: self.serial(),
+            labels,
+            inputs: indices(self.inputs()),
+            outputs: indices(self.outputs()),
+            layers,
+            memory_size: self.memory_size(),
+            cache_minimum_size: self.cache_minimum_size(),
+            cache_optimum_size: self.cache_optimum_size(),
+            resources,
+        }
+    }
+}
+
+/// Description of a model, see [`Model::info`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModelInfo {
+    pub name: String,
+    pub uuid: String,
+    pub serial: u32,
+    pub labels: Vec<String>,
+    /// Indices of the input layers.
+    pub inputs: Vec<usize>,
+    /// Indices of the output layers.
+    pub outputs: Vec<usize>,
+    pub layers: Vec<LayerInfo>,
+    pub memory_size: usize,
+    pub cache_minimum_size: usize,
+    pub cache_optimum_size: usize,
+    pub resources: Vec<ResourceInfo>,
+}
+
+impl ModelInfo {
+    /// Describes the model in `data` without loading it into a context.
+    pub fn from_bytes(data: &[u8]) -> Result<ModelInfo, Error> {
+        Model::validate(data)?;
+        let model = unsafe { Model::try_from_ptr(data.as_ptr() as *const ffi::NNModel)? };
+        Ok(model.info())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayerInfo {
+    pub index: usize,
+    pub name: String,
+    pub layer_type: String,
+    pub datatype: String,
+    pub shape: Vec<i32>,
+    pub quant: QuantParams,
+}
+
+/// Description of an embedded resource, the data itself is not copied.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResourceInfo {
+    pub name: String,
+    pub mime: String,
+    pub meta: String,
+    /// Size of the resource data in bytes.
+    pub size: usize,
+}
+
+/// Name, datatype and shape of a layer, as shown when formatting a model.
+struct LayerSummary<'a>(&'a Model, usize);
+
+impl<'a> fmt::Debug for LayerSummary<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let LayerSummary(model, index) = *self;
+        write!(
+            f,
+            "{}: {} {} {:?}",
+            index,
+            model.layer_name(index).unwrap_or("?"),
+            model.layer_datatype(index).unwrap_or("?"),
+            model.layer_shape(index).unwrap_or(&[])
+        )
+    }
+}
+
+impl Model {
+    fn layer_summaries(&self, layers: Result<&[u32], Error>) -> Vec<LayerSummary<'_>> {
+        layers
+            .unwrap_or(&[])
+            .iter()
+            .map(|index| LayerSummary(self, *index as usize))
+            .collect()
+    }
+}
+
+impl fmt::Debug for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Model")
+            .field("name", &self.name().ok())
+            .field("layers", &self.layer_count())
+            .field("labels", &self.label_count().unwrap_or(0))
+            .field("inputs", &self.layer_summaries(self.inputs()))
+            .field("outputs", &self.layer_summaries(self.outputs()))
+            .finish()
+    }
+}
+
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} ({} layers)",
+            self.name().unwrap_or("<unnamed>"),
+            self.layer_count()
+        )?;
+        writeln!(f, "inputs:")?;
+        for layer in self.layer_summaries(self.inputs()) {
+            writeln!(f, "  {:?}", layer)?;
+        }
+        write!(f, "outputs:")?;
+        for layer in self.layer_summaries(self.outputs()) {
+            write!(f, "\n  {:?}", layer)?;
+        }
+        Ok(())
+    }
+}
+
+/// A parameter attached to a model layer, such as anchors or configuration.
+pub struct Parameter<'a> {
+    ptr: *const ffi::NNModelParameter,
+    _model: PhantomData<&'a Model>,
+}
+
+impl<'a> Parameter<'a> {
+    pub fn shape(&self) -> Result<&'a [i32], Error> {
+        let mut n_dims: usize = 0;
+        let ret = unsafe { ffi::nn_model_parameter_shape(self.ptr, &mut n_dims) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from("parameter has no shape")));
+        }
+        unsafe { Ok(std::slice::from_raw_parts(ret, n_dims)) }
+    }
+
+    pub fn data_f32(&self) -> Result<&'a [f32], Error> {
+        let mut len: usize = 0;
+        let ret = unsafe { ffi::nn_model_parameter_data_f32(self.ptr, &mut len) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from(
+                "parameter has no f32 data",
+            )));
+        }
+        unsafe { Ok(std::slice::from_raw_parts(ret, len)) }
+    }
+
+    pub fn data_i32(&self) -> Result<&'a [i32], Error> {
+        let mut len: usize = 0;
+        let ret = unsafe { ffi::nn_model_parameter_data_i32(self.ptr, &mut len) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from(
+                "parameter has no i32 data",
+            )));
+        }
+        unsafe { Ok(std::slice::from_raw_parts(ret, len)) }
+    }
+
+    pub fn data_i16(&self) -> Result<&'a [i16], Error> {
+        let mut len: usize = 0;
+        let ret = unsafe { ffi::nn_model_parameter_data_i16(self.ptr, &mut len) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from(
+                "parameter has no i16 data",
+            )));
+        }
+        unsafe { Ok(std::slice::from_raw_parts(ret, len)) }
+    }
+
+    pub fn data_i8(&self) -> Result<&'a [i8], Error> {
+        let mut len: usize = 0;
+        let ret = unsafe { ffi::nn_model_parameter_data_i8(self.ptr, &mut len) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from(
+                "parameter has no i8 data",
+            )));
+        }
+        unsafe { Ok(std::slice::from_raw_parts(ret, len)) }
+    }
+
+    pub fn data_raw(&self) -> Result<&'a [u8], Error> {
+        let mut len: usize = 0;
+        let ret = unsafe { ffi::nn_model_parameter_data_raw(self.ptr, &mut len) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from(
+                "parameter has no raw data",
+            )));
+        }
+        unsafe { Ok(std::slice::from_raw_parts(ret, len)) }
+    }
+
+    pub fn data_str_len(&self) -> usize {
+        unsafe { ffi::nn_model_parameter_data_str_len(self.ptr) }
+    }
+
+    pub fn data_str(&self, index: usize) -> Result<&'a str, Error> {
+        let ret = unsafe { ffi::nn_model_parameter_data_str(self.ptr, index) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from("index out of range")));
+        }
+        let cstr = unsafe { CStr::from_ptr(ret) };
+        match cstr.to_str() {
+            Ok(s) => Ok(s),
+            Err(e) => Err(Error::WrapperError(e.to_string())),
+        }
+    }
+}
+
+/// A named resource embedded in the model, such as a label file or anchors.
+pub struct Resource<'a> {
+    ptr: *const ffi::NNModelResource,
+    _model: PhantomData<&'a Model>,
+}
+
+impl<'a> Resource<'a> {
+    fn string(ret: *const std::os::raw::c_char) -> Option<&'a str> {
+        if ret.is_null() {
+            return None;
+        }
+        unsafe { CStr::from_ptr(ret) }.to_str().ok()
+    }
+
+    pub fn name(&self) -> Option<&'a str> {
+        Self::string(unsafe { ffi::nn_model_resource_name(self.ptr) })
+    }
+
+    pub fn meta(&self) -> Option<&'a str> {
+        Self::string(unsafe { ffi::nn_model_resource_meta(self.ptr) })
+    }
+
+    pub fn mime(&self) -> Option<&'a str> {
+        Self::string(unsafe { ffi::nn_model_resource_mime(self.ptr) })
+    }
+
+    pub fn data(&self) -> Option<&'a [u8]> {
+        let mut len: usize = 0;
+        let ret = unsafe { ffi::nn_model_resource_data(self.ptr, &mut len) };
+        if ret.is_null() {
+            return None;
+        }
+        unsafe { Some(std::slice::from_raw_parts(ret, len)) }
+    }
 }
@@ -0,0 +1,757 @@
+#[cfg(feature = "npz")]
+use crate::{context::Context, tensor::QuantParams};
+use crate::{
+    error::Error,
+    tensor::{f16_to_f32, f32_to_f16, Element, MappedData, MappedDataMut, Tensor, TensorType},
+};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// An n-dimensional array as stored in a NumPy `.npy` file, holding
+/// native-endian data of a tensor type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Array {
+    pub dtype: TensorType,
+    pub shape: Vec<usize>,
+    pub data: Vec<u8>,
+}
+
+impl Array {
+    /// Copies the contents, type and shape of `tensor`.  Raw tensors become
+    /// arrays of single byte voids and string tensors a byte string scalar.
+    pub fn from_tensor(tensor: &Tensor) -> Result<Self, Error> {
+        let dims = tensor.dims().clamp(0, 4) as usize;
+        let map = tensor.mapro()?;
+        let data = map.as_bytes().to_vec();
+        let shape = match &*map {
+            MappedData::RAW(_) => vec![data.len()],
+            MappedData::STR(_) => Vec::new(),
+            _ => tensor.shape()[..dims]
+                .iter()
+                .map(|d| (*d).max(0) as usize)
+                .collect(),
+        };
+        Ok(Array {
+            dtype: tensor.tensor_type(),
+            shape,
+            data,
+        })
+    }
+
+    /// Allocates a new tensor holding a copy of the array.
+    pub fn to_tensor(&self) -> Result<Tensor, Error> {
+        let shape: Vec<i32> = match self.dtype {
+            TensorType::STR => vec![self.data.len() as i32 + 1],
+            _ => self.shape.iter().map(|d| *d as i32).collect(),
+        };
+        let mut tensor = Tensor::with_shape(self.dtype, &shape)?;
+        let mut map = tensor.mapwo()?;
+        let bytes = map.as_bytes_mut();
+        if self.dtype == TensorType::STR {
+            bytes.fill(0);
+        }
+        let len = self.data.len().min(bytes.len());
+        bytes[..len].copy_from_slice(&self.data[..len]);
+        drop(map);
+        Ok(tensor)
+    }
+
+    /// Number of elements of the array.
+    pub fn volume(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// Writes the array into an existing tensor of the same volume.  Arrays
+    /// of the tensor type are copied as is, other numeric arrays are
+    /// converted.  Float tensors receive the values as they are, unquantized
+    /// integer tensors receive them rounded and saturated, and quantized
+    /// tensors receive float arrays quantized through the tensor's
+    /// quantization parameters.  Integer arrays are rejected by quantized
+    /// tensors of another type, as their values may already be quantized.
+    pub fn copy_into(&self, tensor: &mut Tensor) -> Result<(), Error> {
+        let tensor_type = tensor.tensor_type();
+        if self.dtype != TensorType::STR && self.volume() != tensor.volume().max(0) as usize {
+            return Err(Error::WrapperError(format!(
+                "array of shape {:?} does not fit a tensor of volume {}",
+                self.shape,
+                tensor.volume()
+            )));
+        }
+        if self.dtype == tensor_type {
+            let mut map = tensor.mapwo()?;
+            let bytes = map.as_bytes_mut();
+            if self.data.len() > bytes.len() {
+                return Err(Error::WrapperError(format!(
+                    "{} bytes do not fit a tensor of {} bytes",
+                    self.data.len(),
+                    bytes.len()
+                )));
+            }
+            bytes[..self.data.len()].copy_from_slice(&self.data);
+            return Ok(());
+        }
+
+        let values = self.to_f32_vec().map_err(|_| {
+            Error::WrapperError(format!(
+                "cannot convert an array of {:?} into a tensor of {:?}",
+                self.dtype, tensor_type
+            ))
+        })?;
+        let quant = tensor.quant_params();
+        let float = matches!(
+            self.dtype,
+            TensorType::F16 | TensorType::F32 | TensorType::F64
+        );
+        if quant.is_quantized() && !float {
+            return Err(Error::WrapperError(format!(
+                "cannot write an array of {:?} into a quantized tensor of {:?}",
+                self.dtype, tensor_type
+            )));
+        }
+        let dims = tensor.dims().clamp(0, 4) as usize;
+        let axis = quant.axis as usize;
+        let inner = if quant.is_per_channel() && quant.axis >= 0 && axis < dims {
+            tensor.shape()[axis + 1..dims]
+                .iter()
+                .product::<i32>()
+                .max(1) as usize
+        } else {
+            1
+        };
+        let channels = if quant.is_per_channel() {
+            quant.scales.len()
+        } else {
+            1
+        };
+        let quantized: Vec<f32> = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| quant.quantize(*v, (i / inner) % channels))
+            .collect();
+
+        fn fill<T: Element>(dst: &mut [T], values: &[f32]) {
+            for (d, v) in dst.iter_mut().zip(values) {
+                *d = T::from_f32(*v);
+            }
+        }
+
+        let mut map = tensor.mapwo()?;
+        match &mut *map {
+            MappedDataMut::I8(dst) => fill(dst, &quantized),
+            MappedDataMut::U8(dst) => fill(dst, &quantized),
+            MappedDataMut::I16(dst) => fill(dst, &quantized),
+            MappedDataMut::U16(dst) => fill(dst, &quantized),
+            MappedDataMut::I32(dst) => fill(dst, &quantized),
+            MappedDataMut::U32(dst) => fill(dst, &quantized),
+            MappedDataMut::I64(dst) => fill(dst, &quantized),
+            MappedDataMut::U64(dst) => fill(dst, &quantized),
+            MappedDataMut::F16(dst) => {
+                for (d, v) in dst.chunks_exact_mut(2).zip(&values) {
+                    d.copy_from_slice(&f32_to_f16(*v).to_ne_bytes());
+                }
+            }
+            MappedDataMut::F32(dst) => dst.copy_from_slice(&values[..dst.len()]),
+            MappedDataMut::F64(dst) => fill(dst, &values),
+            MappedDataMut::RAW(_) => {
+                return Err(Error::WrapperError(format!(
+                    "cannot convert an array of {:?} into a raw tensor",
+                    self.dtype
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the array as `f32` values.
+    pub fn to_f32_vec(&self) -> Result<Vec<f32>, Error> {
+        fn convert<T: Element, const N: usize>(
+            data: &[u8],
+            from: impl Fn([u8; N]) -> T,
+        ) -> Vec<f32> {
+            data.chunks_exact(N)
+                .map(|b| from(b.try_into().unwrap()).to_f32())
+                .collect()
+        }
+        let data = &self.data;
+        let values = match self.dtype {
+            TensorType::I8 => convert(data, i8::from_ne_bytes),
+            TensorType::U8 => convert(data, u8::from_ne_bytes),
+            TensorType::I16 => convert(data, i16::from_ne_bytes),
+            TensorType::U16 => convert(data, u16::from_ne_bytes),
+            TensorType::I32 => convert(data, i32::from_ne_bytes),
+            TensorType::U32 => convert(data, u32::from_ne_bytes),
+            TensorType::I64 => convert(data, i64::from_ne_bytes),
+            TensorType::U64 => convert(data, u64::from_ne_bytes),
+            TensorType::F16 => data
+                .chunks_exact(2)
+                .map(|b| f16_to_f32(u16::from_ne_bytes([b[0], b[1]])))
+                .collect(),
+            TensorType::F32 => convert(data, f32::from_ne_bytes),
+            TensorType::F64 => convert(data, f64::from_ne_bytes),
+            TensorType::RAW | TensorType::STR => {
+                return Err(Error::WrapperError(String::from(
+                    "raw and string arrays have no numeric value",
+                )))
+            }
+        };
+        Ok(values)
+    }
+
+    /// Reads an array in the `.npy` format.  Big-endian data is swapped to
+    /// native order, Fortran ordered arrays are not supported.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        let mut preamble = [0u8; 8];
+        reader.read_exact(&mut preamble)?;
+        if &preamble[..6] != MAGIC {
+            return Err(Error::WrapperError(String::from("not a .npy file")));
+        }
+        let header_len = match preamble[6] {
+            1 => {
+                let mut len = [0u8; 2];
+                reader.read_exact(&mut len)?;
+                u16::from_le_bytes(len) as usize
+            }
+            2 | 3 => {
+                let mut len = [0u8; 4];
+                reader.read_exact(&mut len)?;
+                u32::from_le_bytes(len) as usize
+            }
+            version => {
+                return Err(Error::WrapperError(format!(
+                    "unsupported .npy version {}",
+                    version
+                )))
+            }
+        };
+        let mut header = vec![0u8; header_len];
+        reader.read_exact(&mut header)?;
+        let header = std::str::from_utf8(&header)?;
+
+        let descr = header_value(header, "descr")?;
+        let descr = descr.trim_matches(|c| c == '\'' || c == '"');
+        if header_value(header, "fortran_order")? != "False" {
+            return Err(Error::WrapperError(String::from(
+                "Fortran ordered arrays are not supported",
+            )));
+        }
+        let shape = parse_shape(header_value(header, "shape")?)?;
+        let (dtype, item_size, big_endian) = parse_descr(descr)?;
+        let elements = shape
+            .iter()
+            .try_fold(1usize, |n, d| n.checked_mul(*d))
+            .ok_or_else(|| Error::WrapperError(format!("array shape {:?} is too large", shape)))?;
+        if dtype == TensorType::STR && elements != 1 {
+            return Err(Error::WrapperError(format!(
+                "only scalar string arrays are supported but the shape is {:?}",
+                shape
+            )));
+        }
+
+        let count = match dtype {
+            TensorType::STR => item_size,
+            _ => elements.checked_mul(item_size).ok_or_else(|| {
+                Error::WrapperError(format!("array shape {:?} is too large", shape))
+            })?,
+        };
+        // The buffer grows with the data read so a corrupt header cannot
+        // request a huge allocation up front.
+        let mut data = Vec::new();
+        reader.take(count as u64).read_to_end(&mut data)?;
+        if data.len() != count {
+            return Err(Error::WrapperError(format!(
+                "array data holds {} bytes but {} are required",
+                data.len(),
+                count
+            )));
+        }
+        if big_endian != cfg!(target_endian = "big") && item_size > 1 && dtype != TensorType::STR {
+            for item in data.chunks_exact_mut(item_size) {
+                item.reverse();
+            }
+        }
+        if dtype == TensorType::STR {
+            let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+            data.truncate(end);
+        }
+        Ok(Array { dtype, shape, data })
+    }
+
+    /// Writes the array in the `.npy` format.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        let order = if cfg!(target_endian = "big") {
+            '>'
+        } else {
+            '<'
+        };
+        let descr = match self.dtype {
+            TensorType::RAW => String::from("|V1"),
+            TensorType::STR => format!("|S{}", self.data.len().max(1)),
+            TensorType::I8 => String::from("|i1"),
+            TensorType::U8 => String::from("|u1"),
+            TensorType::I16 => format!("{}i2", order),
+            TensorType::U16 => format!("{}u2", order),
+            TensorType::I32 => format!("{}i4", order),
+            TensorType::U32 => format!("{}u4", order),
+            TensorType::I64 => format!("{}i8", order),
+            TensorType::U64 => format!("{}u8", order),
+            TensorType::F16 => format!("{}f2", order),
+            TensorType::F32 => format!("{}f4", order),
+            TensorType::F64 => format!("{}f8", order),
+        };
+        let shape = match self.shape.as_slice() {
+            [] => String::from("()"),
+            [d] => format!("({},)", d),
+            shape => format!(
+                "({})",
+                shape
+                    .iter()
+                    .map(|d| d.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        let mut header = format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+            descr, shape
+        );
+        // The data starts on a 64 byte boundary, the header ends in a newline.
+        let mut preamble = MAGIC.len() + 2 + 2;
+        if preamble + header.len() + 1 > u16::MAX as usize {
+            preamble += 2;
+        }
+        let padding = (64 - (preamble + header.len() + 1) % 64) % 64;
+        header.extend(std::iter::repeat_n(' ', padding));
+        header.push('\n');
+
+        writer.write_all(MAGIC)?;
+        if preamble == MAGIC.len() + 4 {
+            writer.write_all(&[1, 0])?;
+            writer.write_all(&(header.len() as u16).to_le_bytes())?;
+        } else {
+            writer.write_all(&[2, 0])?;
+            writer.write_all(&(header.len() as u32).to_le_bytes())?;
+        }
+        writer.write_all(header.as_bytes())?;
+        writer.write_all(&self.data)?;
+        if self.dtype == TensorType::STR && self.data.is_empty() {
+            writer.write_all(&[0])?;
+        }
+        Ok(())
+    }
+}
+
+/// The raw text of `key` in a `.npy` header dictionary.
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str, Error> {
+    let missing = || Error::WrapperError(format!(".npy header has no {}", key));
+    let start = header
+        .find(&format!("'{}'", key))
+        .or_else(|| header.find(&format!("\"{}\"", key)))
+        .ok_or_else(missing)?;
+    let rest = header[start + key.len() + 2..].trim_start();
+    let rest = rest.strip_prefix(':').ok_or_else(missing)?.trim_start();
+    let end = if rest.starts_with('(') {
+        rest.find(')').map(|i| i + 1)
+    } else {
+        rest.find([',', '}'])
+    }
+    .ok_or_else(missing)?;
+    Ok(rest[..end].trim())
+}
+
+fn parse_shape(shape: &str) -> Result<Vec<usize>, Error> {
+    shape
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| {
+            d.trim_end_matches('L')
+                .parse()
+                .map_err(|_| Error::WrapperError(format!("invalid .npy shape {}", shape)))
+        })
+        .collect()
+}
+
+/// Tensor type, item size and byte order of a NumPy type description.
+fn parse_descr(descr: &str) -> Result<(TensorType, usize, bool), Error> {
+    let unsupported = || Error::WrapperError(format!("unsupported .npy dtype {}", descr));
+    let (order, kind) = match descr.chars().next() {
+        Some(c @ ('<' | '>' | '|' | '=')) => (c, &descr[1..]),
+        _ => ('=', descr),
+    };
+    let big_endian = match order {
+        '>' => true,
+        '<' => false,
+        _ => cfg!(target_endian = "big"),
+    };
+    let size: usize = kind
+        .get(1..)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(unsupported)?;
+    let dtype = match (&kind[..1], size) {
+        ("i", 1) => TensorType::I8,
+        ("u", 1) | ("b", 1) => TensorType::U8,
+        ("i", 2) => TensorType::I16,
+        ("u", 2) => TensorType::U16,
+        ("i", 4) => TensorType::I32,
+        ("u", 4) => TensorType::U32,
+        ("i", 8) => TensorType::I64,
+        ("u", 8) => TensorType::U64,
+        ("f", 2) => TensorType::F16,
+        ("f", 4) => TensorType::F32,
+        ("f", 8) => TensorType::F64,
+        ("V", 1) => TensorType::RAW,
+        ("S", _) => TensorType::STR,
+        _ => return Err(unsupported()),
+    };
+    Ok((dtype, size, big_endian))
+}
+
+impl Tensor {
+    /// Saves the tensor as a NumPy `.npy` file.
+    pub fn save_npy<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        Array::from_tensor(self)?.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Loads a NumPy `.npy` file into a new tensor of the matching type and
+    /// shape.
+    pub fn load_npy<P: AsRef<Path>>(path: P) -> Result<Tensor, Error> {
+        let mut reader = BufReader::new(File::open(path)?);
+        Array::read(&mut reader)?.to_tensor()
+    }
+}
+
+/// A NumPy `.npz` archive of named arrays.
+///
+/// Tensors are stored under their name with their quantization parameters
+/// alongside as `<name>.scales`, `<name>.zeros` and `<name>.axis`.
+#[cfg(feature = "npz")]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Npz {
+    arrays: Vec<(String, Array)>,
+}
+
+#[cfg(feature = "npz")]
+impl Npz {
+    pub fn new() -> Self {
+        Npz::default()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let mut archive = zip::ZipArchive::new(BufReader::new(File::open(path)?))
+            .map_err(|e| Error::WrapperError(e.to_string()))?;
+        let mut npz = Npz::new();
+        for index in 0..archive.len() {
+            let mut file = archive
+                .by_index(index)
+                .map_err(|e| Error::WrapperError(e.to_string()))?;
+            let name = file.name();
+            let name = name.strip_suffix(".npy").unwrap_or(name).to_string();
+            let array = Array::read(&mut file)?;
+            npz.insert(&name, array);
+        }
+        Ok(npz)
+    }
+
+    /// Saves the archive uncompressed, as `numpy.savez` does.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut writer = zip::ZipWriter::new(BufWriter::new(File::create(path)?));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored)
+            .large_file(true);
+        for (name, array) in &self.arrays {
+            writer
+                .start_file(format!("{}.npy", name), options)
+                .map_err(|e| Error::WrapperError(e.to_string()))?;
+            array.write(&mut writer)?;
+        }
+        writer
+            .finish()
+            .map_err(|e| Error::WrapperError(e.to_string()))?
+            .flush()?;
+        Ok(())
+    }
+
+    /// Names of the arrays in the archive, in insertion order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.arrays.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Array> {
+        self.arrays
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, array)| array)
+    }
+
+    /// Inserts an array, replacing any array of the same name.
+    pub fn insert(&mut self, name: &str, array: Array) {
+        match self.arrays.iter_mut().find(|(n, _)| n == name) {
+            Some((_, entry)) => *entry = array,
+            None => self.arrays.push((name.to_string(), array)),
+        }
+    }
+
+    /// Inserts a copy of `tensor` with its quantization parameters.
+    pub fn insert_tensor(&mut self, name: &str, tensor: &Tensor) -> Result<(), Error> {
+        self.insert(name, Array::from_tensor(tensor)?);
+        let quant = tensor.quant_params();
+        if quant.is_quantized() {
+            self.insert_quant_params(name, &quant);
+        }
+        Ok(())
+    }
+
+    /// Stores quantization parameters alongside the array `name`.
+    pub fn insert_quant_params(&mut self, name: &str, quant: &QuantParams) {
+        let scales = quant.scales.iter().flat_map(|s| s.to_ne_bytes()).collect();
+        let zeros = quant.zeros.iter().flat_map(|z| z.to_ne_bytes()).collect();
+        self.insert(
+            &format!("{}.scales", name),
+            Array {
+                dtype: TensorType::F32,
+                shape: vec![quant.scales.len()],
+                data: scales,
+            },
+        );
+        self.insert(
+            &format!("{}.zeros", name),
+            Array {
+                dtype: TensorType::I32,
+                shape: vec![quant.zeros.len()],
+                data: zeros,
+            },
+        );
+        self.insert(
+            &format!("{}.axis", name),
+            Array {
+                dtype: TensorType::I32,
+                shape: Vec::new(),
+                data: quant.axis.to_ne_bytes().to_vec(),
+            },
+        );
+    }
+
+    /// Quantization parameters stored alongside the array `name`, empty when
+    /// there are none.
+    pub fn quant_params(&self, name: &str) -> QuantParams {
+        let values = |suffix: &str| {
+            self.get(&format!("{}.{}", name, suffix))
+                .and_then(|a| a.to_f32_vec().ok())
+                .unwrap_or_default()
+        };
+        QuantParams {
+            scales: values("scales"),
+            zeros: values("zeros").iter().map(|z| *z as i32).collect(),
+            axis: values("axis").first().map_or(-1, |a| *a as i32),
+        }
+    }
+
+    /// Allocates a new tensor from the array `name` with its quantization
+    /// parameters.
+    pub fn tensor(&self, name: &str) -> Result<Tensor, Error> {
+        let array = self
+            .get(name)
+            .ok_or_else(|| Error::WrapperError(format!("{} not found in archive", name)))?;
+        let mut tensor = array.to_tensor()?;
+        let quant = self.quant_params(name);
+        if quant.is_quantized() {
+            tensor.set_quant_params(&quant)?;
+        }
+        Ok(tensor)
+    }
+}
+
+#[cfg(feature = "npz")]
+impl Context {
+    /// Fills every model input from the array of the same layer name in the
+    /// `.npz` archive at `path`, see [`Array::copy_into`] for conversions.
+    pub fn load_inputs_npz<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let npz = Npz::load(path)?;
+        let model = self
+            .model()
+            .ok_or_else(|| Error::WrapperError(String::from("no model loaded")))?;
+        let mut inputs = Vec::new();
+        for index in model.inputs()? {
+            let index = *index as usize;
+            let name = model.layer_name(index)?;
+            match npz.get(name) {
+                Some(array) => inputs.push((index, array)),
+                None => {
+                    return Err(Error::WrapperError(format!(
+                        "input {} not found in archive",
+                        name
+                    )))
+                }
+            }
+        }
+        for (index, array) in inputs {
+            array.copy_into(self.tensor_index_mut(index)?)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(array: &Array) -> Array {
+        let mut bytes = Vec::new();
+        array.write(&mut bytes).unwrap();
+        Array::read(&mut bytes.as_slice()).unwrap()
+    }
+
+    fn header(bytes: &[u8]) -> &str {
+        let len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        std::str::from_utf8(&bytes[10..10 + len]).unwrap()
+    }
+
+    fn npy(header: &str, data: &[u8]) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend([1, 0]);
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        bytes.extend(data);
+        bytes
+    }
+
+    #[test]
+    fn each_dtype() {
+        for (dtype, item_size) in [
+            (TensorType::I8, 1),
+            (TensorType::U8, 1),
+            (TensorType::I16, 2),
+            (TensorType::U16, 2),
+            (TensorType::I32, 4),
+            (TensorType::U32, 4),
+            (TensorType::I64, 8),
+            (TensorType::U64, 8),
+            (TensorType::F16, 2),
+            (TensorType::F32, 4),
+            (TensorType::F64, 8),
+            (TensorType::RAW, 1),
+        ] {
+            let array = Array {
+                dtype,
+                shape: vec![2, 3],
+                data: (0..6 * item_size).map(|i| i as u8).collect(),
+            };
+            assert_eq!(round_trip(&array), array, "{:?}", dtype);
+        }
+    }
+
+    #[test]
+    fn shapes() {
+        for shape in [vec![], vec![5], vec![1, 2, 2, 1]] {
+            let array = Array {
+                dtype: TensorType::U8,
+                data: vec![7; shape.iter().product()],
+                shape,
+            };
+            assert_eq!(round_trip(&array), array);
+        }
+    }
+
+    #[test]
+    fn string_scalar() {
+        let array = Array {
+            dtype: TensorType::STR,
+            shape: Vec::new(),
+            data: b"hello".to_vec(),
+        };
+        assert_eq!(round_trip(&array), array);
+
+        let bytes = npy(
+            "{'descr': '|S2', 'fortran_order': False, 'shape': (2,), }\n",
+            b"abcd",
+        );
+        assert!(Array::read(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn header_padding() {
+        for len in [0, 1, 40, 1000] {
+            let array = Array {
+                dtype: TensorType::U8,
+                shape: vec![len],
+                data: vec![0; len],
+            };
+            let mut bytes = Vec::new();
+            array.write(&mut bytes).unwrap();
+            let header = header(&bytes);
+            assert_eq!((10 + header.len()) % 64, 0);
+            assert!(header.ends_with('\n'));
+            assert_eq!(bytes.len(), 10 + header.len() + len);
+        }
+    }
+
+    #[test]
+    fn big_endian() {
+        let bytes = npy(
+            "{'descr': '>u2', 'fortran_order': False, 'shape': (2,), }\n",
+            &[0x01, 0x02, 0x03, 0x04],
+        );
+        let array = Array::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(array.to_f32_vec().unwrap(), [258.0, 772.0]);
+    }
+
+    #[test]
+    fn fortran_order() {
+        let bytes = npy(
+            "{'descr': '<f4', 'fortran_order': True, 'shape': (2, 2), }\n",
+            &[0; 16],
+        );
+        assert!(Array::read(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(Array::read(&mut &b"not numpy"[..]).is_err());
+        let bytes = npy(
+            "{'descr': '<c8', 'fortran_order': False, 'shape': (1,), }\n",
+            &[0; 8],
+        );
+        assert!(Array::read(&mut bytes.as_slice()).is_err());
+        // Truncated data.
+        let bytes = npy(
+            "{'descr': '<f4', 'fortran_order': False, 'shape': (2,), }\n",
+            &[0; 4],
+        );
+        assert!(Array::read(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn huge_shape() {
+        // Overflows usize when multiplied out.
+        let bytes = npy(
+            "{'descr': '<f4', 'fortran_order': False, 'shape': (4294967296, 4294967296), }\n",
+            &[0; 16],
+        );
+        assert!(Array::read(&mut bytes.as_slice()).is_err());
+        // Overflows only once multiplied by the item size.
+        let bytes = npy(
+            &format!(
+                "{{'descr': '<f8', 'fortran_order': False, 'shape': ({},), }}\n",
+                usize::MAX / 4
+            ),
+            &[0; 16],
+        );
+        assert!(Array::read(&mut bytes.as_slice()).is_err());
+        // Fits but is far larger than the data actually present.
+        let bytes = npy(
+            "{'descr': '<u1', 'fortran_order': False, 'shape': (1099511627776,), }\n",
+            &[0; 16],
+        );
+        assert!(Array::read(&mut bytes.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{
+    context::Context,
+    error::Error,
+    postprocess::{read_layer, sigmoid, softmax},
+};
+
+/// A single class prediction.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Classification {
+    pub index: usize,
+    pub label: Option<String>,
+    pub score: f32,
+}
+
+/// How the raw output of a classifier is turned into scores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Activation {
+    /// Treat the output as probabilities when it lies in `[0, 1]`, as produced
+    /// by a softmax or sigmoid layer, otherwise as logits for softmax.
+    #[default]
+    Auto,
+    /// The output already holds probabilities.
+    Probabilities,
+    /// The output holds logits of mutually exclusive classes.
+    Softmax,
+    /// The output holds independent logits of a multi-label classifier.
+    Sigmoid,
+}
+
+/// Returns the `k` highest scoring classes of the `output` layer, with labels
+/// from the model.  See [`top_k_with`] to select the activation explicitly.
+pub fn top_k(context: &Context, output: usize, k: usize) -> Result<Vec<Classification>, Error> {
+    top_k_with(context, output, k, Activation::Auto)
+}
+
+/// Returns the `k` highest scoring classes of the `output` layer after
+/// applying `activation`, sorted by descending score.
+pub fn top_k_with(
+    context: &Context,
+    output: usize,
+    k: usize,
+    activation: Activation,
+) -> Result<Vec<Classification>, Error> {
+    let (mut scores, _) = read_layer(context, output)?;
+    activate(&mut scores, activation);
+    let model = context.model();
+    let mut top = rank(&scores, k);
+    for class in top.iter_mut() {
+        class.label = model
+            .and_then(|m| m.label(class.index as i32).ok())
+            .map(String::from);
+    }
+    Ok(top)
+}
+
+/// Applies `activation` to raw classifier outputs in place.
+pub fn activate(values: &mut [f32], activation: Activation) {
+    let activation = match activation {
+        Activation::Auto if values.iter().all(|v| (0.0..=1.0).contains(v)) => {
+            Activation::Probabilities
+        }
+        Activation::Auto => Activation::Softmax,
+        other => other,
+    };
+    match activation {
+        Activation::Softmax => softmax(values),
+        Activation::Sigmoid => values.iter_mut().for_each(|v| *v = sigmoid(*v)),
+        _ => {}
+    }
+}
+
+/// The `k` highest scores, unlabelled, sorted by descending score.
+fn rank(scores: &[f32], k: usize) -> Vec<Classification> {
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    let k = k.min(order.len());
+    let by_score = |a: &usize, b: &usize| scores[*b].total_cmp(&scores[*a]);
+    if k > 0 && k < order.len() {
+        order.select_nth_unstable_by(k - 1, by_score);
+    }
+    order.truncate(k);
+    order.sort_by(by_score);
+    order
+        .into_iter()
+        .map(|index| Classification {
+            index,
+            label: None,
+            score: scores[index],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activated(values: &[f32], activation: Activation) -> Vec<f32> {
+        let mut values = values.to_vec();
+        activate(&mut values, activation);
+        values
+    }
+
+    #[test]
+    fn auto_logits() {
+        let scores = activated(&[2.0, 1.0, -1.0], Activation::Auto);
+        assert_eq!(scores, activated(&[2.0, 1.0, -1.0], Activation::Softmax));
+        assert!((scores.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+        assert!(scores[0] > scores[1] && scores[1] > scores[2]);
+    }
+
+    #[test]
+    fn auto_probabilities() {
+        let probabilities = [0.7, 0.2, 0.1];
+        assert_eq!(activated(&probabilities, Activation::Auto), probabilities);
+    }
+
+    #[test]
+    fn auto_sigmoid_outputs() {
+        // Independent class probabilities need not sum to one.
+        let probabilities = [0.9, 0.8, 0.1];
+        assert_eq!(activated(&probabilities, Activation::Auto), probabilities);
+    }
+
+    #[test]
+    fn explicit_activations() {
+        assert_eq!(activated(&[0.0], Activation::Sigmoid), [0.5]);
+        assert_eq!(
+            activated(&[3.0, -2.0], Activation::Probabilities),
+            [3.0, -2.0]
+        );
+    }
+
+    #[test]
+    fn rank_top_k() {
+        let scores = [0.1, 0.4, 0.3, 0.2];
+        let indices = |k| rank(&scores, k).iter().map(|c| c.index).collect::<Vec<_>>();
+        assert_eq!(indices(2), [1, 2]);
+        assert_eq!(indices(10), [1, 2, 3, 0]);
+        assert!(indices(0).is_empty());
+        assert_eq!(rank(&scores, 1)[0].score, 0.4);
+    }
+}
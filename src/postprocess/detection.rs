@@ -0,0 +1,904 @@
+use crate::{
+    context::Context,
+    error::Error,
+    model::Model,
+    postprocess::{input_size, read_layer, sigmoid},
+    transform::Transform,
+};
+
+/// A detected object.  The box is `[xmin, ymin, xmax, ymax]` in pixels of the
+/// model input tensor, use [`Detection::to_source`] to map it back through the
+/// preprocessing transform.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Detection {
+    pub bbox: [f32; 4],
+    pub score: f32,
+    pub class: usize,
+    pub label: Option<String>,
+}
+
+impl Detection {
+    /// Returns the detection with its box mapped into source image pixels.
+    pub fn to_source(&self, transform: &Transform) -> Detection {
+        Detection {
+            bbox: transform.box_to_source(self.bbox),
+            ..self.clone()
+        }
+    }
+}
+
+/// Intersection over union of two `[xmin, ymin, xmax, ymax]` boxes.
+pub fn iou(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+    let w = (a[2].min(b[2]) - a[0].max(b[0])).max(0.0);
+    let h = (a[3].min(b[3]) - a[1].max(b[1])).max(0.0);
+    let inter = w * h;
+    let area_a = (a[2] - a[0]).max(0.0) * (a[3] - a[1]).max(0.0);
+    let area_b = (b[2] - b[0]).max(0.0) * (b[3] - b[1]).max(0.0);
+    let union = area_a + area_b - inter;
+    if union <= 0.0 {
+        return 0.0;
+    }
+    inter / union
+}
+
+/// Coordinate encoding of boxes in an already decoded output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoxFormat {
+    #[default]
+    Xyxy,
+    Yxyx,
+    Cxcywh,
+}
+
+impl BoxFormat {
+    fn to_xyxy(self, b: [f32; 4]) -> [f32; 4] {
+        match self {
+            BoxFormat::Xyxy => b,
+            BoxFormat::Yxyx => [b[1], b[0], b[3], b[2]],
+            BoxFormat::Cxcywh => [
+                b[0] - b[2] / 2.0,
+                b[1] - b[3] / 2.0,
+                b[0] + b[2] / 2.0,
+                b[1] + b[3] / 2.0,
+            ],
+        }
+    }
+}
+
+/// Source of SSD anchor boxes, each anchor is `[cy, cx, h, w]` normalized to
+/// the input size.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Anchors {
+    Boxes(Vec<[f32; 4]>),
+    /// A model resource holding native endian `f32` anchors.
+    Resource(String),
+    /// A layer parameter holding `f32` anchors.
+    Parameter {
+        layer: usize,
+        key: String,
+    },
+}
+
+impl Anchors {
+    fn resolve(&self, model: &Model) -> Result<Vec<[f32; 4]>, Error> {
+        let values: Vec<f32> = match self {
+            Anchors::Boxes(boxes) => return Ok(boxes.clone()),
+            Anchors::Resource(name) => {
+                let resource = model.resource(name)?;
+                let data = resource.data().ok_or_else(|| {
+                    Error::WrapperError(format!("anchor resource {} has no data", name))
+                })?;
+                data.chunks_exact(4)
+                    .map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect()
+            }
+            Anchors::Parameter { layer, key } => {
+                model.layer_parameter(*layer, key)?.data_f32()?.to_vec()
+            }
+        };
+        Ok(values
+            .chunks_exact(4)
+            .map(|a| [a[0], a[1], a[2], a[3]])
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YoloVersion {
+    /// Rows of `[cx, cy, w, h, objectness, classes...]`.
+    V5,
+    /// Rows of `[cx, cy, w, h, classes...]`, usually stored transposed.
+    V8,
+}
+
+/// A raw YOLOv5 detection head of shape `[1, H, W, anchors * (5 + classes)]`
+/// holding logits, with anchor sizes in input pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YoloHead {
+    pub layer: usize,
+    pub stride: u32,
+    pub anchors: Vec<[f32; 2]>,
+}
+
+/// Layout of the detection outputs of a model, layers are given by index.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decoder {
+    /// SSD box encodings `[N, 4]` as `[ty, tx, th, tw]` relative to `anchors`
+    /// and class scores `[N, C]`.  When `background` is set the first class is
+    /// background and is excluded from the class indices.
+    Ssd {
+        boxes: usize,
+        scores: usize,
+        anchors: Anchors,
+        variances: [f32; 4],
+        background: bool,
+    },
+    /// Exported YOLO outputs with boxes already placed on the grid.  The
+    /// number of classes defaults to every column after the box, columns past
+    /// the classes (such as mask coefficients) are carried along.
+    Yolo {
+        output: usize,
+        version: YoloVersion,
+        classes: Option<usize>,
+        normalized: bool,
+    },
+    /// Raw YOLOv5 grid heads.
+    YoloGrid {
+        heads: Vec<YoloHead>,
+        classes: Option<usize>,
+    },
+    /// Outputs decoded by the model, such as TensorFlow detection
+    /// post-processing.  `scores` is either `[N]` alongside a `classes`
+    /// layer or `[N, C]`, `count` optionally limits the valid boxes.
+    Decoded {
+        boxes: usize,
+        scores: usize,
+        classes: Option<usize>,
+        count: Option<usize>,
+        format: BoxFormat,
+        normalized: bool,
+    },
+}
+
+/// Non-maximum suppression strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Nms {
+    None,
+    /// Suppress overlapping boxes of the same class.
+    #[default]
+    ClassAware,
+    /// Suppress overlapping boxes regardless of class.
+    ClassAgnostic,
+    /// Gaussian soft-NMS within each class, overlapping scores decay by
+    /// `exp(-iou^2 / sigma)` rather than being removed.
+    Soft {
+        sigma: f32,
+    },
+}
+
+/// A decoded box along with the trailing per-box values of the output, such
+/// as the mask coefficients of segmentation models.
+pub(crate) struct Candidate {
+    pub detection: Detection,
+    pub extra: Vec<f32>,
+}
+
+/// Decodes the detection outputs of a model and filters them through NMS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detector {
+    pub decoder: Decoder,
+    pub score_threshold: f32,
+    pub iou_threshold: f32,
+    pub nms: Nms,
+    pub max_detections: usize,
+}
+
+impl Detector {
+    pub fn new(decoder: Decoder) -> Self {
+        Detector {
+            decoder,
+            score_threshold: 0.25,
+            iou_threshold: 0.45,
+            nms: Nms::ClassAware,
+            max_detections: 100,
+        }
+    }
+
+    /// Decodes the outputs of the last run of `context`, sorted by descending
+    /// score and labelled from the model.
+    pub fn detect(&self, context: &Context) -> Result<Vec<Detection>, Error> {
+        let candidates = self.select(self.candidates(context)?);
+        Ok(candidates
+            .into_iter()
+            .map(|c| labelled(context, c.detection))
+            .collect())
+    }
+
+    /// Decodes every box scoring at least `score_threshold`, before NMS.
+    pub(crate) fn candidates(&self, context: &Context) -> Result<Vec<Candidate>, Error> {
+        let threshold = self.score_threshold;
+        match &self.decoder {
+            Decoder::Ssd {
+                boxes,
+                scores,
+                anchors,
+                variances,
+                background,
+            } => {
+                let model = context
+                    .model()
+                    .ok_or_else(|| Error::WrapperError(String::from("context has no model")))?;
+                let anchors = anchors.resolve(model)?;
+                let (boxes, _) = read_layer(context, *boxes)?;
+                let (scores, _) = read_layer(context, *scores)?;
+                let size = input_size(context)?;
+                decode_ssd(
+                    &boxes,
+                    &scores,
+                    &anchors,
+                    *variances,
+                    *background,
+                    size,
+                    threshold,
+                )
+            }
+            Decoder::Yolo {
+                output,
+                version,
+                classes,
+                normalized,
+            } => {
+                let (values, shape) = read_layer(context, *output)?;
+                let size = if *normalized {
+                    input_size(context)?
+                } else {
+                    (1.0, 1.0)
+                };
+                decode_yolo(&values, &shape, *version, *classes, size, threshold)
+            }
+            Decoder::YoloGrid { heads, classes } => {
+                let mut candidates = Vec::new();
+                for head in heads {
+                    let (values, shape) = read_layer(context, head.layer)?;
+                    candidates.extend(decode_yolo_head(
+                        &values, &shape, head, *classes, threshold,
+                    )?);
+                }
+                Ok(candidates)
+            }
+            Decoder::Decoded {
+                boxes,
+                scores,
+                classes,
+                count,
+                format,
+                normalized,
+            } => {
+                let (boxes, _) = read_layer(context, *boxes)?;
+                let (scores, _) = read_layer(context, *scores)?;
+                let classes = match classes {
+                    Some(layer) => Some(read_layer(context, *layer)?.0),
+                    None => None,
+                };
+                let count = match count {
+                    Some(layer) => read_layer(context, *layer)?.0.first().map(|c| *c as usize),
+                    None => None,
+                };
+                let size = if *normalized {
+                    input_size(context)?
+                } else {
+                    (1.0, 1.0)
+                };
+                decode_boxes(
+                    &boxes,
+                    &scores,
+                    classes.as_deref(),
+                    count,
+                    *format,
+                    size,
+                    threshold,
+                )
+            }
+        }
+    }
+
+    /// Returns a detector whose YOLO output holds classes followed by `extra`
+    /// trailing values per box.  When the class count isn't given it becomes
+    /// whatever remains after the box and the trailing values.
+    pub(crate) fn with_trailing(&self, context: &Context, extra: usize) -> Result<Detector, Error> {
+        let mut detector = self.clone();
+        if let Decoder::Yolo {
+            output,
+            version,
+            classes: classes @ None,
+            ..
+        } = &mut detector.decoder
+        {
+            let tensor = context.tensor_index(*output)?;
+            let dims = tensor.dims().clamp(2, 4) as usize;
+            let shape = &tensor.shape()[dims - 2..dims];
+            let attrs = shape[0].min(shape[1]).max(0) as usize;
+            let header = match version {
+                YoloVersion::V5 => 5,
+                YoloVersion::V8 => 4,
+            };
+            *classes = Some(attrs.saturating_sub(header + extra));
+        }
+        Ok(detector)
+    }
+
+    /// Applies NMS and the detection limit, sorted by descending score.
+    pub(crate) fn select(&self, candidates: Vec<Candidate>) -> Vec<Candidate> {
+        let kept = suppress(
+            &candidates,
+            |c| &c.detection,
+            self.nms,
+            self.iou_threshold,
+            self.score_threshold,
+        );
+        let mut candidates: Vec<Option<Candidate>> = candidates.into_iter().map(Some).collect();
+        kept.into_iter()
+            .take(self.max_detections)
+            .filter_map(|(index, score)| {
+                let mut candidate = candidates[index].take()?;
+                candidate.detection.score = score;
+                Some(candidate)
+            })
+            .collect()
+    }
+}
+
+/// Attaches the model label of the detection's class.
+pub(crate) fn labelled(context: &Context, mut detection: Detection) -> Detection {
+    detection.label = context
+        .model()
+        .and_then(|m| m.label(detection.class as i32).ok())
+        .map(String::from);
+    detection
+}
+
+/// Greedy non-maximum suppression, returns the surviving detections sorted by
+/// descending score.
+pub fn nms(detections: Vec<Detection>, iou_threshold: f32, class_aware: bool) -> Vec<Detection> {
+    let strategy = if class_aware {
+        Nms::ClassAware
+    } else {
+        Nms::ClassAgnostic
+    };
+    let kept = suppress(&detections, |d| d, strategy, iou_threshold, 0.0);
+    kept.into_iter()
+        .map(|(index, _)| detections[index].clone())
+        .collect()
+}
+
+/// Gaussian soft-NMS within each class, returns the detections whose decayed
+/// score remains at least `score_threshold` sorted by descending score.
+pub fn soft_nms(detections: Vec<Detection>, sigma: f32, score_threshold: f32) -> Vec<Detection> {
+    let kept = suppress(
+        &detections,
+        |d| d,
+        Nms::Soft { sigma },
+        0.0,
+        score_threshold,
+    );
+    kept.into_iter()
+        .map(|(index, score)| Detection {
+            score,
+            ..detections[index].clone()
+        })
+        .collect()
+}
+
+/// Returns the indices of the items surviving `strategy` with their final
+/// scores, sorted by descending score.
+fn suppress<T>(
+    items: &[T],
+    detection: impl Fn(&T) -> &Detection,
+    strategy: Nms,
+    iou_threshold: f32,
+    score_threshold: f32,
+) -> Vec<(usize, f32)> {
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|a, b| {
+        detection(&items[*b])
+            .score
+            .total_cmp(&detection(&items[*a]).score)
+    });
+
+    match strategy {
+        Nms::None => order
+            .into_iter()
+            .map(|i| (i, detection(&items[i]).score))
+            .collect(),
+        Nms::ClassAware | Nms::ClassAgnostic => {
+            let mut kept: Vec<(usize, f32)> = Vec::new();
+            for i in order {
+                let d = detection(&items[i]);
+                let overlaps = kept.iter().any(|(k, _)| {
+                    let other = detection(&items[*k]);
+                    (strategy == Nms::ClassAgnostic || other.class == d.class)
+                        && iou(&other.bbox, &d.bbox) > iou_threshold
+                });
+                if !overlaps {
+                    kept.push((i, d.score));
+                }
+            }
+            kept
+        }
+        Nms::Soft { sigma } => {
+            let mut remaining: Vec<(usize, f32)> = order
+                .into_iter()
+                .map(|i| (i, detection(&items[i]).score))
+                .collect();
+            let mut kept = Vec::new();
+            while !remaining.is_empty() {
+                let best = remaining
+                    .iter()
+                    .enumerate()
+                    .max_by(|a, b| a.1 .1.total_cmp(&b.1 .1))
+                    .map(|(pos, _)| pos)
+                    .unwrap_or(0);
+                let (index, score) = remaining.swap_remove(best);
+                kept.push((index, score));
+                let top = detection(&items[index]);
+                remaining.retain_mut(|(i, s)| {
+                    let d = detection(&items[*i]);
+                    if d.class == top.class {
+                        let overlap = iou(&top.bbox, &d.bbox);
+                        *s *= (-(overlap * overlap) / sigma).exp();
+                    }
+                    *s >= score_threshold
+                });
+            }
+            kept
+        }
+    }
+}
+
+/// Index and score of the best class in `scores`.
+fn best_class(scores: impl Iterator<Item = f32>) -> Option<(usize, f32)> {
+    scores.enumerate().max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+fn candidate(bbox: [f32; 4], score: f32, class: usize, extra: Vec<f32>) -> Candidate {
+    Candidate {
+        detection: Detection {
+            bbox,
+            score,
+            class,
+            label: None,
+        },
+        extra,
+    }
+}
+
+fn decode_ssd(
+    boxes: &[f32],
+    scores: &[f32],
+    anchors: &[[f32; 4]],
+    variances: [f32; 4],
+    background: bool,
+    (width, height): (f32, f32),
+    threshold: f32,
+) -> Result<Vec<Candidate>, Error> {
+    let n = boxes.len() / 4;
+    if n == 0 || anchors.len() != n || !scores.len().is_multiple_of(n) {
+        return Err(Error::WrapperError(format!(
+            "SSD outputs disagree: {} boxes, {} anchors and {} scores",
+            n,
+            anchors.len(),
+            scores.len()
+        )));
+    }
+    let classes = scores.len() / n;
+    let skip = background as usize;
+    if classes <= skip {
+        return Err(Error::WrapperError(format!(
+            "SSD scores hold {} classes per box, at least {} are required",
+            classes,
+            skip + 1
+        )));
+    }
+    let mut candidates = Vec::new();
+    for (i, anchor) in anchors.iter().enumerate() {
+        let row = &scores[i * classes + skip..(i + 1) * classes];
+        let Some((class, score)) = best_class(row.iter().copied()) else {
+            continue;
+        };
+        if score < threshold {
+            continue;
+        }
+        let b = &boxes[i * 4..i * 4 + 4];
+        let cy = b[0] * variances[0] * anchor[2] + anchor[0];
+        let cx = b[1] * variances[1] * anchor[3] + anchor[1];
+        let h = (b[2] * variances[2]).exp() * anchor[2];
+        let w = (b[3] * variances[3]).exp() * anchor[3];
+        let bbox = [
+            (cx - w / 2.0) * width,
+            (cy - h / 2.0) * height,
+            (cx + w / 2.0) * width,
+            (cy + h / 2.0) * height,
+        ];
+        candidates.push(candidate(bbox, score, class, Vec::new()));
+    }
+    Ok(candidates)
+}
+
+fn decode_yolo(
+    values: &[f32],
+    shape: &[usize],
+    version: YoloVersion,
+    classes: Option<usize>,
+    (width, height): (f32, f32),
+    threshold: f32,
+) -> Result<Vec<Candidate>, Error> {
+    if shape.len() < 2 {
+        return Err(Error::WrapperError(format!(
+            "YOLO output must have at least 2 dimensions but has shape {:?}",
+            shape
+        )));
+    }
+    let (a, b) = (shape[shape.len() - 2], shape[shape.len() - 1]);
+    // Boxes outnumber attributes, which tells rows from transposed columns.
+    let transposed = a < b;
+    let (n, attrs) = if transposed { (b, a) } else { (a, b) };
+    let get = |i: usize, j: usize| {
+        if transposed {
+            values[j * n + i]
+        } else {
+            values[i * attrs + j]
+        }
+    };
+    let header = match version {
+        YoloVersion::V5 => 5,
+        YoloVersion::V8 => 4,
+    };
+    let classes = classes.unwrap_or(attrs.saturating_sub(header));
+    if classes == 0 || header + classes > attrs {
+        return Err(Error::WrapperError(format!(
+            "YOLO output with {} attributes cannot hold {} classes",
+            attrs, classes
+        )));
+    }
+
+    let mut candidates = Vec::new();
+    for i in 0..n {
+        let objectness = match version {
+            YoloVersion::V5 => get(i, 4),
+            YoloVersion::V8 => 1.0,
+        };
+        let Some((class, score)) = best_class((0..classes).map(|c| get(i, header + c))) else {
+            continue;
+        };
+        let score = score * objectness;
+        if score < threshold {
+            continue;
+        }
+        let bbox = BoxFormat::Cxcywh.to_xyxy([get(i, 0), get(i, 1), get(i, 2), get(i, 3)]);
+        let bbox = [
+            bbox[0] * width,
+            bbox[1] * height,
+            bbox[2] * width,
+            bbox[3] * height,
+        ];
+        let extra = (header + classes..attrs).map(|j| get(i, j)).collect();
+        candidates.push(candidate(bbox, score, class, extra));
+    }
+    Ok(candidates)
+}
+
+fn decode_yolo_head(
+    values: &[f32],
+    shape: &[usize],
+    head: &YoloHead,
+    classes: Option<usize>,
+    threshold: f32,
+) -> Result<Vec<Candidate>, Error> {
+    let (h, w, channels) = match shape {
+        [1, h, w, c] | [h, w, c] => (*h, *w, *c),
+        _ => {
+            return Err(Error::WrapperError(format!(
+                "YOLO head must be NHWC but has shape {:?}",
+                shape
+            )))
+        }
+    };
+    let n_anchors = head.anchors.len();
+    if n_anchors == 0 || channels % n_anchors != 0 {
+        return Err(Error::WrapperError(format!(
+            "YOLO head with {} channels does not fit {} anchors",
+            channels, n_anchors
+        )));
+    }
+    let per_anchor = channels / n_anchors;
+    let classes = classes.unwrap_or(per_anchor.saturating_sub(5));
+    if classes == 0 || per_anchor != 5 + classes {
+        return Err(Error::WrapperError(format!(
+            "YOLO head with {} values per anchor cannot hold {} classes",
+            per_anchor, classes
+        )));
+    }
+
+    let stride = head.stride as f32;
+    let mut candidates = Vec::new();
+    for gy in 0..h {
+        for gx in 0..w {
+            for (k, anchor) in head.anchors.iter().enumerate() {
+                let base = (gy * w + gx) * channels + k * per_anchor;
+                let s = |j: usize| sigmoid(values[base + j]);
+                let objectness = s(4);
+                if objectness < threshold {
+                    continue;
+                }
+                let Some((class, score)) = best_class((0..classes).map(|c| s(5 + c))) else {
+                    continue;
+                };
+                let score = score * objectness;
+                if score < threshold {
+                    continue;
+                }
+                let cx = (s(0) * 2.0 - 0.5 + gx as f32) * stride;
+                let cy = (s(1) * 2.0 - 0.5 + gy as f32) * stride;
+                let bw = (s(2) * 2.0).powi(2) * anchor[0];
+                let bh = (s(3) * 2.0).powi(2) * anchor[1];
+                let bbox = BoxFormat::Cxcywh.to_xyxy([cx, cy, bw, bh]);
+                candidates.push(candidate(bbox, score, class, Vec::new()));
+            }
+        }
+    }
+    Ok(candidates)
+}
+
+fn decode_boxes(
+    boxes: &[f32],
+    scores: &[f32],
+    classes: Option<&[f32]>,
+    count: Option<usize>,
+    format: BoxFormat,
+    (width, height): (f32, f32),
+    threshold: f32,
+) -> Result<Vec<Candidate>, Error> {
+    let n = boxes.len() / 4;
+    if n == 0 || !scores.len().is_multiple_of(n) {
+        return Err(Error::WrapperError(format!(
+            "decoded outputs disagree: {} boxes and {} scores",
+            n,
+            scores.len()
+        )));
+    }
+    let per_box = scores.len() / n;
+    let n = count.map_or(n, |count| count.min(n));
+    let mut candidates = Vec::new();
+    for i in 0..n {
+        let (class, score) = match classes {
+            Some(classes) => (
+                classes.get(i).copied().unwrap_or(0.0).max(0.0) as usize,
+                scores[i * per_box],
+            ),
+            None => match best_class(scores[i * per_box..(i + 1) * per_box].iter().copied()) {
+                Some(best) => best,
+                None => continue,
+            },
+        };
+        if score < threshold {
+            continue;
+        }
+        let b = format.to_xyxy([
+            boxes[i * 4],
+            boxes[i * 4 + 1],
+            boxes[i * 4 + 2],
+            boxes[i * 4 + 3],
+        ]);
+        let bbox = [b[0] * width, b[1] * height, b[2] * width, b[3] * height];
+        candidates.push(candidate(bbox, score, class, Vec::new()));
+    }
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detection(bbox: [f32; 4], score: f32, class: usize) -> Detection {
+        Detection {
+            bbox,
+            score,
+            class,
+            label: None,
+        }
+    }
+
+    #[test]
+    fn iou_overlap() {
+        let a = [0.0, 0.0, 10.0, 10.0];
+        assert_eq!(iou(&a, &a), 1.0);
+        assert_eq!(iou(&a, &[10.0, 0.0, 20.0, 10.0]), 0.0);
+        assert_eq!(iou(&a, &[5.0, 0.0, 15.0, 10.0]), 50.0 / 150.0);
+        assert_eq!(iou(&a, &[2.0, 2.0, 2.0, 8.0]), 0.0);
+        assert_eq!(iou(&[1.0, 1.0, 1.0, 1.0], &[1.0, 1.0, 1.0, 1.0]), 0.0);
+    }
+
+    #[test]
+    fn nms_suppresses_overlaps() {
+        let detections = vec![
+            detection([0.0, 0.0, 10.0, 10.0], 0.8, 0),
+            detection([1.0, 0.0, 11.0, 10.0], 0.9, 0),
+            detection([20.0, 20.0, 30.0, 30.0], 0.5, 0),
+        ];
+        let kept = nms(detections, 0.5, true);
+        let scores: Vec<f32> = kept.iter().map(|d| d.score).collect();
+        assert_eq!(scores, [0.9, 0.5]);
+    }
+
+    #[test]
+    fn nms_class_separation() {
+        let detections = vec![
+            detection([0.0, 0.0, 10.0, 10.0], 0.9, 0),
+            detection([0.0, 0.0, 10.0, 10.0], 0.8, 1),
+        ];
+        assert_eq!(nms(detections.clone(), 0.5, true).len(), 2);
+        let kept = nms(detections, 0.5, false);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].class, 0);
+    }
+
+    #[test]
+    fn soft_nms_decay() {
+        let detections = vec![
+            detection([0.0, 0.0, 10.0, 10.0], 0.9, 0),
+            detection([5.0, 0.0, 15.0, 10.0], 0.8, 0),
+            detection([5.0, 0.0, 15.0, 10.0], 0.7, 1),
+        ];
+        let kept = soft_nms(detections, 0.5, 0.1);
+        assert_eq!(kept.len(), 3);
+        assert_eq!(kept[0].score, 0.9);
+        // The other class is left alone, the overlapping box decays.
+        assert_eq!((kept[1].class, kept[1].score), (1, 0.7));
+        let decayed = 0.8 * (-(1.0f32 / 3.0).powi(2) / 0.5).exp();
+        assert_eq!(kept[2].class, 0);
+        assert!((kept[2].score - decayed).abs() < 1e-6);
+
+        let kept = soft_nms(
+            vec![
+                detection([0.0, 0.0, 10.0, 10.0], 0.9, 0),
+                detection([0.0, 0.0, 10.0, 10.0], 0.2, 0),
+            ],
+            0.5,
+            0.1,
+        );
+        assert_eq!(kept.len(), 1);
+    }
+
+    #[test]
+    fn ssd_class_count() {
+        let boxes = [0.0; 4];
+        let anchors = [[0.5, 0.5, 1.0, 1.0]];
+        let decode = |scores: &[f32], background| {
+            decode_ssd(
+                &boxes,
+                scores,
+                &anchors,
+                [0.1, 0.1, 0.2, 0.2],
+                background,
+                (1.0, 1.0),
+                0.0,
+            )
+        };
+        assert!(decode(&[], true).is_err());
+        assert!(decode(&[], false).is_err());
+        assert!(decode(&[0.9], true).is_err());
+        let candidates = decode(&[0.1, 0.9], true).unwrap();
+        assert_eq!(candidates[0].detection.score, 0.9);
+        assert_eq!(decode(&[0.9], false).unwrap().len(), 1);
+    }
+
+    fn summary(candidates: &[Candidate]) -> Vec<([f32; 4], f32, usize)> {
+        candidates
+            .iter()
+            .map(|c| (c.detection.bbox, c.detection.score, c.detection.class))
+            .collect()
+    }
+
+    #[test]
+    fn yolo_v5_rows() {
+        // Eight rows of [cx, cy, w, h, objectness, 2 classes, 1 extra].
+        let mut values = vec![0.0; 8 * 8];
+        values[8..16].copy_from_slice(&[0.5, 0.5, 0.25, 0.5, 0.5, 0.25, 0.75, 0.3]);
+        let candidates = decode_yolo(
+            &values,
+            &[1, 8, 8],
+            YoloVersion::V5,
+            Some(2),
+            (64.0, 32.0),
+            0.25,
+        )
+        .unwrap();
+        assert_eq!(summary(&candidates), [([24.0, 8.0, 40.0, 24.0], 0.375, 1)]);
+        assert_eq!(candidates[0].extra, [0.3]);
+
+        let decode = |shape: &[usize], classes| {
+            decode_yolo(&values, shape, YoloVersion::V5, classes, (1.0, 1.0), 0.0)
+        };
+        assert!(decode(&[64], None).is_err());
+        assert!(decode(&[8, 8], Some(4)).is_err());
+        assert!(decode(&[16, 4], None).is_err());
+    }
+
+    #[test]
+    fn yolo_v8_transposed() {
+        // Six attribute columns of eight boxes, [cx, cy, w, h, 2 classes].
+        let mut values = vec![0.0; 6 * 8];
+        for (j, value) in [16.0, 16.0, 8.0, 4.0, 0.25, 0.5].into_iter().enumerate() {
+            values[j * 8 + 3] = value;
+        }
+        values[4 * 8 + 5] = 0.125;
+        let candidates =
+            decode_yolo(&values, &[1, 6, 8], YoloVersion::V8, None, (1.0, 1.0), 0.2).unwrap();
+        assert_eq!(summary(&candidates), [([12.0, 14.0, 20.0, 18.0], 0.5, 1)]);
+        assert!(candidates[0].extra.is_empty());
+    }
+
+    #[test]
+    fn yolo_grid_head() {
+        // A 2x2 grid of one anchor with [x, y, w, h, objectness, 2 classes]
+        // logits, only the bottom left cell holds an object.
+        let mut values = vec![-20.0; 2 * 2 * 7];
+        values[14..21].copy_from_slice(&[0.0, 0.0, 0.0, 0.0, 20.0, -20.0, 20.0]);
+        let head = YoloHead {
+            layer: 0,
+            stride: 8,
+            anchors: vec![[10.0, 20.0]],
+        };
+        let candidates = decode_yolo_head(&values, &[1, 2, 2, 7], &head, None, 0.5).unwrap();
+        assert_eq!(summary(&candidates), [([-1.0, 2.0, 9.0, 22.0], 1.0, 1)]);
+
+        assert!(decode_yolo_head(&values, &[4, 7], &head, None, 0.5).is_err());
+        assert!(decode_yolo_head(&values, &[2, 2, 7], &head, Some(3), 0.5).is_err());
+        let pair = YoloHead {
+            anchors: vec![[10.0, 20.0]; 2],
+            ..head
+        };
+        assert!(decode_yolo_head(&values, &[2, 2, 7], &pair, None, 0.5).is_err());
+    }
+
+    #[test]
+    fn decoded_boxes() {
+        // Three yxyx boxes with a separate classes output, limited to two.
+        let boxes = [
+            0.25, 0.5, 0.75, 1.0, //
+            0.0, 0.0, 1.0, 1.0, //
+            0.0, 0.0, 0.5, 0.5,
+        ];
+        let candidates = decode_boxes(
+            &boxes,
+            &[0.9, 0.2, 0.8],
+            Some(&[2.0, 1.0, 5.0]),
+            Some(2),
+            BoxFormat::Yxyx,
+            (64.0, 32.0),
+            0.5,
+        )
+        .unwrap();
+        assert_eq!(summary(&candidates), [([32.0, 8.0, 64.0, 24.0], 0.9, 2)]);
+
+        // Per-class scores of centered boxes.
+        let candidates = decode_boxes(
+            &boxes[..8],
+            &[0.1, 0.7, 0.2, 0.3, 0.3, 0.1],
+            None,
+            None,
+            BoxFormat::Cxcywh,
+            (1.0, 1.0),
+            0.5,
+        )
+        .unwrap();
+        assert_eq!(summary(&candidates), [([-0.125, 0.0, 0.625, 1.0], 0.7, 1)]);
+
+        let decode = |boxes: &[f32], scores: &[f32]| {
+            decode_boxes(boxes, scores, None, None, BoxFormat::Xyxy, (1.0, 1.0), 0.0)
+        };
+        assert!(decode(&[], &[]).is_err());
+        assert!(decode(&boxes[..8], &[0.5, 0.5, 0.5]).is_err());
+    }
+}
@@ -0,0 +1,511 @@
+use crate::{
+    context::Context,
+    error::Error,
+    postprocess::{
+        detection::{labelled, Decoder, Detection, Detector},
+        input_size, read_layer,
+    },
+    transform::Transform,
+};
+
+/// A keypoint in pixels of the model input tensor.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Keypoint {
+    pub x: f32,
+    pub y: f32,
+    pub score: f32,
+    pub name: Option<String>,
+}
+
+/// The keypoints of one subject, ordered as in the skeleton.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pose {
+    pub keypoints: Vec<Keypoint>,
+    pub score: f32,
+    /// The detection of the subject for multi-person models.
+    pub detection: Option<Detection>,
+}
+
+impl Pose {
+    /// Returns the pose mapped into source image pixels.
+    pub fn to_source(&self, transform: &Transform) -> Pose {
+        Pose {
+            keypoints: self
+                .keypoints
+                .iter()
+                .map(|k| {
+                    let (x, y) = transform.to_source(k.x, k.y);
+                    Keypoint { x, y, ..k.clone() }
+                })
+                .collect(),
+            score: self.score,
+            detection: self.detection.as_ref().map(|d| d.to_source(transform)),
+        }
+    }
+
+    /// Pairs of connected keypoints from `skeleton` where both ends score at
+    /// least `threshold`.
+    pub fn limbs<'a>(
+        &'a self,
+        skeleton: &Skeleton,
+        threshold: f32,
+    ) -> Vec<(&'a Keypoint, &'a Keypoint)> {
+        skeleton
+            .edges
+            .iter()
+            .filter_map(|(a, b)| Some((self.keypoints.get(*a)?, self.keypoints.get(*b)?)))
+            .filter(|(a, b)| a.score >= threshold && b.score >= threshold)
+            .collect()
+    }
+}
+
+/// Keypoint names and the edges connecting them.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Skeleton {
+    pub names: Vec<String>,
+    pub edges: Vec<(usize, usize)>,
+}
+
+const COCO17_NAMES: [&str; 17] = [
+    "nose",
+    "left_eye",
+    "right_eye",
+    "left_ear",
+    "right_ear",
+    "left_shoulder",
+    "right_shoulder",
+    "left_elbow",
+    "right_elbow",
+    "left_wrist",
+    "right_wrist",
+    "left_hip",
+    "right_hip",
+    "left_knee",
+    "right_knee",
+    "left_ankle",
+    "right_ankle",
+];
+
+const COCO17_EDGES: [(usize, usize); 19] = [
+    (15, 13),
+    (13, 11),
+    (16, 14),
+    (14, 12),
+    (11, 12),
+    (5, 11),
+    (6, 12),
+    (5, 6),
+    (5, 7),
+    (6, 8),
+    (7, 9),
+    (8, 10),
+    (1, 2),
+    (0, 1),
+    (0, 2),
+    (1, 3),
+    (2, 4),
+    (3, 5),
+    (4, 6),
+];
+
+impl Skeleton {
+    /// The 17 keypoint COCO person skeleton.
+    pub fn coco17() -> Self {
+        Skeleton {
+            names: COCO17_NAMES.iter().map(|n| n.to_string()).collect(),
+            edges: COCO17_EDGES.to_vec(),
+        }
+    }
+
+    /// Parses a skeleton configuration of `key = value` lines, `#` starts a
+    /// comment.  `keypoints` lists the comma separated keypoint names in
+    /// output order and each `edge` line connects two keypoints by name or
+    /// index.
+    ///
+    /// ```text
+    /// keypoints = head, neck, tail
+    /// edge = head, neck
+    /// edge = 1, 2
+    /// ```
+    pub fn parse(config: &str) -> Result<Self, Error> {
+        let mut skeleton = Skeleton::default();
+        let mut edges = Vec::new();
+        for (number, line) in config.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                Error::WrapperError(format!(
+                    "skeleton line {}: expected key = value",
+                    number + 1
+                ))
+            })?;
+            let items: Vec<&str> = value.split(',').map(str::trim).collect();
+            match key.trim() {
+                "keypoints" => skeleton.names = items.iter().map(|s| s.to_string()).collect(),
+                "edge" if items.len() == 2 => edges.push((number + 1, items[0], items[1])),
+                "edge" => {
+                    return Err(Error::WrapperError(format!(
+                        "skeleton line {}: an edge connects two keypoints",
+                        number + 1
+                    )))
+                }
+                other => {
+                    return Err(Error::WrapperError(format!(
+                        "skeleton line {}: unknown key {}",
+                        number + 1,
+                        other
+                    )))
+                }
+            }
+        }
+        for (number, a, b) in edges {
+            let a = skeleton.index(a).ok_or_else(|| unknown(number, a))?;
+            let b = skeleton.index(b).ok_or_else(|| unknown(number, b))?;
+            skeleton.edges.push((a, b));
+        }
+        Ok(skeleton)
+    }
+
+    /// Index of a keypoint given by name or by number.
+    fn index(&self, keypoint: &str) -> Option<usize> {
+        match keypoint.parse::<usize>() {
+            Ok(index) if self.names.is_empty() || index < self.names.len() => Some(index),
+            Ok(_) => None,
+            Err(_) => self.names.iter().position(|n| n == keypoint),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+fn unknown(line: usize, keypoint: &str) -> Error {
+    Error::WrapperError(format!(
+        "skeleton line {}: unknown keypoint {}",
+        line, keypoint
+    ))
+}
+
+/// Order of coordinates in a regression output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoordOrder {
+    #[default]
+    Xy,
+    Yx,
+}
+
+/// Layout of the keypoint outputs of a model, layers are given by index.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeypointDecoder {
+    /// Single subject heatmaps `[1, H, W, K]`, one channel per keypoint.
+    Heatmap { layer: usize },
+    /// Single subject coordinates `[.., K, 2]` or `[.., K, 3]` with a trailing
+    /// score, in input pixels or normalized to the input size.
+    Regression {
+        layer: usize,
+        order: CoordOrder,
+        normalized: bool,
+    },
+    /// Multi subject YOLO-pose, each detection carries `K * 3` trailing
+    /// values of `x, y, score` after its classes.
+    YoloPose { detector: Detector },
+}
+
+/// Decodes pose outputs into keypoints named by the skeleton.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoseDecoder {
+    pub decoder: KeypointDecoder,
+    pub skeleton: Skeleton,
+}
+
+impl PoseDecoder {
+    pub fn new(decoder: KeypointDecoder, skeleton: Skeleton) -> Self {
+        PoseDecoder { decoder, skeleton }
+    }
+
+    /// Decodes the poses of the last run of `context` in input tensor pixels,
+    /// use [`Pose::to_source`] to map them back through the preprocessing.
+    pub fn decode(&self, context: &Context) -> Result<Vec<Pose>, Error> {
+        let poses = match &self.decoder {
+            KeypointDecoder::Heatmap { layer } => {
+                let (values, shape) = read_layer(context, *layer)?;
+                let size = input_size(context)?;
+                vec![self.pose(decode_heatmaps(&values, &shape, size)?, None)]
+            }
+            KeypointDecoder::Regression {
+                layer,
+                order,
+                normalized,
+            } => {
+                let (values, shape) = read_layer(context, *layer)?;
+                let size = if *normalized {
+                    input_size(context)?
+                } else {
+                    (1.0, 1.0)
+                };
+                vec![self.pose(decode_regression(&values, &shape, *order, size)?, None)]
+            }
+            KeypointDecoder::YoloPose { detector } => self.decode_yolo(context, detector)?,
+        };
+        Ok(poses)
+    }
+
+    fn decode_yolo(&self, context: &Context, detector: &Detector) -> Result<Vec<Pose>, Error> {
+        let k = self.skeleton.len();
+        if k == 0 {
+            return Err(Error::WrapperError(String::from(
+                "YOLO-pose decoding requires a skeleton with keypoint names",
+            )));
+        }
+        let (width, height) = match detector.decoder {
+            Decoder::Yolo {
+                normalized: true, ..
+            } => input_size(context)?,
+            _ => (1.0, 1.0),
+        };
+        let detector = detector.with_trailing(context, k * 3)?;
+        let candidates = detector.select(detector.candidates(context)?);
+        let mut poses = Vec::with_capacity(candidates.len());
+        for candidate in candidates {
+            let keypoints = decode_yolo_keypoints(&candidate.extra, k, (width, height))?;
+            poses.push(self.pose(keypoints, Some(labelled(context, candidate.detection))));
+        }
+        Ok(poses)
+    }
+
+    fn pose(&self, points: Vec<(f32, f32, f32)>, detection: Option<Detection>) -> Pose {
+        let keypoints: Vec<Keypoint> = points
+            .into_iter()
+            .enumerate()
+            .map(|(i, (x, y, score))| Keypoint {
+                x,
+                y,
+                score,
+                name: self.skeleton.names.get(i).cloned(),
+            })
+            .collect();
+        let score = match &detection {
+            Some(detection) => detection.score,
+            None if keypoints.is_empty() => 0.0,
+            None => keypoints.iter().map(|k| k.score).sum::<f32>() / keypoints.len() as f32,
+        };
+        Pose {
+            keypoints,
+            score,
+            detection,
+        }
+    }
+}
+
+/// Peak of each heatmap channel refined to sub-pixel precision by fitting a
+/// parabola through the peak and its neighbours along each axis.
+fn decode_heatmaps(
+    values: &[f32],
+    shape: &[usize],
+    (width, height): (f32, f32),
+) -> Result<Vec<(f32, f32, f32)>, Error> {
+    let (h, w, k) = match shape {
+        [1, h, w, k] | [h, w, k] => (*h, *w, *k),
+        _ => {
+            return Err(Error::WrapperError(format!(
+                "heatmaps must be NHWC but have shape {:?}",
+                shape
+            )))
+        }
+    };
+    if h == 0 || w == 0 {
+        return Err(Error::WrapperError(String::from("heatmaps are empty")));
+    }
+    let at = |x: usize, y: usize, c: usize| values[(y * w + x) * k + c];
+    let refine = |lo: f32, mid: f32, hi: f32| {
+        let curvature = lo - 2.0 * mid + hi;
+        if curvature < 0.0 {
+            (0.5 * (lo - hi) / curvature).clamp(-0.5, 0.5)
+        } else {
+            0.0
+        }
+    };
+    let (stride_x, stride_y) = (width / w as f32, height / h as f32);
+
+    let mut points = Vec::with_capacity(k);
+    for c in 0..k {
+        let (mut px, mut py, mut peak) = (0, 0, f32::NEG_INFINITY);
+        for y in 0..h {
+            for x in 0..w {
+                if at(x, y, c) > peak {
+                    (px, py, peak) = (x, y, at(x, y, c));
+                }
+            }
+        }
+        let dx = if px > 0 && px + 1 < w {
+            refine(at(px - 1, py, c), peak, at(px + 1, py, c))
+        } else {
+            0.0
+        };
+        let dy = if py > 0 && py + 1 < h {
+            refine(at(px, py - 1, c), peak, at(px, py + 1, c))
+        } else {
+            0.0
+        };
+        points.push((
+            (px as f32 + dx + 0.5) * stride_x,
+            (py as f32 + dy + 0.5) * stride_y,
+            peak,
+        ));
+    }
+    Ok(points)
+}
+
+/// The `k` keypoints of `x, y, score` leading the trailing values of a
+/// YOLO-pose detection.
+fn decode_yolo_keypoints(
+    extra: &[f32],
+    k: usize,
+    (width, height): (f32, f32),
+) -> Result<Vec<(f32, f32, f32)>, Error> {
+    if extra.len() < k * 3 {
+        return Err(Error::WrapperError(format!(
+            "detections carry {} keypoint values but the skeleton needs {}",
+            extra.len(),
+            k * 3
+        )));
+    }
+    Ok(extra[..k * 3]
+        .chunks_exact(3)
+        .map(|v| (v[0] * width, v[1] * height, v[2]))
+        .collect())
+}
+
+fn decode_regression(
+    values: &[f32],
+    shape: &[usize],
+    order: CoordOrder,
+    (width, height): (f32, f32),
+) -> Result<Vec<(f32, f32, f32)>, Error> {
+    let per_point = shape.last().copied().unwrap_or(0);
+    if !matches!(per_point, 2 | 3) {
+        return Err(Error::WrapperError(format!(
+            "keypoint regression must end in 2 or 3 values but has shape {:?}",
+            shape
+        )));
+    }
+    Ok(values
+        .chunks_exact(per_point)
+        .map(|v| {
+            let (x, y) = match order {
+                CoordOrder::Xy => (v[0], v[1]),
+                CoordOrder::Yx => (v[1], v[0]),
+            };
+            let score = if per_point == 3 { v[2] } else { 1.0 };
+            (x * width, y * height, score)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_point(actual: (f32, f32, f32), expected: (f32, f32, f32)) {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-5;
+        assert!(
+            close(actual.0, expected.0)
+                && close(actual.1, expected.1)
+                && close(actual.2, expected.2),
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn heatmap_sub_pixel() {
+        // One 3x3 channel peaking in the center, leaning towards the right.
+        #[rustfmt::skip]
+        let values = [
+            0.0, 1.0, 0.0,
+            0.5, 2.0, 1.0,
+            0.0, 1.0, 0.0,
+        ];
+        let points = decode_heatmaps(&values, &[1, 3, 3, 1], (6.0, 6.0)).unwrap();
+        // dx = 0.5 * (0.5 - 1.0) / (0.5 - 4.0 + 1.0) = 0.1, dy = 0, stride 2.
+        assert_eq!(points.len(), 1);
+        assert_point(points[0], (3.2, 3.0, 2.0));
+    }
+
+    #[test]
+    fn heatmap_channels_and_edges() {
+        // Two 2x2 channels interleaved, peaks on the border are not refined.
+        let values = [0.1, 0.0, 0.9, 0.0, 0.0, 0.8, 0.0, 0.0];
+        let points = decode_heatmaps(&values, &[2, 2, 2], (4.0, 2.0)).unwrap();
+        assert_point(points[0], (3.0, 0.5, 0.9));
+        assert_point(points[1], (1.0, 1.5, 0.8));
+        assert!(decode_heatmaps(&values, &[8], (4.0, 2.0)).is_err());
+        assert!(decode_heatmaps(&[], &[1, 0, 2, 1], (4.0, 2.0)).is_err());
+    }
+
+    #[test]
+    fn regression() {
+        let values = [0.25, 0.5, 0.9, 0.75, 1.0, 0.1];
+        let points = decode_regression(&values, &[1, 2, 3], CoordOrder::Yx, (100.0, 10.0)).unwrap();
+        assert_point(points[0], (50.0, 2.5, 0.9));
+        assert_point(points[1], (100.0, 7.5, 0.1));
+
+        let points = decode_regression(&values[..4], &[2, 2], CoordOrder::Xy, (1.0, 1.0)).unwrap();
+        assert_point(points[1], (0.9, 0.75, 1.0));
+        assert!(decode_regression(&values, &[6], CoordOrder::Xy, (1.0, 1.0)).is_err());
+    }
+
+    #[test]
+    fn yolo_keypoints() {
+        let extra = [0.5, 0.25, 0.9, 1.0, 0.0, 0.2, 7.0];
+        let points = decode_yolo_keypoints(&extra, 2, (640.0, 480.0)).unwrap();
+        assert_eq!(points, [(320.0, 120.0, 0.9), (640.0, 0.0, 0.2)]);
+        assert!(decode_yolo_keypoints(&extra, 3, (1.0, 1.0)).is_err());
+    }
+
+    #[test]
+    fn visibility_threshold() {
+        let skeleton = Skeleton::parse("keypoints = a, b, c\nedge = a, b\nedge = b, 2").unwrap();
+        let decoder = PoseDecoder::new(KeypointDecoder::Heatmap { layer: 0 }, skeleton.clone());
+        let pose = decoder.pose(
+            vec![(0.0, 0.0, 0.9), (1.0, 1.0, 0.6), (2.0, 2.0, 0.3)],
+            None,
+        );
+        assert_eq!(pose.keypoints[2].name.as_deref(), Some("c"));
+        assert_point((pose.score, 0.0, 0.0), (0.6, 0.0, 0.0));
+
+        let names = |limbs: Vec<(&Keypoint, &Keypoint)>| -> Vec<(String, String)> {
+            limbs
+                .into_iter()
+                .map(|(a, b)| (a.name.clone().unwrap(), b.name.clone().unwrap()))
+                .collect()
+        };
+        assert_eq!(
+            names(pose.limbs(&skeleton, 0.5)),
+            [("a".into(), "b".into())]
+        );
+        assert_eq!(pose.limbs(&skeleton, 0.3).len(), 2);
+        assert!(pose.limbs(&skeleton, 0.95).is_empty());
+    }
+
+    #[test]
+    fn skeleton_config() {
+        assert!(Skeleton::parse("edge = a, b").is_err());
+        assert!(Skeleton::parse("keypoints = a, b\nedge = a").is_err());
+        assert!(Skeleton::parse("colour = red").is_err());
+        let skeleton =
+            Skeleton::parse("# comment\nkeypoints = a, b # trailing\nedge = 1, a").unwrap();
+        assert_eq!(skeleton.edges, [(1, 0)]);
+        assert_eq!(Skeleton::coco17().len(), 17);
+    }
+}
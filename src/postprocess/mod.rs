@@ -0,0 +1,67 @@
+pub mod classification;
+pub mod detection;
+pub mod keypoints;
+pub mod segmentation;
+
+use crate::{context::Context, error::Error};
+
+/// Reads layer `index` of the context's model as `f32` values along with its
+/// shape.  Quantized layers are dequantized through the layer's scales and
+/// zero-points, falling back to the tensor's own parameters.
+pub(crate) fn read_layer(context: &Context, index: usize) -> Result<(Vec<f32>, Vec<usize>), Error> {
+    let model = context
+        .model()
+        .ok_or_else(|| Error::WrapperError(String::from("context has no model loaded")))?;
+    let tensor = context.tensor_index(index)?;
+    let mut quant = model.layer_quant_params(index);
+    if !quant.is_quantized() {
+        quant = tensor.quant_params();
+    }
+    let values = tensor.to_f32_vec(&quant)?;
+    let dims = tensor.dims().clamp(0, 4) as usize;
+    let shape = tensor.shape()[..dims]
+        .iter()
+        .map(|d| (*d).max(0) as usize)
+        .collect();
+    Ok((values, shape))
+}
+
+/// Width and height of the model's first NHWC input tensor.
+pub(crate) fn input_size(context: &Context) -> Result<(f32, f32), Error> {
+    let model = context
+        .model()
+        .ok_or_else(|| Error::WrapperError(String::from("context has no model loaded")))?;
+    let input = match model.inputs()?.first() {
+        Some(input) => *input as usize,
+        None => return Err(Error::WrapperError(String::from("model has no inputs"))),
+    };
+    let tensor = context.tensor_index(input)?;
+    let shape = tensor.shape();
+    match tensor.dims() {
+        3 => Ok((shape[1] as f32, shape[0] as f32)),
+        4 => Ok((shape[2] as f32, shape[1] as f32)),
+        dims => Err(Error::WrapperError(format!(
+            "expected an NHWC input tensor but got {} dimensions",
+            dims
+        ))),
+    }
+}
+
+pub(crate) fn softmax(values: &mut [f32]) {
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let mut sum = 0.0;
+    for v in values.iter_mut() {
+        *v = (*v - max).exp();
+        sum += *v;
+    }
+    if sum > 0.0 {
+        for v in values.iter_mut() {
+            *v /= sum;
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn sigmoid(value: f32) -> f32 {
+    1.0 / (1.0 + (-value).exp())
+}
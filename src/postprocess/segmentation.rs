@@ -0,0 +1,406 @@
+use crate::{
+    context::Context,
+    error::Error,
+    model::Model,
+    postprocess::{
+        detection::{labelled, Detection, Detector},
+        input_size, read_layer, sigmoid,
+    },
+    tensor::QuantParams,
+    transform::Transform,
+};
+
+/// A row-major map of class indices, or of 0/1 for instance masks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mask {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl Mask {
+    pub fn new(width: u32, height: u32) -> Self {
+        Mask {
+            width,
+            height,
+            data: vec![0; width as usize * height as usize],
+        }
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> u8 {
+        self.data[y as usize * self.width as usize + x as usize]
+    }
+
+    /// Resizes a mask covering the `input_width` x `input_height` model input
+    /// into the source image of `transform` with nearest neighbour sampling,
+    /// undoing letterbox padding or center-crop.  Source pixels which fall
+    /// outside the input are set to 0.
+    pub fn to_source(&self, transform: &Transform, input_width: u32, input_height: u32) -> Mask {
+        let mut out = Mask::new(transform.source_width, transform.source_height);
+        let sx = self.width as f32 / input_width as f32;
+        let sy = self.height as f32 / input_height as f32;
+        for y in 0..out.height {
+            for x in 0..out.width {
+                let (tx, ty) = transform.to_tensor(x as f32 + 0.5, y as f32 + 0.5);
+                let (mx, my) = ((tx * sx).floor(), (ty * sy).floor());
+                if mx < 0.0 || my < 0.0 || mx >= self.width as f32 || my >= self.height as f32 {
+                    continue;
+                }
+                out.data[(y * out.width + x) as usize] = self.get(mx as u32, my as u32);
+            }
+        }
+        out
+    }
+
+    /// Expands the mask into packed RGB through `palette`.
+    pub fn to_rgb(&self, palette: &Palette) -> Vec<u8> {
+        self.data
+            .iter()
+            .flat_map(|index| palette.color(*index as usize))
+            .collect()
+    }
+}
+
+/// Per-pixel argmax of a semantic segmentation output `[1, H, W, C]`.
+///
+/// Per-tensor quantized outputs are compared in the quantized domain as the
+/// affine mapping preserves order.  Single channel outputs are treated as
+/// foreground probabilities and thresholded at 0.5.
+pub fn argmax(context: &Context, output: usize) -> Result<Mask, Error> {
+    let tensor = context.tensor_index(output)?;
+    let dims = tensor.dims().clamp(0, 4) as usize;
+    let (height, width, channels) = match tensor.shape()[..dims] {
+        [1, h, w, c] | [h, w, c] => (h.max(0) as usize, w.max(0) as usize, c.max(0) as usize),
+        ref shape => {
+            return Err(Error::WrapperError(format!(
+                "segmentation output must be NHWC but has shape {:?}",
+                shape
+            )))
+        }
+    };
+    if channels > 256 {
+        return Err(Error::WrapperError(format!(
+            "masks hold at most 256 classes but the output has {}",
+            channels
+        )));
+    }
+
+    let mut quant = context
+        .model()
+        .map(|m| m.layer_quant_params(output))
+        .unwrap_or_default();
+    if !quant.is_quantized() {
+        quant = tensor.quant_params();
+    }
+    let values = if quant.is_per_channel() || channels == 1 {
+        tensor.to_f32_vec(&quant)?
+    } else {
+        tensor.to_f32_vec(&QuantParams::default())?
+    };
+
+    Ok(argmax_values(&values, width, height, channels))
+}
+
+/// Per-pixel argmax of `height` x `width` x `channels` scores.
+fn argmax_values(values: &[f32], width: usize, height: usize, channels: usize) -> Mask {
+    let mut mask = Mask::new(width as u32, height as u32);
+    for (pixel, out) in mask.data.iter_mut().enumerate() {
+        let scores = &values[pixel * channels..(pixel + 1) * channels];
+        *out = if channels == 1 {
+            (scores[0] > 0.5) as u8
+        } else {
+            scores
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map_or(0, |(class, _)| class as u8)
+        };
+    }
+    mask
+}
+
+/// Prototype masks `[H, W, K]` of a YOLO-seg model.
+struct Prototypes<'a> {
+    values: &'a [f32],
+    width: usize,
+    height: usize,
+    k: usize,
+}
+
+impl Prototypes<'_> {
+    /// The mask of one instance: the prototypes weighted by `coefficients`
+    /// through a sigmoid, thresholded and cropped to `bbox` given in
+    /// prototype pixels.
+    fn mask(&self, coefficients: &[f32], bbox: [f32; 4], threshold: f32) -> Mask {
+        let (width, height, k) = (self.width, self.height, self.k);
+        let x0 = bbox[0].floor().clamp(0.0, width as f32) as usize;
+        let y0 = bbox[1].floor().clamp(0.0, height as f32) as usize;
+        let x1 = bbox[2].ceil().clamp(0.0, width as f32) as usize;
+        let y1 = bbox[3].ceil().clamp(0.0, height as f32) as usize;
+
+        let mut mask = Mask::new(width as u32, height as u32);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let proto = &self.values[(y * width + x) * k..(y * width + x + 1) * k];
+                let value: f32 = proto.iter().zip(coefficients).map(|(p, c)| p * c).sum();
+                mask.data[y * width + x] = (sigmoid(value) > threshold) as u8;
+            }
+        }
+        mask
+    }
+}
+
+/// A detected instance with its binary mask at prototype resolution.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InstanceMask {
+    pub detection: Detection,
+    pub mask: Mask,
+}
+
+/// YOLO-seg instance segmentation, combining the mask coefficients carried by
+/// each detection with the prototype masks of the `protos` layer
+/// `[1, H, W, K]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceSegmenter {
+    pub detector: Detector,
+    pub protos: usize,
+    pub mask_threshold: f32,
+}
+
+impl InstanceSegmenter {
+    pub fn new(detector: Detector, protos: usize) -> Self {
+        InstanceSegmenter {
+            detector,
+            protos,
+            mask_threshold: 0.5,
+        }
+    }
+
+    /// Decodes the instances of the last run of `context`.  Each mask is
+    /// cropped to its detection box and covers the whole model input.
+    pub fn segment(&self, context: &Context) -> Result<Vec<InstanceMask>, Error> {
+        let (protos, shape) = read_layer(context, self.protos)?;
+        let (height, width, k) = match shape.as_slice() {
+            [1, h, w, k] | [h, w, k] => (*h, *w, *k),
+            _ => {
+                return Err(Error::WrapperError(format!(
+                    "prototype masks must be NHWC but have shape {:?}",
+                    shape
+                )))
+            }
+        };
+        let detector = self.detector.with_trailing(context, k)?;
+        let candidates = detector.select(detector.candidates(context)?);
+        let (input_width, input_height) = input_size(context)?;
+        let sx = width as f32 / input_width;
+        let sy = height as f32 / input_height;
+        let prototypes = Prototypes {
+            values: &protos,
+            width,
+            height,
+            k,
+        };
+
+        let mut instances = Vec::with_capacity(candidates.len());
+        for candidate in candidates {
+            if candidate.extra.len() < k {
+                return Err(Error::WrapperError(format!(
+                    "detections carry {} mask coefficients but there are {} prototypes",
+                    candidate.extra.len(),
+                    k
+                )));
+            }
+            let bbox = candidate.detection.bbox;
+            let bbox = [bbox[0] * sx, bbox[1] * sy, bbox[2] * sx, bbox[3] * sy];
+            let mask = prototypes.mask(&candidate.extra[..k], bbox, self.mask_threshold);
+            instances.push(InstanceMask {
+                detection: labelled(context, candidate.detection),
+                mask,
+            });
+        }
+        Ok(instances)
+    }
+}
+
+/// Colors of an indexed mask, one entry per model label.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Palette {
+    pub labels: Vec<Option<String>>,
+    pub colors: Vec<[u8; 3]>,
+}
+
+impl Palette {
+    /// Builds a palette from the model labels.  Colors are derived from the
+    /// label text so a class keeps its color across models, a label named
+    /// "background" is black.
+    pub fn from_model(model: &Model) -> Self {
+        let count = model.label_count().unwrap_or(0).max(0);
+        let mut palette = Palette::default();
+        for index in 0..count {
+            let label = model.label(index).ok().map(String::from);
+            let color = match label.as_deref() {
+                Some(label) if label.eq_ignore_ascii_case("background") => [0, 0, 0],
+                Some(label) => label_color(label),
+                None => label_color(&index.to_string()),
+            };
+            palette.labels.push(label);
+            palette.colors.push(color);
+        }
+        palette
+    }
+
+    /// Overrides the color of every entry with the given label, returns
+    /// whether any entry matched.
+    pub fn set(&mut self, label: &str, color: [u8; 3]) -> bool {
+        let mut found = false;
+        for (entry, c) in self.labels.iter().zip(self.colors.iter_mut()) {
+            if entry.as_deref() == Some(label) {
+                *c = color;
+                found = true;
+            }
+        }
+        found
+    }
+
+    /// Color of a class index, indices past the model labels get a color
+    /// derived from the index.
+    pub fn color(&self, index: usize) -> [u8; 3] {
+        match self.colors.get(index) {
+            Some(color) => *color,
+            None => label_color(&index.to_string()),
+        }
+    }
+
+    /// The full 256 entry color table, as used by indexed-color images.
+    pub fn table(&self) -> [[u8; 3]; 256] {
+        let mut table = [[0; 3]; 256];
+        for (index, entry) in table.iter_mut().enumerate() {
+            *entry = self.color(index);
+        }
+        table
+    }
+}
+
+/// A stable, reasonably bright color derived from a label with FNV-1a.
+pub fn label_color(label: &str) -> [u8; 3] {
+    let hash = label.bytes().fold(0x811c_9dc5u32, |h, b| {
+        (h ^ b as u32).wrapping_mul(0x0100_0193)
+    });
+    [
+        64 + (hash % 192) as u8,
+        64 + ((hash >> 8) % 192) as u8,
+        64 + ((hash >> 16) % 192) as u8,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn argmax_classes() {
+        // 2x1 pixels of 3 classes.
+        let values = [0.1, 0.7, 0.2, 0.5, -1.0, 0.6];
+        let mask = argmax_values(&values, 2, 1, 3);
+        assert_eq!((mask.width, mask.height), (2, 1));
+        assert_eq!(mask.data, [1, 2]);
+    }
+
+    #[test]
+    fn argmax_foreground() {
+        let mask = argmax_values(&[0.2, 0.9, 0.5, 0.51], 2, 2, 1);
+        assert_eq!(mask.data, [0, 1, 0, 1]);
+        assert_eq!(mask.get(1, 1), 1);
+    }
+
+    #[test]
+    fn instance_mask() {
+        // 3x2 prototypes of two channels, the first positive on the left
+        // column and the second on the right.
+        #[rustfmt::skip]
+        let values = [
+            4.0, -4.0,  0.0, 0.0,  -4.0, 4.0,
+            4.0, -4.0,  0.0, 0.0,  -4.0, 4.0,
+        ];
+        let prototypes = Prototypes {
+            values: &values,
+            width: 3,
+            height: 2,
+            k: 2,
+        };
+        let whole = [0.0, 0.0, 3.0, 2.0];
+        assert_eq!(
+            prototypes.mask(&[1.0, 0.0], whole, 0.5).data,
+            [1, 0, 0, 1, 0, 0]
+        );
+        assert_eq!(
+            prototypes.mask(&[0.0, 1.0], whole, 0.5).data,
+            [0, 0, 1, 0, 0, 1]
+        );
+        // The middle column has a sigmoid of exactly 0.5, kept below 0.5.
+        assert_eq!(
+            prototypes.mask(&[1.0, 0.0], whole, 0.4).data,
+            [1, 1, 0, 1, 1, 0]
+        );
+        // Cropped to the first row, fractional edges round outwards.
+        let top = [0.5, 0.0, 2.2, 0.9];
+        assert_eq!(
+            prototypes.mask(&[1.0, 0.0], top, 0.4).data,
+            [1, 1, 0, 0, 0, 0]
+        );
+        // A box outside the prototypes is clamped to an empty mask.
+        let outside = [-5.0, 3.0, -1.0, 9.0];
+        assert_eq!(prototypes.mask(&[1.0, 0.0], outside, 0.4).data, [0; 6]);
+    }
+
+    #[test]
+    fn palette_colors() {
+        let mut palette = Palette {
+            labels: vec![Some(String::from("background")), Some(String::from("cat"))],
+            colors: vec![[0, 0, 0], label_color("cat")],
+        };
+        assert!(palette.set("cat", [255, 0, 0]));
+        assert!(!palette.set("dog", [0, 255, 0]));
+        assert_eq!(palette.color(1), [255, 0, 0]);
+        // Indices past the labels get a stable derived color.
+        assert_eq!(palette.color(7), label_color("7"));
+        assert_eq!(palette.table()[1], [255, 0, 0]);
+
+        let mask = Mask {
+            width: 3,
+            height: 1,
+            data: vec![0, 1, 7],
+        };
+        let mut rgb = vec![0, 0, 0, 255, 0, 0];
+        rgb.extend(label_color("7"));
+        assert_eq!(mask.to_rgb(&palette), rgb);
+    }
+
+    #[test]
+    fn label_colors() {
+        assert_eq!(label_color("cat"), label_color("cat"));
+        assert_ne!(label_color("cat"), label_color("dog"));
+        assert!(label_color("").iter().all(|c| *c >= 64));
+    }
+
+    #[test]
+    fn mask_to_source() {
+        let mask = Mask {
+            width: 2,
+            height: 2,
+            data: vec![1, 2, 3, 4],
+        };
+        // A 2x2 mask over a 4x4 input is upsampled to the 4x4 source.
+        let source = mask.to_source(&Transform::identity(4, 4), 4, 4);
+        assert_eq!(
+            source.data,
+            [1, 1, 2, 2, 1, 1, 2, 2, 3, 3, 4, 4, 3, 3, 4, 4]
+        );
+        // Letterboxing a 4x2 source into the 4x4 input pads rows 0 and 3.
+        let letterbox = Transform::new(crate::transform::ResizeMode::Letterbox, 4, 2, 4, 4);
+        let source = mask.to_source(&letterbox, 4, 4);
+        assert_eq!(source.data, [1, 1, 2, 2, 3, 3, 4, 4]);
+    }
+}
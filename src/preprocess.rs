@@ -0,0 +1,628 @@
+use crate::{
+    error::Error,
+    tensor::{f32_to_f16, Element, MappedDataMut, QuantParams, Tensor, TensorType},
+    transform::{ResizeMode, Transform},
+};
+
+/// Pixel layout of a raw camera frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 8-bit luma only.
+    Gray,
+    /// Packed 8-bit RGB.
+    Rgb,
+    /// Packed 8-bit BGR.
+    Bgr,
+    /// Packed 8-bit RGBA, alpha is ignored.
+    Rgba,
+    /// Packed 8-bit BGRA, alpha is ignored.
+    Bgra,
+    /// Packed 4:2:2 YUV as Y0 U Y1 V, BT.601 limited range.
+    Yuyv,
+    /// Planar 4:2:0 YUV with a Y plane followed by an interleaved UV plane,
+    /// BT.601 limited range.
+    Nv12,
+}
+
+impl PixelFormat {
+    /// Bytes per pixel in the first plane, for YUYV this is the average.
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            PixelFormat::Gray | PixelFormat::Nv12 => 1,
+            PixelFormat::Yuyv => 2,
+            PixelFormat::Rgb | PixelFormat::Bgr => 3,
+            PixelFormat::Rgba | PixelFormat::Bgra => 4,
+        }
+    }
+}
+
+/// A borrowed raw frame buffer.
+///
+/// `stride` is the number of bytes between rows of the first plane.  For NV12
+/// the UV plane starts at `uv_offset` bytes into `data` with rows `uv_stride`
+/// bytes apart.
+#[derive(Debug, Clone, Copy)]
+pub struct Frame<'a> {
+    pub data: &'a [u8],
+    pub format: PixelFormat,
+    pub width: u32,
+    pub height: u32,
+    pub stride: usize,
+    pub uv_offset: usize,
+    pub uv_stride: usize,
+}
+
+impl<'a> Frame<'a> {
+    /// Creates a frame with tightly packed rows and, for NV12, the UV plane
+    /// immediately following the Y plane.
+    pub fn new(data: &'a [u8], format: PixelFormat, width: u32, height: u32) -> Self {
+        let stride = width as usize * format.bytes_per_pixel();
+        Self::with_stride(data, format, width, height, stride)
+    }
+
+    /// Creates a frame with padded rows, for NV12 the UV plane is assumed to
+    /// follow the Y plane with the same stride.
+    pub fn with_stride(
+        data: &'a [u8],
+        format: PixelFormat,
+        width: u32,
+        height: u32,
+        stride: usize,
+    ) -> Self {
+        Frame {
+            data,
+            format,
+            width,
+            height,
+            stride,
+            uv_offset: stride * height as usize,
+            uv_stride: stride,
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.width == 0 || self.height == 0 {
+            return Err(Error::WrapperError(String::from("frame is empty")));
+        }
+        if self.format == PixelFormat::Yuyv && !self.width.is_multiple_of(2) {
+            return Err(Error::WrapperError(String::from(
+                "YUYV frames require an even width",
+            )));
+        }
+        let row = self.width as usize * self.format.bytes_per_pixel();
+        if self.stride < row {
+            return Err(Error::WrapperError(format!(
+                "frame stride {} is smaller than a row of {} bytes",
+                self.stride, row
+            )));
+        }
+        let mut required = self.stride * (self.height as usize - 1) + row;
+        if self.format == PixelFormat::Nv12 {
+            let uv_row = (self.width as usize).div_ceil(2) * 2;
+            let uv_rows = (self.height as usize).div_ceil(2);
+            required = required.max(self.uv_offset + self.uv_stride * (uv_rows - 1) + uv_row);
+        }
+        if self.data.len() < required {
+            return Err(Error::WrapperError(format!(
+                "frame buffer holds {} bytes but {} are required",
+                self.data.len(),
+                required
+            )));
+        }
+        Ok(())
+    }
+
+    /// Converts the pixel at `(x, y)` to RGB.
+    #[inline]
+    fn rgb(&self, x: usize, y: usize) -> [f32; 3] {
+        let d = self.data;
+        let row = y * self.stride;
+        match self.format {
+            PixelFormat::Gray => {
+                let v = d[row + x] as f32;
+                [v, v, v]
+            }
+            PixelFormat::Rgb => {
+                let p = row + x * 3;
+                [d[p] as f32, d[p + 1] as f32, d[p + 2] as f32]
+            }
+            PixelFormat::Bgr => {
+                let p = row + x * 3;
+                [d[p + 2] as f32, d[p + 1] as f32, d[p] as f32]
+            }
+            PixelFormat::Rgba => {
+                let p = row + x * 4;
+                [d[p] as f32, d[p + 1] as f32, d[p + 2] as f32]
+            }
+            PixelFormat::Bgra => {
+                let p = row + x * 4;
+                [d[p + 2] as f32, d[p + 1] as f32, d[p] as f32]
+            }
+            PixelFormat::Yuyv => {
+                let p = row + (x / 2) * 4;
+                yuv_to_rgb(d[p + (x % 2) * 2], d[p + 1], d[p + 3])
+            }
+            PixelFormat::Nv12 => {
+                let uv = self.uv_offset + (y / 2) * self.uv_stride + (x / 2) * 2;
+                yuv_to_rgb(d[row + x], d[uv], d[uv + 1])
+            }
+        }
+    }
+}
+
+/// BT.601 limited range YUV to RGB.
+#[inline]
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> [f32; 3] {
+    let c = 1.164 * (y as f32 - 16.0);
+    let d = u as f32 - 128.0;
+    let e = v as f32 - 128.0;
+    [
+        (c + 1.596 * e).clamp(0.0, 255.0),
+        (c - 0.392 * d - 0.813 * e).clamp(0.0, 255.0),
+        (c + 2.017 * d).clamp(0.0, 255.0),
+    ]
+}
+
+/// Memory layout of the input tensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Layout {
+    /// Channels last, `[N, H, W, C]`.
+    #[default]
+    Hwc,
+    /// Channels first, `[N, C, H, W]`.
+    Chw,
+}
+
+/// Channel order written into three channel tensors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChannelOrder {
+    #[default]
+    Rgb,
+    Bgr,
+}
+
+/// A region of interest in source frame pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Roi {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Single pass frame preprocessing into an input tensor.
+///
+/// Each output pixel is bilinearly sampled from the region of interest of the
+/// frame, converted to RGB and normalized as `(pixel - mean) / std` with
+/// pixels scaled to `[0, 1]`.  `mean` and `std` are given in output channel
+/// order.  Float tensors receive the normalized value and quantized tensors
+/// receive it quantized through the tensor's scales and zero-points.
+/// Unquantized integer tensors receive it scaled back to `[0, 255]`, shifted
+/// by -128 for `I8` tensors without quantization parameters.  With the
+/// defaults this matches `Tensor::write_image`, writing pixels in RGB order
+/// into an NHWC tensor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preprocessor {
+    pub roi: Option<Roi>,
+    pub resize: ResizeMode,
+    pub layout: Layout,
+    pub order: ChannelOrder,
+    pub mean: [f32; 3],
+    pub std: [f32; 3],
+}
+
+impl Default for Preprocessor {
+    fn default() -> Self {
+        Preprocessor {
+            roi: None,
+            resize: ResizeMode::Stretch,
+            layout: Layout::Hwc,
+            order: ChannelOrder::Rgb,
+            mean: [0.0; 3],
+            std: [1.0; 3],
+        }
+    }
+}
+
+impl Preprocessor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Converts `frame` into `tensor`, returning the transform from frame
+    /// coordinates to tensor coordinates.  Letterbox padding is written as a
+    /// black pixel.
+    pub fn apply(&self, frame: &Frame, tensor: &mut Tensor) -> Result<Transform, Error> {
+        frame.validate()?;
+        let roi = self.roi.unwrap_or(Roi {
+            x: 0,
+            y: 0,
+            width: frame.width,
+            height: frame.height,
+        });
+        let fits = |origin: u32, extent: u32, size: u32| {
+            origin.checked_add(extent).is_some_and(|end| end <= size)
+        };
+        if roi.width == 0
+            || roi.height == 0
+            || !fits(roi.x, roi.width, frame.width)
+            || !fits(roi.y, roi.height, frame.height)
+        {
+            return Err(Error::WrapperError(format!(
+                "region of interest {:?} is outside the {}x{} frame",
+                roi, frame.width, frame.height
+            )));
+        }
+
+        let (height, width, channels) = self.tensor_shape(tensor)?;
+        let fit = Transform::new(self.resize, roi.width, roi.height, width, height);
+        let channel_axis = match self.layout {
+            Layout::Hwc => tensor.dims() - 1,
+            Layout::Chw => tensor.dims() - 3,
+        };
+        let encoder = PixelEncoder::new(tensor.quant_params(), tensor.tensor_type(), channel_axis);
+        let pass = Pass {
+            frame,
+            roi,
+            fit,
+            width: width as usize,
+            height: height as usize,
+            channels,
+            layout: self.layout,
+            order: self.order,
+            mean: self.mean,
+            std: self.std,
+        };
+
+        let mut map = tensor.mapwo()?;
+        match &mut *map {
+            MappedDataMut::I8(dst) => pass.run(|i, c, v| dst[i] = encoder.encode(v, c)),
+            MappedDataMut::U8(dst) => pass.run(|i, c, v| dst[i] = encoder.encode(v, c)),
+            MappedDataMut::I16(dst) => pass.run(|i, c, v| dst[i] = encoder.encode(v, c)),
+            MappedDataMut::U16(dst) => pass.run(|i, c, v| dst[i] = encoder.encode(v, c)),
+            MappedDataMut::I32(dst) => pass.run(|i, c, v| dst[i] = encoder.encode(v, c)),
+            MappedDataMut::U32(dst) => pass.run(|i, c, v| dst[i] = encoder.encode(v, c)),
+            MappedDataMut::I64(dst) => pass.run(|i, c, v| dst[i] = encoder.encode(v, c)),
+            MappedDataMut::U64(dst) => pass.run(|i, c, v| dst[i] = encoder.encode(v, c)),
+            MappedDataMut::F32(dst) => pass.run(|i, _, v| dst[i] = v),
+            MappedDataMut::F64(dst) => pass.run(|i, _, v| dst[i] = v as f64),
+            MappedDataMut::F16(dst) => pass
+                .run(|i, _, v| dst[i * 2..i * 2 + 2].copy_from_slice(&f32_to_f16(v).to_ne_bytes())),
+            MappedDataMut::RAW(_) => {
+                return Err(Error::WrapperError(String::from(
+                    "preprocess: unsupported tensor type",
+                )));
+            }
+        }
+
+        // Express the transform relative to the full frame rather than the ROI.
+        Ok(Transform {
+            offset_x: fit.offset_x - roi.x as f32 * fit.scale_x,
+            offset_y: fit.offset_y - roi.y as f32 * fit.scale_y,
+            source_width: frame.width,
+            source_height: frame.height,
+            ..fit
+        })
+    }
+
+    /// Returns the height, width and channels of the tensor for this layout.
+    fn tensor_shape(&self, tensor: &Tensor) -> Result<(u32, u32, usize), Error> {
+        let shape = tensor.shape();
+        let dims = tensor.dims();
+        let (h, w, c) = match (dims, self.layout) {
+            (3, Layout::Hwc) => (shape[0], shape[1], shape[2]),
+            (4, Layout::Hwc) if shape[0] == 1 => (shape[1], shape[2], shape[3]),
+            (3, Layout::Chw) => (shape[1], shape[2], shape[0]),
+            (4, Layout::Chw) if shape[0] == 1 => (shape[2], shape[3], shape[1]),
+            _ => {
+                return Err(Error::WrapperError(format!(
+                    "expected a {:?} image tensor with a batch of 1 but got shape {:?}",
+                    self.layout,
+                    &shape[..dims.clamp(0, 4) as usize]
+                )));
+            }
+        };
+        if !matches!(c, 1 | 3) || h <= 0 || w <= 0 {
+            return Err(Error::WrapperError(format!(
+                "unsupported image tensor with height {} width {} and {} channels",
+                h, w, c
+            )));
+        }
+        Ok((h as u32, w as u32, c as usize))
+    }
+}
+
+/// Stores normalized pixels, `[0, 1]` before mean and std, into integer
+/// tensor elements.  Shared by [`Preprocessor`] and `Tensor::write_image` so
+/// both follow the convention documented on [`Preprocessor`].
+pub(crate) struct PixelEncoder {
+    quant: QuantParams,
+    per_channel: bool,
+    shift: f32,
+}
+
+impl PixelEncoder {
+    /// `channel_axis` is the tensor dimension holding the image channels.
+    pub(crate) fn new(quant: QuantParams, tensor_type: TensorType, channel_axis: i32) -> Self {
+        let per_channel = quant.is_per_channel() && quant.axis == channel_axis;
+        let shift =
+            if tensor_type == TensorType::I8 && quant.scales.is_empty() && quant.zeros.is_empty() {
+                -128.0
+            } else {
+                0.0
+            };
+        PixelEncoder {
+            quant,
+            per_channel,
+            shift,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn encode<T: Element>(&self, value: f32, channel: usize) -> T {
+        let value = if self.quant.is_quantized() {
+            self.quant
+                .quantize(value, if self.per_channel { channel } else { 0 })
+        } else {
+            value * 255.0 + self.shift
+        };
+        T::from_f32(value)
+    }
+}
+
+/// Per-call state of a preprocessing pass.
+struct Pass<'a, 'b> {
+    frame: &'a Frame<'b>,
+    roi: Roi,
+    fit: Transform,
+    width: usize,
+    height: usize,
+    channels: usize,
+    layout: Layout,
+    order: ChannelOrder,
+    mean: [f32; 3],
+    std: [f32; 3],
+}
+
+/// Bilinear sampling position along one axis.
+#[derive(Clone, Copy)]
+struct Tap {
+    lo: usize,
+    hi: usize,
+    weight: f32,
+    inside: bool,
+}
+
+impl<'a, 'b> Pass<'a, 'b> {
+    fn taps(size: usize, origin: u32, extent: u32, scale: f32, offset: f32) -> Vec<Tap> {
+        let resized = (extent as f32 * scale).round() as i64;
+        (0..size)
+            .map(|i| {
+                let inside = (i as i64) >= offset as i64 && (i as i64) < offset as i64 + resized;
+                // Sample at pixel centers.
+                let src = ((i as f32 + 0.5 - offset) / scale - 0.5).clamp(0.0, (extent - 1) as f32);
+                let lo = src.floor() as usize;
+                let hi = (lo + 1).min(extent as usize - 1);
+                Tap {
+                    lo: lo + origin as usize,
+                    hi: hi + origin as usize,
+                    weight: src - lo as f32,
+                    inside,
+                }
+            })
+            .collect()
+    }
+
+    fn run(&self, mut store: impl FnMut(usize, usize, f32)) {
+        let xs = Self::taps(
+            self.width,
+            self.roi.x,
+            self.roi.width,
+            self.fit.scale_x,
+            self.fit.offset_x,
+        );
+        let ys = Self::taps(
+            self.height,
+            self.roi.y,
+            self.roi.height,
+            self.fit.scale_y,
+            self.fit.offset_y,
+        );
+        let plane = self.width * self.height;
+        for (y, ty) in ys.iter().enumerate() {
+            for (x, tx) in xs.iter().enumerate() {
+                let rgb = if tx.inside && ty.inside {
+                    let p00 = self.frame.rgb(tx.lo, ty.lo);
+                    let p01 = self.frame.rgb(tx.hi, ty.lo);
+                    let p10 = self.frame.rgb(tx.lo, ty.hi);
+                    let p11 = self.frame.rgb(tx.hi, ty.hi);
+                    let mut rgb = [0.0; 3];
+                    for c in 0..3 {
+                        let top = p00[c] + (p01[c] - p00[c]) * tx.weight;
+                        let bottom = p10[c] + (p11[c] - p10[c]) * tx.weight;
+                        rgb[c] = top + (bottom - top) * ty.weight;
+                    }
+                    rgb
+                } else {
+                    [0.0; 3]
+                };
+
+                let pixel = y * self.width + x;
+                if self.channels == 1 {
+                    let luma = 0.299 * rgb[0] + 0.587 * rgb[1] + 0.114 * rgb[2];
+                    store(pixel, 0, (luma / 255.0 - self.mean[0]) / self.std[0]);
+                    continue;
+                }
+                for c in 0..3 {
+                    let src = match self.order {
+                        ChannelOrder::Rgb => c,
+                        ChannelOrder::Bgr => 2 - c,
+                    };
+                    let value = (rgb[src] / 255.0 - self.mean[c]) / self.std[c];
+                    let index = match self.layout {
+                        Layout::Hwc => pixel * 3 + c,
+                        Layout::Chw => c * plane + pixel,
+                    };
+                    store(index, c, value);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(encoder: &PixelEncoder, pixels: &[u8]) -> Vec<i8> {
+        pixels
+            .iter()
+            .map(|p| encoder.encode(*p as f32 / 255.0, 0))
+            .collect()
+    }
+
+    #[test]
+    fn i8_shift() {
+        let pixels = [0, 128, 255];
+        let raw = PixelEncoder::new(QuantParams::default(), TensorType::I8, 3);
+        assert_eq!(encode(&raw, &pixels), [-128, 0, 127]);
+
+        let zeros = QuantParams {
+            zeros: vec![0],
+            ..QuantParams::default()
+        };
+        let zeros = PixelEncoder::new(zeros, TensorType::I8, 3);
+        assert_eq!(encode(&zeros, &pixels), [0, 127, 127]);
+    }
+
+    #[test]
+    fn quantized() {
+        let quant = QuantParams {
+            scales: vec![1.0 / 255.0],
+            zeros: vec![-128],
+            axis: -1,
+        };
+        let encoder = PixelEncoder::new(quant, TensorType::I8, 3);
+        assert_eq!(encode(&encoder, &[0, 128, 255]), [-128, 0, 127]);
+    }
+
+    #[test]
+    fn per_channel() {
+        let quant = QuantParams {
+            scales: vec![1.0 / 255.0, 0.5],
+            zeros: vec![0, 10],
+            axis: 3,
+        };
+        let encoder = PixelEncoder::new(quant.clone(), TensorType::U8, 3);
+        assert_eq!(encoder.encode::<u8>(1.0, 0), 255);
+        assert_eq!(encoder.encode::<u8>(1.0, 1), 12);
+        // Parameters along another axis fall back to the first channel.
+        let encoder = PixelEncoder::new(quant, TensorType::U8, 1);
+        assert_eq!(encoder.encode::<u8>(1.0, 1), 255);
+    }
+
+    /// Samples the ROI of `frame` into a `width` by `height` RGB image in
+    /// `[0, 255]`, rounded to whole pixel values.
+    fn sample(frame: &Frame, roi: Roi, width: usize, height: usize, layout: Layout) -> Vec<f32> {
+        let pass = Pass {
+            frame,
+            roi,
+            fit: Transform::new(
+                ResizeMode::Stretch,
+                roi.width,
+                roi.height,
+                width as u32,
+                height as u32,
+            ),
+            width,
+            height,
+            channels: 3,
+            layout,
+            order: ChannelOrder::Rgb,
+            mean: [0.0; 3],
+            std: [1.0; 3],
+        };
+        let mut pixels = vec![0.0; width * height * 3];
+        pass.run(|i, _, v| pixels[i] = (v * 255.0).round());
+        pixels
+    }
+
+    fn whole(frame: &Frame) -> Roi {
+        Roi {
+            x: 0,
+            y: 0,
+            width: frame.width,
+            height: frame.height,
+        }
+    }
+
+    fn rgb(frame: &Frame, x: usize, y: usize) -> [f32; 3] {
+        frame.rgb(x, y).map(f32::round)
+    }
+
+    #[test]
+    fn nv12_to_rgb() {
+        // A 4x2 frame, each 2x2 block shares one UV pair: red then neutral.
+        let data = [81, 81, 235, 16, 81, 81, 235, 16, 90, 240, 128, 128];
+        let frame = Frame::new(&data, PixelFormat::Nv12, 4, 2);
+        frame.validate().unwrap();
+        assert_eq!(rgb(&frame, 1, 1), [254.0, 0.0, 0.0]);
+        assert_eq!(rgb(&frame, 2, 1), [255.0, 255.0, 255.0]);
+        assert_eq!(rgb(&frame, 3, 0), [0.0, 0.0, 0.0]);
+        assert!(Frame::new(&data[..10], PixelFormat::Nv12, 4, 2)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn yuyv_to_rgb() {
+        // Y0 U Y1 V, two pixels sharing the blue chroma.
+        let data = [41, 240, 16, 110];
+        let frame = Frame::new(&data, PixelFormat::Yuyv, 2, 1);
+        frame.validate().unwrap();
+        assert_eq!(rgb(&frame, 0, 0), [0.0, 0.0, 255.0]);
+        assert_eq!(rgb(&frame, 1, 0), [0.0, 0.0, 226.0]);
+        assert!(Frame::new(&data[..2], PixelFormat::Yuyv, 1, 1)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn bilinear_resize() {
+        let data = [0, 255];
+        let frame = Frame::new(&data, PixelFormat::Gray, 2, 1);
+        let pixels = sample(&frame, whole(&frame), 4, 1, Layout::Hwc);
+        let red: Vec<f32> = pixels.iter().step_by(3).copied().collect();
+        // Pixel centers map to 0, 0.25, 0.75 and 1 source pixels.
+        assert_eq!(red, [0.0, 64.0, 191.0, 255.0]);
+    }
+
+    #[test]
+    fn chw_layout() {
+        let data = [10, 20, 30, 40, 50, 60];
+        let frame = Frame::new(&data, PixelFormat::Rgb, 2, 1);
+        let roi = whole(&frame);
+        assert_eq!(
+            sample(&frame, roi, 2, 1, Layout::Hwc),
+            [10.0, 20.0, 30.0, 40.0, 50.0, 60.0]
+        );
+        assert_eq!(
+            sample(&frame, roi, 2, 1, Layout::Chw),
+            [10.0, 40.0, 20.0, 50.0, 30.0, 60.0]
+        );
+    }
+
+    #[test]
+    fn roi_crop() {
+        let data = [0, 10, 20, 30, 40, 50, 60, 70, 80];
+        let frame = Frame::new(&data, PixelFormat::Gray, 3, 3);
+        let roi = Roi {
+            x: 1,
+            y: 1,
+            width: 2,
+            height: 2,
+        };
+        let pixels = sample(&frame, roi, 2, 2, Layout::Chw);
+        assert_eq!(pixels[..4], [40.0, 50.0, 70.0, 80.0]);
+    }
+}
@@ -0,0 +1,197 @@
+//! Benchmarking of model runs.
+
+use crate::{context::Context, error::Error, tensor::Tensor};
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// Latency statistics in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Latency {
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl Latency {
+    /// Statistics of `samples`, all zero when there are none.  Percentiles
+    /// use the nearest rank.
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Latency::default();
+        }
+        let mut ms: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1e3).collect();
+        ms.sort_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            let rank = (p / 100.0 * ms.len() as f64).ceil() as usize;
+            ms[rank.clamp(1, ms.len()) - 1]
+        };
+        Latency {
+            min: ms[0],
+            mean: ms.iter().sum::<f64>() / ms.len() as f64,
+            p50: percentile(50.0),
+            p90: percentile(90.0),
+            p99: percentile(99.0),
+            max: ms[ms.len() - 1],
+        }
+    }
+}
+
+/// Mean time of a layer over the measured runs as reported by
+/// `nn_tensor_time`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayerProfile {
+    pub index: usize,
+    pub name: String,
+    pub layer_type: String,
+    /// Milliseconds.
+    pub time: f64,
+}
+
+/// Results of a [`Benchmark`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BenchmarkReport {
+    pub warmup: usize,
+    pub iterations: usize,
+    /// Mapping and writing the input tensors.
+    pub input_map: Latency,
+    /// `nn_context_run`.
+    pub run: Latency,
+    /// Mapping and reading the output tensors.
+    pub output_map: Latency,
+    /// Whole iterations.
+    pub total: Latency,
+    /// Iterations per second.
+    pub throughput: f64,
+    /// Per-layer times in layer order, empty unless profiling.
+    pub layers: Vec<LayerProfile>,
+}
+
+/// Runs a model repeatedly and measures each step of an inference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Benchmark {
+    /// Iterations run before measuring.
+    pub warmup: usize,
+    pub iterations: usize,
+    /// Collect the time of every layer after each measured run.
+    pub profile: bool,
+}
+
+impl Default for Benchmark {
+    fn default() -> Self {
+        Benchmark {
+            warmup: 10,
+            iterations: 100,
+            profile: false,
+        }
+    }
+}
+
+/// Handles to the tensors of `layers` which do not borrow the context, so the
+/// inputs can be mapped mutably between runs.
+fn handles(context: &Context, layers: &[u32]) -> Result<Vec<Tensor>, Error> {
+    layers
+        .iter()
+        .map(|index| {
+            let ptr = context.tensor_index(*index as usize)?.to_mut_ptr();
+            unsafe { Tensor::from_ptr(ptr, false) }
+        })
+        .collect()
+}
+
+impl Benchmark {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Benchmarks the model loaded in `context`.  Each iteration writes the
+    /// current contents of the input tensors back into them, runs the model
+    /// and copies out the output tensors.
+    pub fn run(&self, context: &Context) -> Result<BenchmarkReport, Error> {
+        if self.iterations == 0 {
+            return Err(Error::WrapperError(String::from(
+                "benchmark needs at least one iteration",
+            )));
+        }
+        let model = context
+            .model()
+            .ok_or_else(|| Error::WrapperError(String::from("context has no model loaded")))?;
+        let mut inputs = handles(context, model.inputs()?)?;
+        let outputs = handles(context, model.outputs()?)?;
+        let layers: Vec<u32> = if self.profile {
+            (0..model.layer_count() as u32).collect()
+        } else {
+            Vec::new()
+        };
+        let layer_tensors = handles(context, &layers)?;
+        let input_data = inputs
+            .iter()
+            .map(|tensor| Ok(tensor.mapro()?.as_bytes().to_vec()))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut input_map = Vec::with_capacity(self.iterations);
+        let mut run = Vec::with_capacity(self.iterations);
+        let mut output_map = Vec::with_capacity(self.iterations);
+        let mut total = Vec::with_capacity(self.iterations);
+        let mut layer_times = vec![0i64; layer_tensors.len()];
+        let mut buffer = Vec::new();
+        for iteration in 0..self.warmup + self.iterations {
+            let start = Instant::now();
+            for (tensor, data) in inputs.iter_mut().zip(&input_data) {
+                tensor.mapwo()?.as_bytes_mut().copy_from_slice(data);
+            }
+            let mapped = Instant::now();
+            context.run_model()?;
+            let ran = Instant::now();
+            for tensor in &outputs {
+                buffer.clear();
+                buffer.extend_from_slice(tensor.mapro()?.as_bytes());
+                black_box(&buffer);
+            }
+            let end = Instant::now();
+
+            if iteration < self.warmup {
+                continue;
+            }
+            input_map.push(mapped - start);
+            run.push(ran - mapped);
+            output_map.push(end - ran);
+            total.push(end - start);
+            for (time, tensor) in layer_times.iter_mut().zip(&layer_tensors) {
+                *time += tensor.time();
+            }
+        }
+
+        let elapsed: Duration = total.iter().sum();
+        let layers = layers
+            .iter()
+            .zip(layer_times)
+            .map(|(index, time)| {
+                let index = *index as usize;
+                LayerProfile {
+                    index,
+                    name: model.layer_name(index).unwrap_or_default().to_string(),
+                    layer_type: model.layer_type(index).unwrap_or_default().to_string(),
+                    time: time as f64 / self.iterations as f64 / 1e6,
+                }
+            })
+            .collect();
+        Ok(BenchmarkReport {
+            warmup: self.warmup,
+            iterations: self.iterations,
+            input_map: Latency::from_samples(&input_map),
+            run: Latency::from_samples(&run),
+            output_map: Latency::from_samples(&output_map),
+            total: Latency::from_samples(&total),
+            throughput: self.iterations as f64 / elapsed.as_secs_f64().max(f64::MIN_POSITIVE),
+            layers,
+        })
+    }
+}
@@ -0,0 +1,350 @@
+use crate::{
+    context::Context,
+    error::Error,
+    model::Model,
+    npy::Array,
+    tensor::{ComparisonReport, QuantParams, Tolerance},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+const MANIFEST: &str = "manifest.json";
+
+/// A tensor captured during a recorded run, stored as a `.npy` file relative
+/// to the recording directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TensorRecord {
+    pub layer: usize,
+    pub name: String,
+    pub file: String,
+    pub dtype: String,
+    pub shape: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scales: Vec<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub zeros: Vec<i32>,
+    #[serde(default)]
+    pub axis: i32,
+    /// Time in nanoseconds taken by the layer which produced the tensor.
+    pub time_ns: i64,
+}
+
+impl TensorRecord {
+    pub fn quant_params(&self) -> QuantParams {
+        QuantParams {
+            scales: self.scales.clone(),
+            zeros: self.zeros.clone(),
+            axis: self.axis,
+        }
+    }
+}
+
+/// The tensors and timing of one recorded run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Wall time in nanoseconds of the whole run.
+    pub duration_ns: u64,
+    pub inputs: Vec<TensorRecord>,
+    pub outputs: Vec<TensorRecord>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub intermediates: Vec<TensorRecord>,
+}
+
+/// Description of a recording, written as `manifest.json` in the recording
+/// directory.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub runtime_version: String,
+    pub model_name: Option<String>,
+    pub model_uuid: Option<String>,
+    pub engine_name: Option<String>,
+    pub engine_version: Option<String>,
+    pub runs: Vec<RunRecord>,
+}
+
+impl Manifest {
+    pub fn load<P: AsRef<Path>>(dir: P) -> Result<Self, Error> {
+        let reader = BufReader::new(File::open(dir.as_ref().join(MANIFEST))?);
+        serde_json::from_reader(reader).map_err(|e| Error::WrapperError(e.to_string()))
+    }
+
+    fn save(&self, dir: &Path) -> Result<(), Error> {
+        let writer = BufWriter::new(File::create(dir.join(MANIFEST))?);
+        serde_json::to_writer_pretty(writer, self).map_err(|e| Error::WrapperError(e.to_string()))
+    }
+}
+
+/// Records the inputs, outputs and optionally every intermediate layer tensor
+/// of the runs of a context into a directory.
+///
+/// Each run is stored in a `run-NNNN` subdirectory with one `.npy` file per
+/// layer, named by layer index.  The manifest is rewritten after every run so
+/// a recording survives the process being killed.
+///
+/// The memory pool reuses intermediate buffers for later layers, so runs
+/// recording intermediates step the model one layer at a time and capture
+/// each layer right after it ran.
+pub struct Recorder {
+    dir: PathBuf,
+    intermediates: bool,
+    manifest: Manifest,
+}
+
+impl Recorder {
+    /// Creates a recorder writing into `dir`, which is created if needed.
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<Self, Error> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Recorder {
+            dir: dir.as_ref().to_path_buf(),
+            intermediates: false,
+            manifest: Manifest::default(),
+        })
+    }
+
+    /// Also record the tensor of every layer which is neither an input nor an
+    /// output.
+    pub fn with_intermediates(mut self, intermediates: bool) -> Self {
+        self.intermediates = intermediates;
+        self
+    }
+
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Runs the model of `context` and records the run.
+    pub fn run(&mut self, context: &Context) -> Result<(), Error> {
+        let model = context
+            .model()
+            .ok_or_else(|| Error::WrapperError(String::from("no model loaded")))?;
+        if self.manifest.runs.is_empty() {
+            self.manifest.runtime_version = crate::version()?.to_string();
+            self.manifest.model_name = model.name().ok().map(String::from);
+            self.manifest.model_uuid = model.uuid().ok().map(String::from);
+            let engine = context.engine();
+            self.manifest.engine_name = engine.and_then(|e| e.name()).map(String::from);
+            self.manifest.engine_version = engine.and_then(|e| e.version()).map(String::from);
+        }
+        let run_dir = format!("run-{:04}", self.manifest.runs.len());
+        fs::create_dir_all(self.dir.join(&run_dir))?;
+
+        let input_layers: Vec<usize> = model.inputs()?.iter().map(|i| *i as usize).collect();
+        let output_layers: Vec<usize> = model.outputs()?.iter().map(|i| *i as usize).collect();
+        let inputs = input_layers
+            .iter()
+            .map(|index| self.capture(context, model, &run_dir, *index))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut intermediates = Vec::new();
+        let duration = if self.intermediates {
+            let mut duration = Duration::ZERO;
+            for index in 0..model.layer_count() {
+                let start = Instant::now();
+                context.step(index)?;
+                duration += start.elapsed();
+                let recorded = input_layers.contains(&index) || output_layers.contains(&index);
+                if recorded || context.tensor_index(index).is_err() {
+                    continue;
+                }
+                intermediates.push(self.capture(context, model, &run_dir, index)?);
+            }
+            duration
+        } else {
+            let start = Instant::now();
+            context.run_model()?;
+            start.elapsed()
+        };
+
+        let outputs = output_layers
+            .iter()
+            .map(|index| self.capture(context, model, &run_dir, *index))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.manifest.runs.push(RunRecord {
+            duration_ns: duration.as_nanos() as u64,
+            inputs,
+            outputs,
+            intermediates,
+        });
+        self.manifest.save(&self.dir)
+    }
+
+    fn capture(
+        &self,
+        context: &Context,
+        model: &Model,
+        run_dir: &str,
+        index: usize,
+    ) -> Result<TensorRecord, Error> {
+        let tensor = context.tensor_index(index)?;
+        let name = model.layer_name(index).unwrap_or_default().to_string();
+        let file = format!("{}/{}.npy", run_dir, index);
+        let array = Array::from_tensor(tensor)?;
+        let mut writer = BufWriter::new(File::create(self.dir.join(&file))?);
+        array.write(&mut writer)?;
+        let quant = tensor.quant_params();
+        Ok(TensorRecord {
+            layer: index,
+            name,
+            file,
+            dtype: format!("{:?}", array.dtype),
+            shape: array.shape,
+            scales: quant.scales,
+            zeros: quant.zeros,
+            axis: quant.axis,
+            time_ns: tensor.time(),
+        })
+    }
+}
+
+/// Difference of one layer between a replay and the recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerDiff {
+    pub layer: usize,
+    pub name: String,
+    pub report: ComparisonReport,
+}
+
+/// Result of replaying one recorded run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunDiff {
+    pub run: usize,
+    pub duration_ns: u64,
+    pub recorded_duration_ns: u64,
+    pub layers: Vec<LayerDiff>,
+}
+
+impl RunDiff {
+    pub fn is_match(&self) -> bool {
+        self.layers.iter().all(|l| l.report.is_match())
+    }
+}
+
+/// Re-runs the inputs of a recording and compares the results against the
+/// recorded outputs and intermediates.
+pub struct Replayer {
+    dir: PathBuf,
+    manifest: Manifest,
+}
+
+impl Replayer {
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self, Error> {
+        Ok(Replayer {
+            dir: dir.as_ref().to_path_buf(),
+            manifest: Manifest::load(dir.as_ref())?,
+        })
+    }
+
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    fn array(&self, record: &TensorRecord) -> Result<Array, Error> {
+        let mut reader = BufReader::new(File::open(self.dir.join(&record.file))?);
+        Array::read(&mut reader)
+    }
+
+    /// Replays every recorded run on `context`, which must have the recorded
+    /// model loaded.
+    pub fn replay(
+        &self,
+        context: &mut Context,
+        tolerance: Tolerance,
+    ) -> Result<Vec<RunDiff>, Error> {
+        let uuid = context
+            .model()
+            .and_then(|m| m.uuid().ok())
+            .map(String::from);
+        if let (Some(recorded), Some(uuid)) = (&self.manifest.model_uuid, &uuid) {
+            if recorded != uuid {
+                return Err(Error::WrapperError(format!(
+                    "recording was made with model {} but model {} is loaded",
+                    recorded, uuid
+                )));
+            }
+        }
+        (0..self.manifest.runs.len())
+            .map(|run| self.replay_run(context, run, tolerance))
+            .collect()
+    }
+
+    /// Replays the recorded run `run` on `context`.  Runs with intermediates
+    /// are stepped layer by layer, as they were recorded.
+    pub fn replay_run(
+        &self,
+        context: &mut Context,
+        run: usize,
+        tolerance: Tolerance,
+    ) -> Result<RunDiff, Error> {
+        let record = self
+            .manifest
+            .runs
+            .get(run)
+            .ok_or_else(|| Error::WrapperError(format!("no recorded run {}", run)))?;
+        for input in &record.inputs {
+            self.array(input)?
+                .copy_into(context.tensor_index_mut(input.layer)?)?;
+        }
+
+        let mut intermediates = Vec::new();
+        let duration = if record.intermediates.is_empty() {
+            let start = Instant::now();
+            context.run_model()?;
+            start.elapsed()
+        } else {
+            let layer_count = context
+                .model()
+                .ok_or_else(|| Error::WrapperError(String::from("no model loaded")))?
+                .layer_count();
+            let mut duration = Duration::ZERO;
+            for index in 0..layer_count {
+                let start = Instant::now();
+                context.step(index)?;
+                duration += start.elapsed();
+                for recorded in record.intermediates.iter().filter(|r| r.layer == index) {
+                    intermediates.push(self.diff(context, recorded, tolerance)?);
+                }
+            }
+            duration
+        };
+
+        let mut layers = record
+            .outputs
+            .iter()
+            .map(|recorded| self.diff(context, recorded, tolerance))
+            .collect::<Result<Vec<_>, _>>()?;
+        layers.append(&mut intermediates);
+        Ok(RunDiff {
+            run,
+            duration_ns: duration.as_nanos() as u64,
+            recorded_duration_ns: record.duration_ns,
+            layers,
+        })
+    }
+
+    fn diff(
+        &self,
+        context: &Context,
+        recorded: &TensorRecord,
+        tolerance: Tolerance,
+    ) -> Result<LayerDiff, Error> {
+        let mut reference = self.array(recorded)?.to_tensor()?;
+        let quant = recorded.quant_params();
+        if quant.is_quantized() {
+            reference.set_quant_params(&quant)?;
+        }
+        let report = context
+            .tensor_index(recorded.layer)?
+            .compare(&reference, tolerance)?;
+        Ok(LayerDiff {
+            layer: recorded.layer,
+            name: recorded.name.clone(),
+            report,
+        })
+    }
+}
@@ -0,0 +1,214 @@
+use crate::error::Error;
+use deepviewrt_sys as ffi;
+use std::{fmt, str::FromStr, sync::OnceLock};
+
+/// A `MAJOR.MINOR.PATCH` version of the DeepViewRT runtime library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RuntimeVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl RuntimeVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        RuntimeVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl FromStr for RuntimeVersion {
+    type Err = Error;
+
+    /// Parses `MAJOR.MINOR.PATCH`, a missing patch is 0 and any pre-release
+    /// or build suffix is ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::WrapperError(format!("invalid runtime version {}", s));
+        let core = s.trim().split(['-', '+', ' ']).next().unwrap_or("");
+        let mut parts = core.split('.').map(|p| p.parse::<u32>());
+        let major = parts.next().ok_or_else(invalid)?.map_err(|_| invalid())?;
+        let minor = parts.next().ok_or_else(invalid)?.map_err(|_| invalid())?;
+        let patch = parts.next().unwrap_or(Ok(0)).map_err(|_| invalid())?;
+        Ok(RuntimeVersion::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for RuntimeVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+pub(crate) const INIT: RuntimeVersion = RuntimeVersion::new(2, 4, 0);
+pub(crate) const CONTEXT_MEMORY: RuntimeVersion = RuntimeVersion::new(2, 2, 0);
+pub(crate) const CONTEXT_STEP: RuntimeVersion = RuntimeVersion::new(2, 3, 0);
+pub(crate) const IO_TIME: RuntimeVersion = RuntimeVersion::new(2, 1, 0);
+pub(crate) const AUX_OBJECT: RuntimeVersion = RuntimeVersion::new(2, 1, 0);
+pub(crate) const MODEL_IO: RuntimeVersion = RuntimeVersion::new(2, 4, 0);
+pub(crate) const QUANTIZATION: RuntimeVersion = RuntimeVersion::new(2, 4, 0);
+pub(crate) const LAYER_PARAMETERS: RuntimeVersion = RuntimeVersion::new(2, 4, 0);
+pub(crate) const RESOURCES: RuntimeVersion = RuntimeVersion::new(2, 4, 0);
+pub(crate) const ENGINE_HANDLE: RuntimeVersion = RuntimeVersion::new(2, 0, 0);
+
+/// APIs available in the loaded runtime, by the version which introduced
+/// them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    pub version: RuntimeVersion,
+    /// `nn_init`.
+    pub init: bool,
+    /// Context cache and memory pool tensors.
+    pub context_memory: bool,
+    /// Running a model layer by layer.
+    pub context_step: bool,
+    /// Tensor map and unmap timing.
+    pub io_time: bool,
+    /// Model input and output lists and layer type ids.
+    pub model_io: bool,
+    /// Tensor and layer scales, zero-points and axis.
+    pub quantization: bool,
+    /// Layer parameters such as weights and anchors.
+    pub layer_parameters: bool,
+    /// Resources embedded in models.
+    pub resources: bool,
+    /// Engine plugin native handles.
+    pub engine_handle: bool,
+    /// Custom user ops, always false as no user ops API is exposed by the
+    /// runtime bindings.
+    pub user_ops: bool,
+}
+
+impl Capabilities {
+    pub fn for_version(version: RuntimeVersion) -> Self {
+        Capabilities {
+            version,
+            init: version >= INIT,
+            context_memory: version >= CONTEXT_MEMORY,
+            context_step: version >= CONTEXT_STEP,
+            io_time: version >= IO_TIME,
+            model_io: version >= MODEL_IO,
+            quantization: version >= QUANTIZATION,
+            layer_parameters: version >= LAYER_PARAMETERS,
+            resources: version >= RESOURCES,
+            engine_handle: version >= ENGINE_HANDLE,
+            user_ops: false,
+        }
+    }
+
+    /// Capabilities of the loaded runtime.  A runtime whose version cannot
+    /// be parsed is assumed to support every API.
+    pub fn get() -> Self {
+        Capabilities::for_version(runtime_version().unwrap_or(RuntimeVersion::new(u32::MAX, 0, 0)))
+    }
+}
+
+/// Version of the loaded runtime, parsed from [`crate::version`].
+pub fn runtime_version() -> Result<RuntimeVersion, Error> {
+    static VERSION: OnceLock<Result<RuntimeVersion, Error>> = OnceLock::new();
+    VERSION.get_or_init(|| crate::version()?.parse()).clone()
+}
+
+/// Loads the runtime library at `path` instead of the default one.  Must be
+/// called before any other function of this crate.
+#[cfg(feature = "dynamic")]
+pub fn load_library<P: AsRef<std::ffi::OsStr>>(path: P) -> Result<(), Error> {
+    Ok(ffi::dynamic::load(path)?)
+}
+
+/// Returns an error when the runtime predates `since`, the version which
+/// introduced `api`, or when a dynamically loaded runtime lacks `api`.
+pub(crate) fn require(since: RuntimeVersion, api: &str) -> Result<(), Error> {
+    #[cfg(feature = "dynamic")]
+    if api.starts_with("nn_") {
+        ffi::dynamic::require(api)?;
+    }
+    match runtime_version() {
+        Ok(version) if version < since => Err(Error::WrapperError(format!(
+            "{} requires DeepViewRT {} but the runtime is {}",
+            api, since, version
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Options passed to `nn_init` by [`init_with`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct InitOptions {
+    options: Vec<(ffi::NNOptions, ffi::NNOptions)>,
+    minimum_version: Option<RuntimeVersion>,
+}
+
+impl InitOptions {
+    pub fn new() -> Self {
+        InitOptions::default()
+    }
+
+    /// Adds a raw `key`, `value` option pair for `nn_init`.
+    pub fn option(mut self, key: ffi::NNOptions, value: ffi::NNOptions) -> Self {
+        self.options.push((key, value));
+        self
+    }
+
+    /// Fails initialization when the runtime is older than `version`.
+    pub fn minimum_version(mut self, version: RuntimeVersion) -> Self {
+        self.minimum_version = Some(version);
+        self
+    }
+
+    /// The zero terminated key and value list expected by `nn_init`.
+    fn to_ffi(&self) -> Vec<ffi::NNOptions> {
+        let mut list: Vec<ffi::NNOptions> =
+            self.options.iter().flat_map(|(k, v)| [*k, *v]).collect();
+        list.push(0);
+        list
+    }
+}
+
+static INITIALIZED: OnceLock<Result<(), Error>> = OnceLock::new();
+
+/// Initializes the runtime once with `options`.  Later calls, and the
+/// implicit initialization done by constructors, return the result of the
+/// first call and ignore their options.
+pub fn init_with(options: &InitOptions) -> Result<(), Error> {
+    INITIALIZED
+        .get_or_init(|| {
+            ensure_loaded()?;
+            if let Some(minimum) = options.minimum_version {
+                require(minimum, "this application")?;
+            }
+            #[cfg(feature = "dynamic")]
+            match ffi::dynamic::require("nn_init") {
+                Err(ffi::dynamic::LoadError::MissingSymbol(_)) => return Ok(()),
+                result => result?,
+            }
+            if !Capabilities::get().init {
+                return Ok(());
+            }
+            let list = options.to_ffi();
+            let ret = unsafe { ffi::nn_init(list.as_ptr()) };
+            if ret != ffi::NNError::NN_SUCCESS {
+                return Err(Error::from(ret));
+            }
+            Ok(())
+        })
+        .clone()
+}
+
+/// Returns an error when the dynamically loaded runtime failed to load.
+/// Every constructor and free function calls this, directly or through
+/// [`ensure_init`], before its first `nn_*` call.  Once loaded the library
+/// exports every function of its version and newer functions are checked by
+/// [`require`], so no call can reach a missing symbol.
+pub(crate) fn ensure_loaded() -> Result<(), Error> {
+    #[cfg(feature = "dynamic")]
+    ffi::dynamic::ensure_loaded()?;
+    Ok(())
+}
+
+/// Initializes the runtime with default options if it was not yet.
+pub(crate) fn ensure_init() -> Result<(), Error> {
+    init_with(&InitOptions::default())
+}
@@ -0,0 +1,329 @@
+use crate::{
+    context::Context,
+    error::Error,
+    model::{Model, Parameter},
+    npy::Array,
+    tensor::{QuantParams, Tensor, TensorType},
+};
+use ::safetensors::tensor::{Dtype, SafeTensors, TensorView, View};
+use memmap2::Mmap;
+use std::{borrow::Cow, collections::HashMap, fs::File, path::Path};
+
+fn to_dtype(tensor_type: TensorType) -> Result<Dtype, Error> {
+    let dtype = match tensor_type {
+        TensorType::RAW | TensorType::U8 => Dtype::U8,
+        TensorType::I8 => Dtype::I8,
+        TensorType::I16 => Dtype::I16,
+        TensorType::U16 => Dtype::U16,
+        TensorType::I32 => Dtype::I32,
+        TensorType::U32 => Dtype::U32,
+        TensorType::I64 => Dtype::I64,
+        TensorType::U64 => Dtype::U64,
+        TensorType::F16 => Dtype::F16,
+        TensorType::F32 => Dtype::F32,
+        TensorType::F64 => Dtype::F64,
+        TensorType::STR => {
+            return Err(Error::WrapperError(String::from(
+                "string tensors cannot be stored as safetensors",
+            )))
+        }
+    };
+    Ok(dtype)
+}
+
+fn to_tensor_type(dtype: Dtype) -> Result<TensorType, Error> {
+    let tensor_type = match dtype {
+        Dtype::BOOL | Dtype::U8 => TensorType::U8,
+        Dtype::I8 => TensorType::I8,
+        Dtype::I16 => TensorType::I16,
+        Dtype::U16 => TensorType::U16,
+        Dtype::I32 => TensorType::I32,
+        Dtype::U32 => TensorType::U32,
+        Dtype::I64 => TensorType::I64,
+        Dtype::U64 => TensorType::U64,
+        Dtype::F16 => TensorType::F16,
+        Dtype::F32 => TensorType::F32,
+        Dtype::F64 => TensorType::F64,
+        dtype => {
+            return Err(Error::WrapperError(format!(
+                "unsupported safetensors dtype {:?}",
+                dtype
+            )))
+        }
+    };
+    Ok(tensor_type)
+}
+
+fn safetensors_error(e: ::safetensors::SafeTensorError) -> Error {
+    Error::WrapperError(e.to_string())
+}
+
+/// Swaps between the little-endian safetensors layout and native order.
+fn swap_endian(data: &mut [u8], item_size: usize) {
+    if cfg!(target_endian = "big") && item_size > 1 {
+        for item in data.chunks_exact_mut(item_size) {
+            item.reverse();
+        }
+    }
+}
+
+/// An array prepared for serialization with its safetensors dtype.
+struct Entry {
+    dtype: Dtype,
+    array: Array,
+}
+
+impl Entry {
+    fn new(mut array: Array) -> Result<Self, Error> {
+        let dtype = to_dtype(array.dtype)?;
+        swap_endian(&mut array.data, dtype.size());
+        Ok(Entry { dtype, array })
+    }
+}
+
+impl View for &Entry {
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn shape(&self) -> &[usize] {
+        &self.array.shape
+    }
+
+    fn data(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self.array.data)
+    }
+
+    fn data_len(&self) -> usize {
+        self.array.data.len()
+    }
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn split<T: std::str::FromStr>(values: Option<&String>) -> Vec<T> {
+    values
+        .map(|v| v.split(',').filter_map(|v| v.trim().parse().ok()).collect())
+        .unwrap_or_default()
+}
+
+fn write(
+    entries: Vec<(String, Entry)>,
+    metadata: HashMap<String, String>,
+    path: &Path,
+) -> Result<(), Error> {
+    let metadata = if metadata.is_empty() {
+        None
+    } else {
+        Some(metadata)
+    };
+    ::safetensors::serialize_to_file(
+        entries.iter().map(|(name, entry)| (name.as_str(), entry)),
+        &metadata,
+        path,
+    )
+    .map_err(safetensors_error)
+}
+
+/// Saves the named tensors into a safetensors file.  Quantization parameters
+/// are stored in the file metadata as `<name>.scales`, `<name>.zeros` and
+/// `<name>.axis`.
+pub fn save_tensors<P: AsRef<Path>>(tensors: &[(&str, &Tensor)], path: P) -> Result<(), Error> {
+    let mut entries = Vec::with_capacity(tensors.len());
+    let mut metadata = HashMap::new();
+    for (name, tensor) in tensors {
+        entries.push((name.to_string(), Entry::new(Array::from_tensor(tensor)?)?));
+        let quant = tensor.quant_params();
+        if quant.is_quantized() {
+            metadata.insert(format!("{}.scales", name), join(&quant.scales));
+            metadata.insert(format!("{}.zeros", name), join(&quant.zeros));
+            metadata.insert(format!("{}.axis", name), quant.axis.to_string());
+        }
+    }
+    write(entries, metadata, path.as_ref())
+}
+
+/// Copies a layer parameter into an array, using the first data type the
+/// parameter provides.
+fn parameter_array(parameter: &Parameter) -> Result<Array, Error> {
+    fn bytes<T: Copy, const N: usize>(data: &[T], to_bytes: impl Fn(T) -> [u8; N]) -> Vec<u8> {
+        data.iter().flat_map(|v| to_bytes(*v)).collect()
+    }
+    let (dtype, data) = if let Ok(data) = parameter.data_f32() {
+        (TensorType::F32, bytes(data, f32::to_ne_bytes))
+    } else if let Ok(data) = parameter.data_i32() {
+        (TensorType::I32, bytes(data, i32::to_ne_bytes))
+    } else if let Ok(data) = parameter.data_i16() {
+        (TensorType::I16, bytes(data, i16::to_ne_bytes))
+    } else if let Ok(data) = parameter.data_i8() {
+        (TensorType::I8, bytes(data, i8::to_ne_bytes))
+    } else {
+        (TensorType::U8, parameter.data_raw()?.to_vec())
+    };
+    let item_size = to_dtype(dtype)?.size();
+    let shape: Vec<usize> = parameter
+        .shape()
+        .map(|s| s.iter().map(|d| (*d).max(0) as usize).collect())
+        .unwrap_or_default();
+    let shape = if shape.iter().product::<usize>() * item_size == data.len() {
+        shape
+    } else {
+        vec![data.len() / item_size]
+    };
+    Ok(Array { dtype, shape, data })
+}
+
+/// Saves the parameters `keys` of every layer of `model` which has them into
+/// a safetensors file, named `<layer name>.<key>`.  Returns the number of
+/// parameters saved.
+pub fn save_model_parameters<P: AsRef<Path>>(
+    model: &Model,
+    keys: &[&str],
+    path: P,
+) -> Result<usize, Error> {
+    let mut entries = Vec::new();
+    for index in 0..model.layer_count() {
+        let layer = model.layer_name(index)?;
+        for key in keys {
+            if let Ok(parameter) = model.layer_parameter(index, key) {
+                let array = parameter_array(&parameter)?;
+                entries.push((format!("{}.{}", layer, key), Entry::new(array)?));
+            }
+        }
+    }
+    let count = entries.len();
+    write(entries, HashMap::new(), path.as_ref())?;
+    Ok(count)
+}
+
+/// A safetensors file mapped into memory.  Tensor data is copied once, from
+/// the mapping straight into the destination tensor.  Tensors cannot alias
+/// the mapping itself since their buffers are owned by the runtime.
+pub struct SafetensorsFile {
+    data: Mmap,
+}
+
+impl SafetensorsFile {
+    /// Maps the file at `path`, which must not be modified while it is open.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        // The mapping is read only and the file is documented to be left
+        // untouched while the SafetensorsFile exists.
+        let data = unsafe { Mmap::map(&file)? };
+        SafeTensors::read_metadata(&data).map_err(safetensors_error)?;
+        Ok(SafetensorsFile { data })
+    }
+
+    fn tensors(&self) -> SafeTensors<'_> {
+        // The header was validated when the file was opened.
+        SafeTensors::deserialize(&self.data).unwrap()
+    }
+
+    fn metadata(&self) -> HashMap<String, String> {
+        SafeTensors::read_metadata(&self.data)
+            .ok()
+            .and_then(|(_, metadata)| metadata.metadata().clone())
+            .unwrap_or_default()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.tensors().names().into_iter().cloned().collect()
+    }
+
+    /// Quantization parameters stored in the metadata for the tensor `name`,
+    /// empty when there are none.
+    pub fn quant_params(&self, name: &str) -> QuantParams {
+        let metadata = self.metadata();
+        let get = |suffix: &str| metadata.get(&format!("{}.{}", name, suffix));
+        QuantParams {
+            scales: split(get("scales")),
+            zeros: split(get("zeros")),
+            axis: get("axis").and_then(|a| a.parse().ok()).unwrap_or(-1),
+        }
+    }
+
+    fn array(view: &TensorView) -> Result<Array, Error> {
+        let dtype = to_tensor_type(view.dtype())?;
+        let mut data = view.data().to_vec();
+        swap_endian(&mut data, view.dtype().size());
+        Ok(Array {
+            dtype,
+            shape: view.shape().to_vec(),
+            data,
+        })
+    }
+
+    /// Allocates a new tensor from the tensor `name` with its quantization
+    /// parameters.
+    pub fn tensor(&self, name: &str) -> Result<Tensor, Error> {
+        let tensors = self.tensors();
+        let view = tensors.tensor(name).map_err(safetensors_error)?;
+        let shape: Vec<i32> = view.shape().iter().map(|d| *d as i32).collect();
+        let mut tensor = Tensor::with_shape(to_tensor_type(view.dtype())?, &shape)?;
+        Self::copy_view(name, &view, &mut tensor)?;
+        let quant = self.quant_params(name);
+        if quant.is_quantized() {
+            tensor.set_quant_params(&quant)?;
+        }
+        Ok(tensor)
+    }
+
+    /// Writes the tensor `name` into `tensor`.  When the types match the data
+    /// is copied straight from the mapped file into the mapped tensor,
+    /// otherwise it is converted as by [`Array::copy_into`].
+    pub fn copy_into(&self, name: &str, tensor: &mut Tensor) -> Result<(), Error> {
+        let tensors = self.tensors();
+        let view = tensors.tensor(name).map_err(safetensors_error)?;
+        Self::copy_view(name, &view, tensor)
+    }
+
+    fn copy_view(name: &str, view: &TensorView, tensor: &mut Tensor) -> Result<(), Error> {
+        let dtype = to_tensor_type(view.dtype())?;
+        if dtype != tensor.tensor_type() || cfg!(target_endian = "big") {
+            return Self::array(view)?.copy_into(tensor);
+        }
+        let volume: usize = view.shape().iter().product();
+        let mut map = tensor.mapwo()?;
+        let bytes = map.as_bytes_mut();
+        if volume * view.dtype().size() != bytes.len() {
+            return Err(Error::WrapperError(format!(
+                "{} of shape {:?} does not fit a tensor of {} bytes",
+                name,
+                view.shape(),
+                bytes.len()
+            )));
+        }
+        bytes.copy_from_slice(view.data());
+        Ok(())
+    }
+}
+
+impl Context {
+    /// Fills the context tensors of the model layers named in the safetensors
+    /// file at `path`.  Returns the names of the layers filled, tensors which
+    /// do not name a layer of the model are skipped.
+    pub fn load_safetensors<P: AsRef<Path>>(&mut self, path: P) -> Result<Vec<String>, Error> {
+        let file = SafetensorsFile::open(path)?;
+        let model = self
+            .model()
+            .ok_or_else(|| Error::WrapperError(String::from("no model loaded")))?;
+        let layers: Vec<(String, usize)> = file
+            .names()
+            .into_iter()
+            .filter_map(|name| {
+                let index = model.layer_lookup(&name).ok()?;
+                Some((name, index as usize))
+            })
+            .collect();
+        for (name, index) in &layers {
+            file.copy_into(name, self.tensor_index_mut(*index)?)?;
+        }
+        Ok(layers.into_iter().map(|(name, _)| name).collect())
+    }
+}
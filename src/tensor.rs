@@ -1,13 +1,15 @@
-use crate::{engine::Engine, error::Error};
+use crate::{engine::Engine, error::Error, runtime};
 use deepviewrt_sys as ffi;
 use std::{
     cell::Cell,
     ffi::{c_void, CStr},
-    io,
-    ops::Deref,
+    fmt, io,
+    ops::{Deref, DerefMut},
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum TensorType {
     RAW = 0,
     STR = 1,
@@ -47,11 +49,43 @@ impl TryFrom<u32> for TensorType {
     }
 }
 
+impl TryFrom<ffi::NNTensorType> for TensorType {
+    type Error = ();
+
+    fn try_from(value: ffi::NNTensorType) -> Result<TensorType, Self::Error> {
+        TensorType::try_from(value.0)
+    }
+}
+
+impl From<TensorType> for ffi::NNTensorType {
+    fn from(value: TensorType) -> Self {
+        ffi::NNTensorType(value as u32)
+    }
+}
+
+/// A tensor, either owned or a view of a layer tensor of a [`Context`].
+///
+/// Tensors are neither `Send` nor `Sync`: a view aliases memory owned by its
+/// context, maps are not synchronized by the runtime and the cached engine is
+/// reference counted without atomics.
+///
+/// ```compile_fail
+/// fn send<T: Send>() {}
+/// send::<deepviewrt::tensor::Tensor>();
+/// ```
+///
+/// ```compile_fail
+/// fn sync<T: Sync>() {}
+/// sync::<deepviewrt::tensor::Tensor>();
+/// ```
+///
+/// [`Context`]: crate::context::Context
 pub struct Tensor {
     owned: bool,
     ptr: *mut ffi::NNTensor,
     engine: Cell<Option<Engine>>,
     scales: Option<Vec<f32>>,
+    zeros: Option<Vec<i32>>,
 }
 
 #[repr(u8)]
@@ -71,6 +105,28 @@ pub enum MappedData<'a> {
     F64(&'a [f64]) = 12,
 }
 
+impl<'a> MappedData<'a> {
+    /// The mapped data as native-endian bytes.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        fn bytes<T>(data: &[T]) -> &[u8] {
+            unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, size_of_val(data)) }
+        }
+        match *self {
+            MappedData::RAW(d) | MappedData::U8(d) | MappedData::F16(d) => d,
+            MappedData::STR(d) => d.as_bytes(),
+            MappedData::I8(d) => bytes(d),
+            MappedData::I16(d) => bytes(d),
+            MappedData::U16(d) => bytes(d),
+            MappedData::I32(d) => bytes(d),
+            MappedData::U32(d) => bytes(d),
+            MappedData::I64(d) => bytes(d),
+            MappedData::U64(d) => bytes(d),
+            MappedData::F32(d) => bytes(d),
+            MappedData::F64(d) => bytes(d),
+        }
+    }
+}
+
 pub struct TensorData<'a> {
     tensor: &'a Tensor,
     data: MappedData<'a>,
@@ -90,8 +146,278 @@ impl<'a> Drop for TensorData<'a> {
     }
 }
 
-unsafe impl Send for Tensor {}
-unsafe impl Sync for Tensor {}
+#[repr(u8)]
+pub enum MappedDataMut<'a> {
+    RAW(&'a mut [u8]) = 0,
+    I8(&'a mut [i8]) = 2,
+    U8(&'a mut [u8]) = 3,
+    I16(&'a mut [i16]) = 4,
+    U16(&'a mut [u16]) = 5,
+    I32(&'a mut [i32]) = 6,
+    U32(&'a mut [u32]) = 7,
+    I64(&'a mut [i64]) = 8,
+    U64(&'a mut [u64]) = 9,
+    F16(&'a mut [u8]) = 10,
+    F32(&'a mut [f32]) = 11,
+    F64(&'a mut [f64]) = 12,
+}
+
+impl<'a> MappedDataMut<'a> {
+    /// The mapped data as mutable native-endian bytes.
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        fn bytes<T>(data: &mut [T]) -> &mut [u8] {
+            unsafe {
+                std::slice::from_raw_parts_mut(data.as_mut_ptr() as *mut u8, size_of_val(data))
+            }
+        }
+        match self {
+            MappedDataMut::RAW(d) | MappedDataMut::U8(d) | MappedDataMut::F16(d) => d,
+            MappedDataMut::I8(d) => bytes(d),
+            MappedDataMut::I16(d) => bytes(d),
+            MappedDataMut::U16(d) => bytes(d),
+            MappedDataMut::I32(d) => bytes(d),
+            MappedDataMut::U32(d) => bytes(d),
+            MappedDataMut::I64(d) => bytes(d),
+            MappedDataMut::U64(d) => bytes(d),
+            MappedDataMut::F32(d) => bytes(d),
+            MappedDataMut::F64(d) => bytes(d),
+        }
+    }
+}
+
+pub struct TensorDataMut<'a> {
+    tensor: &'a Tensor,
+    data: MappedDataMut<'a>,
+}
+
+impl<'a> Deref for TensorDataMut<'a> {
+    type Target = MappedDataMut<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<'a> DerefMut for TensorDataMut<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+impl<'a> Drop for TensorDataMut<'a> {
+    fn drop(&mut self) {
+        unsafe { self.tensor.unmap() };
+    }
+}
+
+/// Affine quantization parameters of a tensor or model layer.  A real value is
+/// recovered from a quantized value `q` as `(q - zero) * scale`.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuantParams {
+    pub scales: Vec<f32>,
+    pub zeros: Vec<i32>,
+    pub axis: i32,
+}
+
+impl QuantParams {
+    pub fn is_quantized(&self) -> bool {
+        !self.scales.is_empty()
+    }
+
+    pub fn is_per_channel(&self) -> bool {
+        self.scales.len() > 1 || self.zeros.len() > 1
+    }
+
+    /// Scale of the given channel, per-tensor parameters ignore the channel.
+    pub fn scale(&self, channel: usize) -> f32 {
+        match self.scales.len() {
+            0 => 1.0,
+            1 => self.scales[0],
+            n => self.scales[channel.min(n - 1)],
+        }
+    }
+
+    /// Zero-point of the given channel, per-tensor parameters ignore the
+    /// channel.
+    pub fn zero(&self, channel: usize) -> i32 {
+        match self.zeros.len() {
+            0 => 0,
+            1 => self.zeros[0],
+            n => self.zeros[channel.min(n - 1)],
+        }
+    }
+
+    pub fn quantize(&self, value: f32, channel: usize) -> f32 {
+        if !self.is_quantized() {
+            return value;
+        }
+        (value / self.scale(channel)).round() + self.zero(channel) as f32
+    }
+
+    pub fn dequantize(&self, value: f32, channel: usize) -> f32 {
+        if !self.is_quantized() {
+            return value;
+        }
+        (value - self.zero(channel) as f32) * self.scale(channel)
+    }
+}
+
+/// Element type, shape and quantization of a tensor, see [`Tensor::info`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TensorInfo {
+    pub datatype: TensorType,
+    pub shape: Vec<i32>,
+    pub quant: QuantParams,
+}
+
+/// Allowed difference between two values, they match when
+/// `|a - b| <= abs + rel * |b|` with `b` taken from the reference tensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tolerance {
+    pub abs: f32,
+    pub rel: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance {
+            abs: 1e-5,
+            rel: 1e-3,
+        }
+    }
+}
+
+/// Result of [`Tensor::compare`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComparisonReport {
+    /// Infinite when a NaN or infinity is compared against another value.
+    pub max_abs_error: f32,
+    pub mean_abs_error: f32,
+    pub mismatches: usize,
+    /// Multi-dimensional index of the first mismatching element.
+    pub first_mismatch: Option<Vec<usize>>,
+    /// Over the elements which are finite in both tensors.
+    pub cosine_similarity: f32,
+}
+
+impl ComparisonReport {
+    pub fn is_match(&self) -> bool {
+        self.mismatches == 0
+    }
+}
+
+/// Element types which can be stored in a mapped tensor.  Conversions from
+/// `f32` round to nearest and saturate to the range of the target type.
+pub trait Element: Copy {
+    fn from_f32(value: f32) -> Self;
+    fn to_f32(self) -> f32;
+}
+
+macro_rules! impl_element_int {
+    ($($t:ty),*) => {
+        $(impl Element for $t {
+            fn from_f32(value: f32) -> Self {
+                value.round() as $t
+            }
+
+            fn to_f32(self) -> f32 {
+                self as f32
+            }
+        })*
+    };
+}
+
+impl_element_int!(i8, u8, i16, u16, i32, u32, i64, u64);
+
+impl Element for f32 {
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+}
+
+impl Element for f64 {
+    fn from_f32(value: f32) -> Self {
+        value as f64
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+}
+
+/// Converts a flat row-major index into a multi-dimensional index.
+fn unravel(mut index: usize, shape: &[i32]) -> Vec<usize> {
+    let mut out = vec![0; shape.len()];
+    for (dim, size) in shape.iter().enumerate().rev() {
+        let size = (*size).max(1) as usize;
+        out[dim] = index % size;
+        index /= size;
+    }
+    out
+}
+
+/// Converts an IEEE 754 half-precision value, stored as its bit pattern, to
+/// `f32`.
+pub fn f16_to_f32(bits: u16) -> f32 {
+    let sign = ((bits as u32) & 0x8000) << 16;
+    let exp = ((bits >> 10) & 0x1f) as u32;
+    let mant = (bits & 0x3ff) as u32;
+    let value = match (exp, mant) {
+        (0, 0) => sign,
+        (0, _) => {
+            // Subnormal half, renormalize into an f32 normal.
+            let shift = mant.leading_zeros() - 21;
+            let mant = (mant << shift) & 0x3ff;
+            sign | ((113 - shift) << 23) | (mant << 13)
+        }
+        (0x1f, 0) => sign | 0x7f80_0000,
+        (0x1f, _) => sign | 0x7fc0_0000 | (mant << 13),
+        _ => sign | ((exp + 112) << 23) | (mant << 13),
+    };
+    f32::from_bits(value)
+}
+
+/// Converts an `f32` to the bit pattern of the nearest IEEE 754 half-precision
+/// value.
+pub fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let mant = bits & 0x7f_ffff;
+    if exp == 0xff {
+        let nan = if mant != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+    let exp = exp - 127 + 15;
+    if exp >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if exp <= 0 {
+        if exp < -10 {
+            return sign;
+        }
+        let mant = mant | 0x80_0000;
+        let shift = (14 - exp) as u32;
+        let half = mant >> shift;
+        let round = (mant >> (shift - 1)) & 1;
+        let sticky = mant & ((1 << (shift - 1)) - 1);
+        let half = half + (round & (sticky != 0 || half & 1 == 1) as u32);
+        return sign | half as u16;
+    }
+    let half = ((exp as u32) << 10) | (mant >> 13);
+    let round = (mant >> 12) & 1;
+    let sticky = mant & 0xfff;
+    let half = half + (round & (sticky != 0 || half & 1 == 1) as u32);
+    sign | half as u16
+}
 
 impl Deref for Tensor {
     type Target = ffi::NNTensor;
@@ -103,6 +429,7 @@ impl Deref for Tensor {
 
 impl Tensor {
     pub fn new() -> Result<Self, Error> {
+        runtime::ensure_init()?;
         let ptr = unsafe {
             ffi::nn_tensor_init(
                 std::ptr::null::<c_void>() as *mut c_void,
@@ -119,22 +446,56 @@ impl Tensor {
             engine: Cell::new(None),
             ptr,
             scales: None,
+            zeros: None,
         });
     }
 
     pub fn alloc(&self, ttype: TensorType, n_dims: i32, shape: &[i32; 3]) -> Result<(), Error> {
-        let ttype_c_uint = (ttype as u32) as std::os::raw::c_uint;
-        let ret = unsafe { ffi::nn_tensor_alloc(self.ptr, ttype_c_uint, n_dims, shape.as_ptr()) };
-        if ret != ffi::NNError_NN_SUCCESS {
+        let ret = unsafe { ffi::nn_tensor_alloc(self.ptr, ttype.into(), n_dims, shape.as_ptr()) };
+        if ret != ffi::NNError::NN_SUCCESS {
             return Err(Error::from(ret));
         }
 
         return Ok(());
     }
 
+    /// Creates a tensor of `tensor_type` allocated to `shape`, which has at
+    /// most four dimensions.
+    pub fn with_shape(tensor_type: TensorType, shape: &[i32]) -> Result<Self, Error> {
+        if shape.len() > 4 {
+            return Err(Error::WrapperError(format!(
+                "tensors have at most 4 dimensions but the shape is {:?}",
+                shape
+            )));
+        }
+        let tensor = Tensor::new()?;
+        let ret = unsafe {
+            ffi::nn_tensor_alloc(
+                tensor.ptr,
+                tensor_type.into(),
+                shape.len() as i32,
+                shape.as_ptr(),
+            )
+        };
+        if ret != ffi::NNError::NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+        Ok(tensor)
+    }
+
+    /// Sets every element of the tensor to `value`.
+    pub fn fill(&self, value: f64) -> Result<(), Error> {
+        let ret = unsafe { ffi::nn_tensor_fill(self.ptr, value) };
+        if ret != ffi::NNError::NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+        Ok(())
+    }
+
     pub fn dequantize(&self, dest: &mut Self) -> Result<(), Error> {
+        runtime::require(runtime::QUANTIZATION, "nn_tensor_dequantize")?;
         let ret = unsafe { ffi::nn_tensor_dequantize(dest.to_mut_ptr(), self.ptr) };
-        if ret != ffi::NNError_NN_SUCCESS {
+        if ret != ffi::NNError::NN_SUCCESS {
             return Err(Error::from(ret));
         }
 
@@ -142,9 +503,9 @@ impl Tensor {
     }
 
     pub fn set_tensor_type(&self, tensor_type: TensorType) -> Result<(), Error> {
-        let tensor_type_ = TensorType::try_from(tensor_type as u32).unwrap();
-        let ret = unsafe { ffi::nn_tensor_set_type(self.ptr, tensor_type_ as ffi::NNTensorType) };
-        if ret != ffi::NNError_NN_SUCCESS {
+        runtime::require(runtime::QUANTIZATION, "nn_tensor_set_type")?;
+        let ret = unsafe { ffi::nn_tensor_set_type(self.ptr, tensor_type.into()) };
+        if ret != ffi::NNError::NN_SUCCESS {
             return Err(Error::from(ret));
         }
         return Ok(());
@@ -171,6 +532,11 @@ impl Tensor {
         return ra;
     }
 
+    pub fn strides(&self) -> &[i32] {
+        let ret = unsafe { ffi::nn_tensor_strides(self.ptr) };
+        unsafe { std::slice::from_raw_parts(ret, 4) }
+    }
+
     pub fn dims(&self) -> i32 {
         return unsafe { ffi::nn_tensor_dims(self.ptr) };
     }
@@ -183,11 +549,25 @@ impl Tensor {
         return unsafe { ffi::nn_tensor_size(self.ptr) };
     }
 
-    pub fn axis(&self) -> i16 {
-        return unsafe { ffi::nn_tensor_axis(self.ptr) as i16 };
+    /// Time in nanoseconds taken by the layer producing this tensor during the
+    /// last run.
+    pub fn time(&self) -> i64 {
+        unsafe { ffi::nn_tensor_time(self.ptr) }
+    }
+
+    /// Time in nanoseconds spent in the last map and unmap of this tensor.
+    pub fn io_time(&self) -> Result<i64, Error> {
+        runtime::require(runtime::IO_TIME, "nn_tensor_io_time")?;
+        Ok(unsafe { ffi::nn_tensor_io_time(self.ptr) })
+    }
+
+    pub fn axis(&self) -> Result<i16, Error> {
+        runtime::require(runtime::QUANTIZATION, "nn_tensor_axis")?;
+        Ok(unsafe { ffi::nn_tensor_axis(self.ptr) as i16 })
     }
 
     pub fn zeros(&self) -> Result<&[i32], Error> {
+        runtime::require(runtime::QUANTIZATION, "nn_tensor_zeros")?;
         let mut zeros: usize = 0;
         let ret = unsafe { ffi::nn_tensor_zeros(self.ptr, &mut zeros as *mut usize) };
         if ret.is_null() {
@@ -196,9 +576,38 @@ impl Tensor {
         return unsafe { Ok(std::slice::from_raw_parts(ret, zeros)) };
     }
 
+    pub fn scales(&self) -> Result<&[f32], Error> {
+        runtime::require(runtime::QUANTIZATION, "nn_tensor_scales")?;
+        let mut scales: usize = 0;
+        let ret = unsafe { ffi::nn_tensor_scales(self.ptr, &mut scales as *mut usize) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from("scales returned null")));
+        }
+        unsafe { Ok(std::slice::from_raw_parts(ret, scales)) }
+    }
+
+    /// Quantization parameters of the tensor, empty when the tensor is not
+    /// quantized.
+    pub fn quant_params(&self) -> QuantParams {
+        QuantParams {
+            scales: self.scales().map(|s| s.to_vec()).unwrap_or_default(),
+            zeros: self.zeros().map(|z| z.to_vec()).unwrap_or_default(),
+            axis: self.axis().map(i32::from).unwrap_or(-1),
+        }
+    }
+
+    pub fn info(&self) -> TensorInfo {
+        let dims = self.dims().clamp(0, 4) as usize;
+        TensorInfo {
+            datatype: self.tensor_type(),
+            shape: self.shape()[..dims].to_vec(),
+            quant: self.quant_params(),
+        }
+    }
+
     pub fn set_scales(&mut self, scales: &[f32]) -> Result<(), Error> {
         self.scales = Some(scales.to_vec());
-        if scales.len() < (self.axis() as usize) || scales.len() != 1 {
+        if scales.len() < (self.axis()? as usize) || scales.len() != 1 {
             return Err(Error::WrapperError(String::from(
                 "scales should either have length of 1 or equal to channel_dimension (axis)",
             )));
@@ -209,11 +618,27 @@ impl Tensor {
         return Ok(());
     }
 
-    pub fn set_aux_object<T>(&self, aux_object: &mut T) {
+    /// Sets the scales, zero-points and channel axis of the tensor.  The
+    /// parameters are kept alive by the tensor.
+    pub fn set_quant_params(&mut self, quant: &QuantParams) -> Result<(), Error> {
+        runtime::require(runtime::QUANTIZATION, "nn_tensor_set_scales")?;
+        let scales = self.scales.insert(quant.scales.clone());
+        let zeros = self.zeros.insert(quant.zeros.clone());
+        unsafe {
+            ffi::nn_tensor_set_scales(self.ptr, scales.len(), scales.as_ptr(), 0);
+            ffi::nn_tensor_set_zeros(self.ptr, zeros.len(), zeros.as_ptr(), 0);
+            ffi::nn_tensor_set_axis(self.ptr, quant.axis);
+        }
+        Ok(())
+    }
+
+    pub fn set_aux_object<T>(&self, aux_object: &mut T) -> Result<(), Error> {
+        runtime::require(runtime::AUX_OBJECT, "nn_tensor_set_aux_object")?;
         let ptr = aux_object as *mut T;
         unsafe {
             ffi::nn_tensor_set_aux_object(self.ptr, ptr as *mut std::ffi::c_void, None);
         };
+        Ok(())
     }
 
     fn mapro_(&self) -> Result<*const ::std::os::raw::c_void, Error> {
@@ -227,6 +652,7 @@ impl Tensor {
     pub fn mapro<'a>(&'a self) -> Result<TensorData<'a>, Error> {
         let tensor_type = self.tensor_type();
         let size = self.size();
+        let volume = self.volume();
         match tensor_type {
             TensorType::RAW => {
                 let ptr = self.mapro_()? as *const u8;
@@ -247,7 +673,7 @@ impl Tensor {
             }
             TensorType::I8 => {
                 let ptr = self.mapro_()? as *const i8;
-                let sret = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
+                let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
                 return Ok(TensorData {
                     tensor: self,
                     data: MappedData::I8(sret),
@@ -255,7 +681,7 @@ impl Tensor {
             }
             TensorType::U8 => {
                 let ptr = self.mapro_()? as *const u8;
-                let sret = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
+                let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
                 return Ok(TensorData {
                     tensor: self,
                     data: MappedData::U8(sret),
@@ -263,7 +689,7 @@ impl Tensor {
             }
             TensorType::I16 => {
                 let ptr = self.mapro_()? as *const i16;
-                let sret = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
+                let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
                 return Ok(TensorData {
                     tensor: self,
                     data: MappedData::I16(sret),
@@ -271,7 +697,7 @@ impl Tensor {
             }
             TensorType::U16 => {
                 let ptr = self.mapro_()? as *const u16;
-                let sret = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
+                let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
                 return Ok(TensorData {
                     tensor: self,
                     data: MappedData::U16(sret),
@@ -279,7 +705,7 @@ impl Tensor {
             }
             TensorType::I32 => {
                 let ptr = self.mapro_()? as *const i32;
-                let sret = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
+                let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
                 return Ok(TensorData {
                     tensor: self,
                     data: MappedData::I32(sret),
@@ -287,7 +713,7 @@ impl Tensor {
             }
             TensorType::U32 => {
                 let ptr = self.mapro_()? as *const u32;
-                let sret = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
+                let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
                 return Ok(TensorData {
                     tensor: self,
                     data: MappedData::U32(sret),
@@ -295,7 +721,7 @@ impl Tensor {
             }
             TensorType::I64 => {
                 let ptr = self.mapro_()? as *const i64;
-                let sret = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
+                let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
                 return Ok(TensorData {
                     tensor: self,
                     data: MappedData::I64(sret),
@@ -303,7 +729,7 @@ impl Tensor {
             }
             TensorType::U64 => {
                 let ptr = self.mapro_()? as *const u64;
-                let sret = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
+                let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
                 return Ok(TensorData {
                     tensor: self,
                     data: MappedData::U64(sret),
@@ -314,12 +740,12 @@ impl Tensor {
                 let sret = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
                 return Ok(TensorData {
                     tensor: self,
-                    data: MappedData::RAW(sret),
+                    data: MappedData::F16(sret),
                 });
             }
             TensorType::F32 => {
                 let ptr = self.mapro_()? as *const f32;
-                let sret = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
+                let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
                 return Ok(TensorData {
                     tensor: self,
                     data: MappedData::F32(sret),
@@ -327,7 +753,7 @@ impl Tensor {
             }
             TensorType::F64 => {
                 let ptr = self.mapro_()? as *const f64;
-                let sret = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
+                let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
                 return Ok(TensorData {
                     tensor: self,
                     data: MappedData::F64(sret),
@@ -336,6 +762,189 @@ impl Tensor {
         }
     }
 
+    /// Maps the tensor for reading and writing.  The tensor is unmapped when
+    /// the returned guard is dropped.
+    pub fn maprw<'a>(&'a mut self) -> Result<TensorDataMut<'a>, Error> {
+        let ptr = unsafe { ffi::nn_tensor_maprw(self.ptr) };
+        if ptr.is_null() {
+            return Err(Error::WrapperError("nn_tensor_maprw failed".to_string()));
+        }
+        self.mapped_mut(ptr)
+    }
+
+    /// Maps the tensor for writing only, the previous contents of the tensor
+    /// are undefined.  The tensor is unmapped when the returned guard is
+    /// dropped.
+    pub fn mapwo<'a>(&'a mut self) -> Result<TensorDataMut<'a>, Error> {
+        let ptr = unsafe { ffi::nn_tensor_mapwo(self.ptr) };
+        if ptr.is_null() {
+            return Err(Error::WrapperError("nn_tensor_mapwo failed".to_string()));
+        }
+        self.mapped_mut(ptr)
+    }
+
+    fn mapped_mut<'a>(&'a self, ptr: *mut c_void) -> Result<TensorDataMut<'a>, Error> {
+        let size = self.size() as usize;
+        let volume = self.volume() as usize;
+        let data = unsafe {
+            match self.tensor_type() {
+                TensorType::RAW | TensorType::STR => {
+                    MappedDataMut::RAW(std::slice::from_raw_parts_mut(ptr as *mut u8, size))
+                }
+                TensorType::I8 => {
+                    MappedDataMut::I8(std::slice::from_raw_parts_mut(ptr as *mut i8, volume))
+                }
+                TensorType::U8 => {
+                    MappedDataMut::U8(std::slice::from_raw_parts_mut(ptr as *mut u8, volume))
+                }
+                TensorType::I16 => {
+                    MappedDataMut::I16(std::slice::from_raw_parts_mut(ptr as *mut i16, volume))
+                }
+                TensorType::U16 => {
+                    MappedDataMut::U16(std::slice::from_raw_parts_mut(ptr as *mut u16, volume))
+                }
+                TensorType::I32 => {
+                    MappedDataMut::I32(std::slice::from_raw_parts_mut(ptr as *mut i32, volume))
+                }
+                TensorType::U32 => {
+                    MappedDataMut::U32(std::slice::from_raw_parts_mut(ptr as *mut u32, volume))
+                }
+                TensorType::I64 => {
+                    MappedDataMut::I64(std::slice::from_raw_parts_mut(ptr as *mut i64, volume))
+                }
+                TensorType::U64 => {
+                    MappedDataMut::U64(std::slice::from_raw_parts_mut(ptr as *mut u64, volume))
+                }
+                TensorType::F16 => {
+                    MappedDataMut::F16(std::slice::from_raw_parts_mut(ptr as *mut u8, size))
+                }
+                TensorType::F32 => {
+                    MappedDataMut::F32(std::slice::from_raw_parts_mut(ptr as *mut f32, volume))
+                }
+                TensorType::F64 => {
+                    MappedDataMut::F64(std::slice::from_raw_parts_mut(ptr as *mut f64, volume))
+                }
+            }
+        };
+        Ok(TensorDataMut { tensor: self, data })
+    }
+
+    /// Reads the tensor as `f32` values, dequantizing integer data through
+    /// `quant`.  Per-channel parameters are applied along `quant.axis`.
+    pub fn to_f32_vec(&self, quant: &QuantParams) -> Result<Vec<f32>, Error> {
+        let dims = self.dims().clamp(0, 4) as usize;
+        let shape = &self.shape()[..dims];
+        let axis = quant.axis as usize;
+        let (channels, inner) = if quant.is_per_channel() && quant.axis >= 0 && axis < dims {
+            let inner: i32 = shape[axis + 1..].iter().product();
+            (shape[axis].max(1) as usize, inner.max(1) as usize)
+        } else {
+            (1, 1)
+        };
+        let channel = |i: usize| (i / inner) % channels;
+
+        fn convert<T: Element>(
+            data: &[T],
+            quant: &QuantParams,
+            channel: impl Fn(usize) -> usize,
+        ) -> Vec<f32> {
+            data.iter()
+                .enumerate()
+                .map(|(i, v)| quant.dequantize(v.to_f32(), channel(i)))
+                .collect()
+        }
+
+        let map = self.mapro()?;
+        let values = match &*map {
+            MappedData::I8(data) => convert(data, quant, channel),
+            MappedData::U8(data) => convert(data, quant, channel),
+            MappedData::I16(data) => convert(data, quant, channel),
+            MappedData::U16(data) => convert(data, quant, channel),
+            MappedData::I32(data) => convert(data, quant, channel),
+            MappedData::U32(data) => convert(data, quant, channel),
+            MappedData::I64(data) => convert(data, quant, channel),
+            MappedData::U64(data) => convert(data, quant, channel),
+            MappedData::F16(data) => data
+                .chunks_exact(2)
+                .map(|b| f16_to_f32(u16::from_ne_bytes([b[0], b[1]])))
+                .collect(),
+            MappedData::F32(data) => data.to_vec(),
+            MappedData::F64(data) => data.iter().map(|v| *v as f32).collect(),
+            MappedData::RAW(_) | MappedData::STR(_) => {
+                return Err(Error::WrapperError(String::from(
+                    "raw and string tensors have no numeric value",
+                )));
+            }
+        };
+        Ok(values)
+    }
+
+    /// Reads the tensor as `f32` values, dequantized through the tensor's own
+    /// quantization parameters.
+    pub fn dequantized(&self) -> Result<Vec<f32>, Error> {
+        self.to_f32_vec(&self.quant_params())
+    }
+
+    /// Compares this tensor against the `reference` tensor element by element.
+    /// Both tensors are dequantized through their own quantization parameters
+    /// so tensors of different types can be compared, their volumes must
+    /// agree.
+    pub fn compare(
+        &self,
+        reference: &Tensor,
+        tolerance: Tolerance,
+    ) -> Result<ComparisonReport, Error> {
+        if self.volume() != reference.volume() {
+            return Err(Error::WrapperError(format!(
+                "cannot compare tensors of volume {} and {}",
+                self.volume(),
+                reference.volume()
+            )));
+        }
+        let actual = self.dequantized()?;
+        let expected = reference.dequantized()?;
+
+        let mut max_abs_error = 0.0f32;
+        let mut sum_abs_error = 0.0f64;
+        let mut mismatches = 0;
+        let mut first_mismatch = None;
+        let (mut dot, mut norm_a, mut norm_b) = (0.0f64, 0.0f64, 0.0f64);
+        for (i, (a, b)) in actual.iter().zip(&expected).enumerate() {
+            let error = abs_error(*a, *b);
+            let matches = error == 0.0
+                || (error.is_finite() && error <= tolerance.abs + tolerance.rel * b.abs());
+            if !matches {
+                mismatches += 1;
+                if first_mismatch.is_none() {
+                    first_mismatch = Some(i);
+                }
+            }
+            max_abs_error = max_abs_error.max(error);
+            sum_abs_error += error as f64;
+            if a.is_finite() && b.is_finite() {
+                dot += *a as f64 * *b as f64;
+                norm_a += *a as f64 * *a as f64;
+                norm_b += *b as f64 * *b as f64;
+            }
+        }
+
+        let cosine_similarity = if norm_a == 0.0 && norm_b == 0.0 {
+            1.0
+        } else if norm_a == 0.0 || norm_b == 0.0 {
+            0.0
+        } else {
+            (dot / (norm_a.sqrt() * norm_b.sqrt())) as f32
+        };
+        let dims = self.dims().clamp(0, 4) as usize;
+        Ok(ComparisonReport {
+            max_abs_error,
+            mean_abs_error: (sum_abs_error / actual.len().max(1) as f64) as f32,
+            mismatches,
+            first_mismatch: first_mismatch.map(|i| unravel(i, &self.shape()[..dims])),
+            cosine_similarity,
+        })
+    }
+
     unsafe fn unmap(&self) {
         unsafe { ffi::nn_tensor_unmap(self.ptr) };
     }
@@ -350,6 +959,7 @@ impl Tensor {
             engine: Cell::new(None),
             ptr,
             scales: None,
+            zeros: None,
         });
     }
 
@@ -358,6 +968,155 @@ impl Tensor {
     }
 }
 
+/// Absolute difference of two values.  Equal values, including the same
+/// infinity, and pairs of NaN differ by zero, any other pair involving NaN or
+/// infinity differs by infinity.
+fn abs_error(a: f32, b: f32) -> f32 {
+    if a == b || (a.is_nan() && b.is_nan()) {
+        return 0.0;
+    }
+    let error = (a - b).abs();
+    if error.is_nan() {
+        f32::INFINITY
+    } else {
+        error
+    }
+}
+
+/// Per dimension, the number of leading and trailing entries shown when a
+/// tensor is summarized.
+const EDGE_ITEMS: usize = 3;
+
+/// Tensors with more elements than this are summarized.
+const SUMMARY_THRESHOLD: usize = 1000;
+
+/// Numpy style rendering of the tensor contents.
+struct Preview<'a>(&'a Tensor);
+
+impl<'a> Preview<'a> {
+    fn element(f: &mut fmt::Formatter<'_>, data: &MappedData, index: usize) -> fmt::Result {
+        match data {
+            MappedData::I8(d) => write!(f, "{}", d[index]),
+            MappedData::U8(d) => write!(f, "{}", d[index]),
+            MappedData::I16(d) => write!(f, "{}", d[index]),
+            MappedData::U16(d) => write!(f, "{}", d[index]),
+            MappedData::I32(d) => write!(f, "{}", d[index]),
+            MappedData::U32(d) => write!(f, "{}", d[index]),
+            MappedData::I64(d) => write!(f, "{}", d[index]),
+            MappedData::U64(d) => write!(f, "{}", d[index]),
+            MappedData::F16(d) => {
+                let bits = u16::from_ne_bytes([d[index * 2], d[index * 2 + 1]]);
+                write!(f, "{:?}", f16_to_f32(bits))
+            }
+            MappedData::F32(d) => write!(f, "{:?}", d[index]),
+            MappedData::F64(d) => write!(f, "{:?}", d[index]),
+            MappedData::RAW(d) => write!(f, "{:#04x}", d[index]),
+            MappedData::STR(_) => Ok(()),
+        }
+    }
+
+    fn nested(
+        f: &mut fmt::Formatter<'_>,
+        data: &MappedData,
+        shape: &[usize],
+        offset: usize,
+        depth: usize,
+        summarize: bool,
+    ) -> fmt::Result {
+        let Some((&n, inner_shape)) = shape.split_first() else {
+            return Self::element(f, data, offset);
+        };
+        let inner: usize = inner_shape.iter().product();
+        let indices: Vec<Option<usize>> = if summarize && n > 2 * EDGE_ITEMS {
+            (0..EDGE_ITEMS)
+                .map(Some)
+                .chain(std::iter::once(None))
+                .chain((n - EDGE_ITEMS..n).map(Some))
+                .collect()
+        } else {
+            (0..n).map(Some).collect()
+        };
+        write!(f, "[")?;
+        for (i, index) in indices.iter().enumerate() {
+            if i > 0 {
+                if inner_shape.is_empty() {
+                    write!(f, ", ")?;
+                } else {
+                    write!(f, ",\n{:width$}", "", width = depth + 1)?;
+                }
+            }
+            match index {
+                Some(index) => Self::nested(
+                    f,
+                    data,
+                    inner_shape,
+                    offset + index * inner,
+                    depth + 1,
+                    summarize,
+                )?,
+                None => write!(f, "...")?,
+            }
+        }
+        write!(f, "]")
+    }
+}
+
+impl<'a> fmt::Display for Preview<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let map = match self.0.mapro() {
+            Ok(map) => map,
+            Err(_) => return write!(f, "<unmapped>"),
+        };
+        let dims = self.0.dims().clamp(0, 4) as usize;
+        let shape: Vec<usize> = self.0.shape()[..dims]
+            .iter()
+            .map(|d| (*d).max(0) as usize)
+            .collect();
+        match &*map {
+            MappedData::STR(s) => write!(f, "{:?}", s),
+            MappedData::RAW(d) => Preview::nested(f, &map, &[d.len()], 0, 0, true),
+            data => {
+                let summarize = shape.iter().product::<usize>() > SUMMARY_THRESHOLD;
+                Preview::nested(f, data, &shape, 0, 0, summarize)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Tensor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dims = self.dims().clamp(0, 4) as usize;
+        let quant = self.quant_params();
+        let mut s = f.debug_struct("Tensor");
+        s.field("type", &self.tensor_type())
+            .field("shape", &&self.shape()[..dims])
+            .field("strides", &&self.strides()[..dims]);
+        if quant.is_quantized() {
+            s.field("scales", &quant.scales)
+                .field("zeros", &quant.zeros)
+                .field("axis", &quant.axis);
+        }
+        s.field("data", &format_args!("{}", Preview(self))).finish()
+    }
+}
+
+impl fmt::Display for Tensor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dims = self.dims().clamp(0, 4) as usize;
+        write!(f, "{}", Preview(self))?;
+        match TensorType::try_from(unsafe { ffi::nn_tensor_type(self.ptr) }) {
+            Ok(tensor_type) => write!(f, ", dtype={:?}", tensor_type)?,
+            Err(_) => write!(f, ", dtype=unknown")?,
+        }
+        write!(f, ", shape={:?}", &self.shape()[..dims])?;
+        let quant = self.quant_params();
+        if quant.is_quantized() {
+            write!(f, ", scales={:?}, zeros={:?}", quant.scales, quant.zeros)?;
+        }
+        Ok(())
+    }
+}
+
 impl Drop for Tensor {
     fn drop(&mut self) {
         if self.owned {
@@ -367,3 +1126,66 @@ impl Drop for Tensor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f16_values() {
+        for (bits, value) in [
+            (0x0000, 0.0),
+            (0x3c00, 1.0),
+            (0xc000, -2.0),
+            (0x3555, 0.333_251_95),
+            (0x7bff, 65504.0),
+            (0x0400, 6.103_515_6e-5),
+            (0x0001, 5.960_464_5e-8),
+            (0x03ff, 6.097_555e-5),
+            (0x7c00, f32::INFINITY),
+            (0xfc00, f32::NEG_INFINITY),
+        ] {
+            assert_eq!(f16_to_f32(bits), value, "{:#06x}", bits);
+            assert_eq!(f32_to_f16(value), bits, "{}", value);
+        }
+        assert_eq!(f32_to_f16(-0.0), 0x8000);
+        assert!(f16_to_f32(0x8000).is_sign_negative());
+    }
+
+    #[test]
+    fn f16_nan() {
+        assert!(f16_to_f32(0x7e00).is_nan());
+        assert!(f16_to_f32(0xfc01).is_nan());
+        let bits = f32_to_f16(f32::NAN);
+        assert_eq!(bits & 0x7c00, 0x7c00);
+        assert_ne!(bits & 0x3ff, 0);
+    }
+
+    #[test]
+    fn f16_rounding() {
+        // Ties round to even.
+        assert_eq!(f32_to_f16(1.0 + 2f32.powi(-11)), 0x3c00);
+        assert_eq!(f32_to_f16(1.0 + 3.0 * 2f32.powi(-11)), 0x3c02);
+        assert_eq!(f32_to_f16(1.0 + 2f32.powi(-11) + 2f32.powi(-20)), 0x3c01);
+        // Overflow rounds to infinity, underflow to zero.
+        assert_eq!(f32_to_f16(65519.0), 0x7bff);
+        assert_eq!(f32_to_f16(65520.0), 0x7c00);
+        assert_eq!(f32_to_f16(1e10), 0x7c00);
+        assert_eq!(f32_to_f16(2f32.powi(-25)), 0x0000);
+        assert_eq!(f32_to_f16(3.0 * 2f32.powi(-26)), 0x0001);
+        assert_eq!(f32_to_f16(-1e-10), 0x8000);
+        // The largest subnormal rounds up into the smallest normal.
+        assert_eq!(f32_to_f16(2f32.powi(-14) - 2f32.powi(-26)), 0x0400);
+    }
+
+    #[test]
+    fn f16_round_trip() {
+        for bits in 0..=u16::MAX {
+            let value = f16_to_f32(bits);
+            if value.is_nan() {
+                continue;
+            }
+            assert_eq!(f32_to_f16(value), bits, "{:#06x}", bits);
+        }
+    }
+}
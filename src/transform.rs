@@ -0,0 +1,185 @@
+/// How a source image is fitted to the height and width of an input tensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResizeMode {
+    /// Scale each axis independently to fill the tensor, ignoring aspect ratio.
+    #[default]
+    Stretch,
+    /// Scale preserving aspect ratio until the tensor is covered then crop the
+    /// overflowing edges equally on both sides.
+    CenterCrop,
+    /// Scale preserving aspect ratio until the image fits inside the tensor
+    /// then pad the remaining border equally on both sides.
+    Letterbox,
+}
+
+/// Affine mapping from source image coordinates to tensor coordinates, as
+/// produced by resizing an image into an input tensor.
+///
+/// A source point `(x, y)` lands at `(x * scale_x + offset_x, y * scale_y +
+/// offset_y)` in the tensor.  The offsets are positive for letterbox padding
+/// and negative for center-crop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub source_width: u32,
+    pub source_height: u32,
+}
+
+impl Transform {
+    /// Computes the transform fitting a `source_width` x `source_height` image
+    /// into a `width` x `height` tensor according to `mode`.
+    pub fn new(
+        mode: ResizeMode,
+        source_width: u32,
+        source_height: u32,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let sx = width as f32 / source_width as f32;
+        let sy = height as f32 / source_height as f32;
+        let (scale_x, scale_y) = match mode {
+            ResizeMode::Stretch => (sx, sy),
+            ResizeMode::CenterCrop => (sx.max(sy), sx.max(sy)),
+            ResizeMode::Letterbox => (sx.min(sy), sx.min(sy)),
+        };
+        let (resized_width, resized_height) = match mode {
+            ResizeMode::Stretch => (width, height),
+            _ => (
+                (source_width as f32 * scale_x).round().max(1.0) as u32,
+                (source_height as f32 * scale_y).round().max(1.0) as u32,
+            ),
+        };
+        Transform {
+            scale_x,
+            scale_y,
+            offset_x: ((width as i64 - resized_width as i64) / 2) as f32,
+            offset_y: ((height as i64 - resized_height as i64) / 2) as f32,
+            source_width,
+            source_height,
+        }
+    }
+
+    /// The identity transform for a tensor of the same size as the source.
+    pub fn identity(width: u32, height: u32) -> Self {
+        Transform {
+            scale_x: 1.0,
+            scale_y: 1.0,
+            offset_x: 0.0,
+            offset_y: 0.0,
+            source_width: width,
+            source_height: height,
+        }
+    }
+
+    /// Size of the source image once scaled, before padding or cropping.
+    pub fn resized_size(&self) -> (u32, u32) {
+        (
+            (self.source_width as f32 * self.scale_x).round().max(1.0) as u32,
+            (self.source_height as f32 * self.scale_y).round().max(1.0) as u32,
+        )
+    }
+
+    /// Maps a source image point into tensor coordinates.
+    pub fn to_tensor(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            x * self.scale_x + self.offset_x,
+            y * self.scale_y + self.offset_y,
+        )
+    }
+
+    /// Maps a tensor point back into source image coordinates.
+    pub fn to_source(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.offset_x) / self.scale_x,
+            (y - self.offset_y) / self.scale_y,
+        )
+    }
+
+    /// Maps an `[xmin, ymin, xmax, ymax]` box in tensor coordinates back into
+    /// source image coordinates, clamped to the source image bounds.
+    pub fn box_to_source(&self, bbox: [f32; 4]) -> [f32; 4] {
+        let (xmin, ymin) = self.to_source(bbox[0], bbox[1]);
+        let (xmax, ymax) = self.to_source(bbox[2], bbox[3]);
+        let w = self.source_width as f32;
+        let h = self.source_height as f32;
+        [
+            xmin.clamp(0.0, w),
+            ymin.clamp(0.0, h),
+            xmax.clamp(0.0, w),
+            ymax.clamp(0.0, h),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letterbox_offsets() {
+        let t = Transform::new(ResizeMode::Letterbox, 100, 50, 64, 64);
+        assert_eq!((t.scale_x, t.scale_y), (0.64, 0.64));
+        assert_eq!(t.resized_size(), (64, 32));
+        assert_eq!((t.offset_x, t.offset_y), (0.0, 16.0));
+
+        // 51 rows scale to 33, leaving 31 rows split 15 above and 16 below.
+        let t = Transform::new(ResizeMode::Letterbox, 100, 51, 64, 64);
+        assert_eq!(t.resized_size(), (64, 33));
+        assert_eq!((t.offset_x, t.offset_y), (0.0, 15.0));
+    }
+
+    #[test]
+    fn center_crop_offsets() {
+        let t = Transform::new(ResizeMode::CenterCrop, 100, 50, 64, 64);
+        assert_eq!(t.resized_size(), (128, 64));
+        assert_eq!((t.offset_x, t.offset_y), (-32.0, 0.0));
+
+        // 126 columns cropped to 63 drop 31 on the left and 32 on the right.
+        let t = Transform::new(ResizeMode::CenterCrop, 100, 50, 63, 63);
+        assert_eq!(t.resized_size(), (126, 63));
+        assert_eq!((t.offset_x, t.offset_y), (-31.0, 0.0));
+    }
+
+    #[test]
+    fn stretch() {
+        let t = Transform::new(ResizeMode::Stretch, 640, 480, 320, 320);
+        assert_eq!((t.scale_x, t.scale_y), (0.5, 320.0 / 480.0));
+        assert_eq!((t.offset_x, t.offset_y), (0.0, 0.0));
+        assert_eq!(
+            Transform::new(ResizeMode::Stretch, 8, 8, 8, 8),
+            Transform::identity(8, 8)
+        );
+    }
+
+    #[test]
+    fn to_source_round_trip() {
+        for mode in [
+            ResizeMode::Stretch,
+            ResizeMode::CenterCrop,
+            ResizeMode::Letterbox,
+        ] {
+            let t = Transform::new(mode, 641, 479, 320, 320);
+            for (x, y) in [(0.0, 0.0), (320.5, 17.25), (640.0, 478.0)] {
+                let (tx, ty) = t.to_tensor(x, y);
+                let (sx, sy) = t.to_source(tx, ty);
+                assert!((sx - x).abs() < 1e-3 && (sy - y).abs() < 1e-3, "{:?}", mode);
+            }
+        }
+    }
+
+    #[test]
+    fn box_to_source_clamps() {
+        let t = Transform::new(ResizeMode::Letterbox, 100, 50, 64, 64);
+        assert_eq!(
+            t.box_to_source([0.0, 16.0, 64.0, 48.0]),
+            [0.0, 0.0, 100.0, 50.0]
+        );
+        assert_eq!(
+            t.box_to_source([-8.0, 0.0, 72.0, 64.0]),
+            [0.0, 0.0, 100.0, 50.0]
+        );
+    }
+}
@@ -0,0 +1,585 @@
+//! Runs the wrapper against the deepviewrt-mock implementation of the runtime.
+
+use deepviewrt::{
+    context::Context,
+    divergence::find_divergence,
+    engine::{Engine, EngineChoice, EnginePolicy, ENGINE_PATH_VAR},
+    model::{Model, ModelInfo},
+    npy::Array,
+    preprocess::{Frame, PixelFormat, Preprocessor, Roi},
+    profile::Benchmark,
+    runtime::{self, Capabilities, RuntimeVersion},
+    tensor::{MappedDataMut, QuantParams, Tensor, TensorType, Tolerance},
+};
+use deepviewrt_mock::{Layer, ModelBuilder};
+use deepviewrt_sys::NNError;
+
+fn classifier() -> Vec<u8> {
+    ModelBuilder::new("classifier")
+        .uuid("6f1c2a9e-0000-4000-8000-000000000000")
+        .serial(3)
+        .label("cat")
+        .label("dog")
+        .layer(Layer::input("input", "f32", &[1, 2]))
+        .layer(Layer::constant("weights", "f32", &[1, 2], &[1.0, 2.0]))
+        .layer(Layer::new("scaled", "mul", "f32", &[1, 2]).inputs(&["input", "weights"]))
+        .layer(Layer::new("output", "softmax", "f32", &[1, 2]).inputs(&["scaled"]))
+        .resource("notes", "text/plain", b"hi", "greeting")
+        .build()
+}
+
+fn set_input(context: &mut Context, values: &[f32]) {
+    let tensor = context.tensor_index_mut(0).unwrap();
+    let mut map = tensor.maprw().unwrap();
+    match &mut *map {
+        MappedDataMut::F32(data) => data.copy_from_slice(values),
+        _ => panic!("input is not f32"),
+    }
+}
+
+#[test]
+fn model_metadata() {
+    let data = classifier();
+    Model::validate(&data).unwrap();
+    let context = Context::with_model(None, data).unwrap();
+    let model = context.model().unwrap();
+
+    assert_eq!(model.name().unwrap(), "classifier");
+    assert_eq!(model.label_count().unwrap(), 2);
+    assert_eq!(model.label(1).unwrap(), "dog");
+    assert_eq!(model.layer_count(), 4);
+    assert_eq!(model.inputs().unwrap(), &[0]);
+    assert_eq!(model.outputs().unwrap(), &[3]);
+    assert_eq!(model.layer_lookup("scaled").unwrap(), 2);
+    assert_eq!(model.layer_type(3).unwrap(), "softmax");
+    assert_eq!(model.layer_shape(1).unwrap(), &[1, 2]);
+    assert_eq!(model.layer_datatype_id(0).unwrap(), TensorType::F32);
+
+    let resource = model.resource("notes").unwrap();
+    assert_eq!(resource.mime(), Some("text/plain"));
+    assert_eq!(resource.data(), Some(&b"hi"[..]));
+}
+
+#[test]
+fn model_info() {
+    let info = ModelInfo::from_bytes(&classifier()).unwrap();
+    assert_eq!(info.serial, 3);
+    assert_eq!(info.labels, vec!["cat", "dog"]);
+    assert_eq!(info.outputs, vec![3]);
+    assert_eq!(info.layers[2].layer_type, "mul");
+    assert_eq!(info.layers[2].quant.axis, -1);
+    assert_eq!(info.resources[0].size, 2);
+    assert_eq!(info.memory_size, 32);
+}
+
+#[test]
+fn invalid_model() {
+    assert!(Model::validate(b"not a model").is_err());
+    assert!(Context::with_model(None, b"not a model".to_vec()).is_err());
+
+    // The header records the model size, trailing or missing bytes are
+    // rejected rather than scanned past.
+    let mut data = classifier();
+    assert!(Model::validate(&data[..data.len() - 1]).is_err());
+    data.extend(b"# trailing\n");
+    assert!(Model::validate(&data).is_err());
+}
+
+#[test]
+fn run_model() {
+    let mut context = Context::with_model(None, classifier()).unwrap();
+    set_input(&mut context, &[1.0, 1.0]);
+    context.run_model().unwrap();
+
+    let output = context.tensor("output").unwrap().dequantized().unwrap();
+    let expected = 1.0 / (1.0 + 1f32.exp());
+    assert!((output[0] - expected).abs() < 1e-6);
+    assert!((output[0] + output[1] - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn benchmark() {
+    let mut context = Context::with_model(None, classifier()).unwrap();
+    set_input(&mut context, &[1.0, 1.0]);
+    let benchmark = Benchmark {
+        warmup: 2,
+        iterations: 5,
+        profile: true,
+    };
+    let report = benchmark.run(&context).unwrap();
+
+    assert_eq!(report.iterations, 5);
+    assert!(report.total.min <= report.total.p50);
+    assert!(report.total.p50 <= report.total.max);
+    assert!(report.throughput > 0.0);
+    let layers: Vec<&str> = report.layers.iter().map(|l| l.name.as_str()).collect();
+    assert_eq!(layers, ["input", "weights", "scaled", "output"]);
+
+    let output = context.tensor("output").unwrap().dequantized().unwrap();
+    assert!((output[0] + output[1] - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn contexts_per_thread() {
+    let data = classifier();
+    let reference = Context::with_model(None, data.clone()).unwrap();
+    let model = reference.model().unwrap();
+    let outputs: Vec<Vec<f32>> = std::thread::scope(|scope| {
+        let threads: Vec<_> = (0..4)
+            .map(|n| {
+                let data = &data;
+                scope.spawn(move || {
+                    assert_eq!(model.name().unwrap(), "classifier");
+                    let mut context = Context::with_model(None, data.clone()).unwrap();
+                    set_input(&mut context, &[n as f32, 1.0]);
+                    context.run_model().unwrap();
+                    context.tensor("output").unwrap().dequantized().unwrap()
+                })
+            })
+            .collect();
+        threads.into_iter().map(|t| t.join().unwrap()).collect()
+    });
+
+    for (n, output) in outputs.iter().enumerate() {
+        let expected = 1.0 / (1.0 + (2.0 - n as f32).exp());
+        assert!((output[0] - expected).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn quantized_layer() {
+    let data = ModelBuilder::new("quantized")
+        .layer(Layer::input("input", "f32", &[4]))
+        .layer(
+            Layer::new("quantized", "quantize", "i8", &[4])
+                .inputs(&["input"])
+                .quantization(&[0.5], &[1], -1),
+        )
+        .build();
+    let mut context = Context::with_model(None, data).unwrap();
+    set_input(&mut context, &[-1.0, 0.0, 1.0, 100.0]);
+    context.run_model().unwrap();
+
+    let output = context.tensor("quantized").unwrap();
+    assert_eq!(output.tensor_type(), TensorType::I8);
+    assert_eq!(output.scales().unwrap(), &[0.5]);
+    assert_eq!(output.axis().unwrap(), -1);
+    assert!(output.io_time().unwrap() >= 0);
+    assert_eq!(output.dequantized().unwrap(), vec![-1.0, 0.0, 1.0, 63.0]);
+
+    let info = output.info();
+    assert_eq!(info.datatype, TensorType::I8);
+    assert_eq!(info.shape, vec![4]);
+    assert_eq!(info.quant.zeros, vec![1]);
+}
+
+#[test]
+fn tensor_fill_and_dequantize() {
+    let mut source = Tensor::with_shape(TensorType::U8, &[2, 3]).unwrap();
+    source
+        .set_quant_params(&QuantParams {
+            scales: vec![0.25],
+            zeros: vec![0],
+            axis: -1,
+        })
+        .unwrap();
+    source.fill(2.0).unwrap();
+    assert_eq!(source.volume(), 6);
+    assert_eq!(&source.strides()[..2], &[3, 1]);
+
+    let mut dest = Tensor::with_shape(TensorType::F32, &[2, 3]).unwrap();
+    source.dequantize(&mut dest).unwrap();
+    assert_eq!(dest.dequantized().unwrap(), vec![2.0; 6]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let info = ModelInfo::from_bytes(&classifier()).unwrap();
+    let json = serde_json::to_string(&info).unwrap();
+    assert_eq!(serde_json::from_str::<ModelInfo>(&json).unwrap(), info);
+
+    let tensor = Tensor::with_shape(TensorType::F32, &[1, 2]).unwrap();
+    let json = serde_json::to_value(tensor.info()).unwrap();
+    assert_eq!(json["datatype"], "f32");
+    assert_eq!(json["shape"], serde_json::json!([1, 2]));
+}
+
+#[test]
+fn preprocess_roi_overflow() {
+    let data = [0u8; 4 * 4 * 3];
+    let frame = Frame::new(&data, PixelFormat::Rgb, 4, 4);
+    let mut tensor = Tensor::with_shape(TensorType::F32, &[1, 2, 2, 3]).unwrap();
+    let preprocessor = Preprocessor {
+        roi: Some(Roi {
+            x: u32::MAX,
+            y: 0,
+            width: 2,
+            height: 2,
+        }),
+        ..Preprocessor::default()
+    };
+    assert!(preprocessor.apply(&frame, &mut tensor).is_err());
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn preprocess_matches_write_image() {
+    let pixels: Vec<u8> = (0..2 * 2 * 3).map(|i| (i * 23) as u8).collect();
+    let image =
+        image::DynamicImage::ImageRgb8(image::RgbImage::from_raw(2, 2, pixels.clone()).unwrap());
+    let frame = Frame::new(&pixels, PixelFormat::Rgb, 2, 2);
+    let quantized = QuantParams {
+        scales: vec![1.0 / 255.0],
+        zeros: vec![-128],
+        axis: -1,
+    };
+    for (tensor_type, quant) in [
+        (TensorType::F32, None),
+        (TensorType::U8, None),
+        (TensorType::I8, None),
+        (TensorType::I8, Some(&quantized)),
+    ] {
+        let mut written = Tensor::with_shape(tensor_type, &[1, 2, 2, 3]).unwrap();
+        let mut preprocessed = Tensor::with_shape(tensor_type, &[1, 2, 2, 3]).unwrap();
+        if let Some(quant) = quant {
+            written.set_quant_params(quant).unwrap();
+            preprocessed.set_quant_params(quant).unwrap();
+        }
+        written
+            .write_image(&image, deepviewrt::transform::ResizeMode::Stretch)
+            .unwrap();
+        Preprocessor::default()
+            .apply(&frame, &mut preprocessed)
+            .unwrap();
+        assert_eq!(
+            written.mapro().unwrap().as_bytes(),
+            preprocessed.mapro().unwrap().as_bytes(),
+            "{:?}",
+            tensor_type
+        );
+    }
+}
+
+fn f32_tensor(values: &[f32]) -> Tensor {
+    let mut tensor = Tensor::with_shape(TensorType::F32, &[values.len() as i32]).unwrap();
+    match &mut *tensor.maprw().unwrap() {
+        MappedDataMut::F32(data) => data.copy_from_slice(values),
+        _ => panic!("tensor is not f32"),
+    }
+    tensor
+}
+
+#[test]
+fn compare_non_finite() {
+    let values = [1.0, f32::INFINITY, f32::NEG_INFINITY, f32::NAN];
+    let report = f32_tensor(&values)
+        .compare(&f32_tensor(&values), Tolerance::default())
+        .unwrap();
+    assert!(report.is_match());
+    assert_eq!(report.max_abs_error, 0.0);
+    assert_eq!(report.mean_abs_error, 0.0);
+    assert_eq!(report.cosine_similarity, 1.0);
+
+    let report = f32_tensor(&[1.0, 2.0, f32::NEG_INFINITY, 0.0])
+        .compare(&f32_tensor(&values), Tolerance::default())
+        .unwrap();
+    assert_eq!(report.mismatches, 2);
+    assert_eq!(report.first_mismatch, Some(vec![1]));
+    assert_eq!(report.max_abs_error, f32::INFINITY);
+    assert_eq!(report.mean_abs_error, f32::INFINITY);
+}
+
+#[test]
+fn npy_copy_into_quantized() {
+    let mut tensor = Tensor::with_shape(TensorType::I8, &[2]).unwrap();
+    tensor
+        .set_quant_params(&QuantParams {
+            scales: vec![0.5],
+            zeros: vec![0],
+            axis: -1,
+        })
+        .unwrap();
+    let floats = Array {
+        dtype: TensorType::F32,
+        shape: vec![2],
+        data: [1.0f32, -2.0]
+            .iter()
+            .flat_map(|v| v.to_ne_bytes())
+            .collect(),
+    };
+    floats.copy_into(&mut tensor).unwrap();
+    assert_eq!(tensor.dequantized().unwrap(), vec![1.0, -2.0]);
+
+    let integers = Array {
+        dtype: TensorType::I32,
+        shape: vec![2],
+        data: [1i32, 2].iter().flat_map(|v| v.to_ne_bytes()).collect(),
+    };
+    assert!(integers.copy_into(&mut tensor).is_err());
+
+    let mut unquantized = Tensor::with_shape(TensorType::I8, &[2]).unwrap();
+    integers.copy_into(&mut unquantized).unwrap();
+    assert_eq!(unquantized.dequantized().unwrap(), vec![1.0, 2.0]);
+}
+
+#[cfg(feature = "safetensors")]
+#[test]
+fn safetensors_round_trip() {
+    use deepviewrt::safetensors::{save_tensors, SafetensorsFile};
+
+    let mut quantized = Tensor::with_shape(TensorType::I8, &[2]).unwrap();
+    let quant = QuantParams {
+        scales: vec![0.5],
+        zeros: vec![1],
+        axis: -1,
+    };
+    quantized.set_quant_params(&quant).unwrap();
+    quantized.fill(3.0).unwrap();
+    let floats = f32_tensor(&[1.5, -2.0, 4.0]);
+    let path = std::env::temp_dir().join(format!("deepviewrt-{}.safetensors", std::process::id()));
+    save_tensors(&[("q", &quantized), ("f", &floats)], &path).unwrap();
+
+    let file = SafetensorsFile::open(&path).unwrap();
+    let q = file.tensor("q").unwrap();
+    assert_eq!(q.quant_params(), quant);
+    assert_eq!(q.mapro().unwrap().as_bytes(), [7, 7]);
+
+    let mut f = Tensor::with_shape(TensorType::F32, &[3]).unwrap();
+    file.copy_into("f", &mut f).unwrap();
+    assert_eq!(f.dequantized().unwrap(), vec![1.5, -2.0, 4.0]);
+    let mut small = Tensor::with_shape(TensorType::F32, &[2]).unwrap();
+    assert!(file.copy_into("f", &mut small).is_err());
+    drop(file);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn record_and_replay() {
+    use deepviewrt::record::{Recorder, Replayer};
+
+    let dir = std::env::temp_dir().join(format!("deepviewrt-record-{}", std::process::id()));
+    let mut context = Context::with_model(None, classifier()).unwrap();
+    set_input(&mut context, &[1.0, 1.0]);
+    let mut recorder = Recorder::new(&dir).unwrap().with_intermediates(true);
+    recorder.run(&context).unwrap();
+
+    let run = &recorder.manifest().runs[0];
+    assert_eq!(run.inputs.len(), 1);
+    assert_eq!(run.outputs[0].name, "output");
+    let names: Vec<&str> = run.intermediates.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["weights", "scaled"]);
+
+    let replayer = Replayer::open(&dir).unwrap();
+    let diffs = replayer.replay(&mut context, Tolerance::default()).unwrap();
+    assert_eq!(diffs.len(), 1);
+    assert_eq!(diffs[0].layers.len(), 3);
+    assert!(diffs[0].is_match());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn borrowed_engine() {
+    let path = std::env::temp_dir().join(format!("deepview-rt-mock{}.so", std::process::id()));
+    std::fs::write(&path, b"").unwrap();
+    let engine = Engine::new(&path).unwrap();
+    assert!(engine.native_handle::<u8>().unwrap().is_some());
+
+    let borrowed = Engine::wrap(unsafe { engine.to_ptr_mut() }).unwrap();
+    assert!(borrowed.unload().is_err());
+    assert!(borrowed.load(&path).is_err());
+    assert!(borrowed.reload().is_err());
+    drop(borrowed);
+    assert!(engine.name().is_some());
+
+    engine.unload().unwrap();
+    assert!(engine.name().is_none());
+    assert!(engine.native_handle::<u8>().unwrap().is_none());
+    engine.reload().unwrap();
+    assert!(engine.name().is_some());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn runtime_version() {
+    let version: RuntimeVersion = deepviewrt::version().unwrap().parse().unwrap();
+    assert_eq!(runtime::runtime_version().unwrap(), version);
+    assert!(Context::sizeof().unwrap() > 0);
+
+    let capabilities = Capabilities::for_version(RuntimeVersion::new(2, 0, 0));
+    assert!(capabilities.engine_handle);
+    assert!(!capabilities.io_time && !capabilities.quantization);
+    assert!(!Capabilities::get().user_ops);
+}
+
+#[test]
+fn borrowed_views_stay_valid() {
+    let context = Context::with_model(None, classifier()).unwrap();
+    let model = context.model().unwrap();
+    let input = context.tensor_index(0).unwrap();
+    std::thread::scope(|scope| {
+        let name = scope.spawn(|| model.name().unwrap().to_string());
+        for index in 0..64 {
+            assert_eq!(context.model().unwrap().layer_count(), 4);
+            context.tensor_index(index % 4).unwrap();
+        }
+        assert_eq!(name.join().unwrap(), "classifier");
+    });
+    assert!(std::ptr::eq(model, context.model().unwrap()));
+    assert_eq!(input.volume(), 2);
+}
+
+#[test]
+fn layer_quant_params() {
+    let data = ModelBuilder::new("per-channel")
+        .layer(Layer::input("input", "f32", &[1, 2]))
+        .layer(
+            Layer::new("quantized", "quantize", "i8", &[1, 2])
+                .inputs(&["input"])
+                .quantization(&[0.5, 0.25], &[0, 1], 0),
+        )
+        .build();
+    let context = Context::with_model(None, data).unwrap();
+    let model = context.model().unwrap();
+
+    let unquantized = model.layer_quant_params(0);
+    assert!(!unquantized.is_quantized());
+    assert_eq!(unquantized.axis, -1);
+    assert_eq!(model.layer_axis(0).unwrap(), -1);
+
+    let quant = model.layer_quant_params(1);
+    assert_eq!(quant.scales, vec![0.5, 0.25]);
+    assert_eq!(quant.zeros, vec![0, 1]);
+    assert_eq!(quant.axis, 0);
+    assert!(model.layer_axis(2).is_err());
+}
+
+#[test]
+fn policy_falls_back_on_missing_kernel() {
+    let path = std::env::temp_dir().join(format!("deepview-rt-nosoftmax{}.so", std::process::id()));
+    std::fs::write(&path, "softmax\n").unwrap();
+    let policy = EnginePolicy::new().prefer(EngineChoice::Path(path.clone()));
+
+    let (mut context, selection) = Context::with_policy(&policy, classifier()).unwrap();
+    assert_eq!(selection.choice, EngineChoice::Cpu);
+    assert_eq!(selection.rejected.len(), 1);
+    assert_eq!(
+        selection.rejected[0].choice,
+        EngineChoice::Path(path.clone())
+    );
+    let error = &selection.rejected[0].error;
+    assert!(error.is_nn_error(NNError::NN_ERROR_KERNEL_MISSING));
+    assert!(!error.is_nn_error(NNError::NN_ERROR_INVALID_ENGINE));
+    assert!(context.engine().is_none());
+    set_input(&mut context, &[1.0, 1.0]);
+    context.run_model().unwrap();
+
+    let strict = policy.cpu_fallback(false);
+    assert!(Context::with_policy(&strict, classifier()).is_err());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn formatting() {
+    let mut context = Context::with_model(None, classifier()).unwrap();
+    set_input(&mut context, &[1.0, 2.0]);
+    assert_eq!(
+        format!("{:?}", context),
+        "Context { owned: true, engine: None, model: Some(\"classifier\"), mempool: Some(32), \
+         cache: Some(8) }"
+    );
+    assert_eq!(
+        context.to_string(),
+        "engine: cpu\nmodel: classifier\nmemory: 32 bytes, cache: 8 bytes"
+    );
+
+    let model = context.model().unwrap();
+    assert_eq!(
+        format!("{:?}", model),
+        "Model { name: Some(\"classifier\"), layers: 4, labels: 2, inputs: [0: input f32 [1, 2]], \
+         outputs: [3: output f32 [1, 2]] }"
+    );
+    assert_eq!(
+        model.to_string(),
+        "classifier (4 layers)\ninputs:\n  0: input f32 [1, 2]\noutputs:\n  3: output f32 [1, 2]"
+    );
+
+    let tensor = context.tensor_index(0).unwrap();
+    assert_eq!(
+        format!("{:?}", tensor),
+        "Tensor { type: F32, shape: [1, 2], strides: [2, 1], data: [[1.0, 2.0]] }"
+    );
+    assert_eq!(tensor.to_string(), "[[1.0, 2.0]], dtype=F32, shape=[1, 2]");
+
+    let mut quantized = Tensor::with_shape(TensorType::I8, &[3]).unwrap();
+    quantized
+        .set_quant_params(&QuantParams {
+            scales: vec![0.5],
+            zeros: vec![1],
+            axis: -1,
+        })
+        .unwrap();
+    assert_eq!(
+        format!("{:?}", quantized),
+        "Tensor { type: I8, shape: [3], strides: [1], scales: [0.5], zeros: [1], axis: -1, \
+         data: [0, 0, 0] }"
+    );
+    assert_eq!(
+        quantized.to_string(),
+        "[0, 0, 0], dtype=I8, shape=[3], scales=[0.5], zeros=[1]"
+    );
+}
+
+#[test]
+fn divergence_same_model() {
+    let mut reference = Context::with_model(None, classifier()).unwrap();
+    let mut candidate = Context::with_model(None, classifier()).unwrap();
+    set_input(&mut reference, &[1.0, 3.0]);
+
+    let report = find_divergence(&mut reference, &mut candidate, Tolerance::default()).unwrap();
+    assert_eq!(
+        candidate.tensor_index(0).unwrap().dequantized().unwrap(),
+        [1.0, 3.0]
+    );
+    assert_eq!(report.layers.len(), 4);
+    assert!(report.first_divergence().is_none());
+
+    let other = ModelBuilder::new("classifier")
+        .layer(Layer::input("input", "f32", &[1, 2]))
+        .layer(Layer::constant("weights", "f32", &[1, 2], &[1.0, 2.0]))
+        .layer(Layer::new("scaled", "add", "f32", &[1, 2]).inputs(&["input", "weights"]))
+        .layer(Layer::new("output", "softmax", "f32", &[1, 2]).inputs(&["scaled"]))
+        .build();
+    let mut candidate = Context::with_model(None, other).unwrap();
+    let report = find_divergence(&mut reference, &mut candidate, Tolerance::default()).unwrap();
+    assert_eq!(report.first_divergence().unwrap().name, "scaled");
+}
+
+#[test]
+fn discover_engines() {
+    let dir = std::env::temp_dir().join(format!("deepviewrt-engines-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("deepview-rt-foo.so");
+    std::fs::write(&path, "").unwrap();
+    std::fs::write(dir.join("libunrelated.so"), "").unwrap();
+    std::env::set_var(ENGINE_PATH_VAR, &dir);
+
+    let found: Vec<_> = Engine::discover()
+        .into_iter()
+        .filter(|info| info.path.starts_with(&dir))
+        .collect();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name, "foo");
+    assert_eq!(found[0].path, path);
+
+    let engine = Engine::by_name("FOO").unwrap();
+    assert_eq!(engine.name().unwrap(), "foo");
+    match Engine::by_name("nosuchengine") {
+        Ok(_) => panic!("found an engine that does not exist"),
+        Err(error) => assert!(error.to_string().contains(ENGINE_PATH_VAR)),
+    }
+
+    std::env::remove_var(ENGINE_PATH_VAR);
+    std::fs::remove_dir_all(dir).unwrap();
+}